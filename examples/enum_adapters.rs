@@ -1,11 +1,17 @@
 extern crate dxgi;
+#[cfg(windows)]
 extern crate windows_dpi;
 
+#[cfg(windows)]
 use dxgi::adapter::{IAdapter, IAdapter1};
+#[cfg(windows)]
 use dxgi::enums::Format;
+#[cfg(windows)]
 use dxgi::factory::{Factory1, IFactory1};
+#[cfg(windows)]
 use dxgi::output::IOutput;
 
+#[cfg(windows)]
 fn main() {
     windows_dpi::enable_dpi();
 
@@ -47,3 +53,8 @@ fn main() {
         }
     }
 }
+
+#[cfg(not(windows))]
+fn main() {
+    eprintln!("This example only runs on Windows.");
+}
@@ -1,7 +1,11 @@
+#[cfg(windows)]
 use dxgi::adapter::{Adapter4, IAdapter4};
+#[cfg(windows)]
 use dxgi::enums::GpuPreference;
+#[cfg(windows)]
 use dxgi::factory::{Factory6, IFactory6};
 
+#[cfg(windows)]
 fn main() {
     let factory: Factory6 = match dxgi::factory::create() {
         Ok(factory) => factory,
@@ -35,3 +39,8 @@ fn main() {
             .collect::<Vec<_>>()
     );
 }
+
+#[cfg(not(windows))]
+fn main() {
+    eprintln!("This example only runs on Windows.");
+}
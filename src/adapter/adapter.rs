@@ -1,5 +1,6 @@
 use crate::adapter::AdapterType;
 use crate::descriptions::AdapterDesc;
+use crate::driver::DriverVersion;
 use crate::factory::Factory;
 use crate::factory::FactoryType;
 use crate::output::Output;
@@ -69,6 +70,13 @@ pub unsafe trait IAdapter {
         }
     }
 
+    /// Like `check_interface_support`, with the user-mode driver version
+    /// split into its parts.
+    fn driver_version(&self, guid: &GUID) -> Option<DriverVersion> {
+        self.check_interface_support(guid)
+            .map(DriverVersion::from_raw)
+    }
+
     /// Create an iterator that enumerates over the outputs associated with
     /// this adapter.
     fn outputs(&self) -> OutputIter {
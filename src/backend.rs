@@ -0,0 +1,198 @@
+//! A safe trait layer over adapter and output enumeration.
+//!
+//! Code that picks adapters or display modes can be written against
+//! [`FactoryBackend`], [`AdapterBackend`] and [`OutputBackend`] instead of the
+//! COM interfaces. On Windows the traits are implemented by [`Factory1`][1],
+//! [`Adapter1`][2] and [`Output`][3]; on every target the [`fake`] module
+//! provides an in-memory implementation configured from plain descriptions,
+//! so that selection code can be unit tested without a GPU.
+//!
+//! [1]: ../factory/struct.Factory1.html
+//! [2]: ../adapter/struct.Adapter1.html
+//! [3]: ../output/struct.Output.html
+
+use crate::descriptions::{AdapterDesc1, AdapterDesc2, AdapterDesc3, Mode, OutputDesc};
+use crate::enums::{Format, GpuPreference};
+use crate::error::DxgiError;
+
+use std::fmt;
+
+pub mod fake;
+
+#[cfg(windows)]
+mod com;
+
+/// A raw `HRESULT` returned from a failed backend call.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct HResult(pub i32);
+
+impl HResult {
+    pub const FAIL: HResult = HResult(0x8000_4005u32 as i32);
+    pub const INVALID_CALL: HResult = HResult(0x887A_0001u32 as i32);
+    pub const NOT_FOUND: HResult = HResult(0x887A_0002u32 as i32);
+    pub const MORE_DATA: HResult = HResult(0x887A_0003u32 as i32);
+    pub const UNSUPPORTED: HResult = HResult(0x887A_0004u32 as i32);
+    pub const DEVICE_REMOVED: HResult = HResult(0x887A_0005u32 as i32);
+    pub const NOT_CURRENTLY_AVAILABLE: HResult = HResult(0x887A_0022u32 as i32);
+
+    /// The typed error for this code.
+    pub fn error(self) -> DxgiError {
+        DxgiError::from(self)
+    }
+
+    fn name(self) -> Option<&'static str> {
+        match self {
+            HResult::FAIL => Some("E_FAIL"),
+            hr => hr.error().name(),
+        }
+    }
+}
+
+impl fmt::Debug for HResult {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "HResult(0x{:08X})", self.0 as u32)
+    }
+}
+
+impl fmt::Display for HResult {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self.name() {
+            Some(name) => fmt.write_str(name),
+            None => write!(fmt, "0x{:08X}", self.0 as u32),
+        }
+    }
+}
+
+impl std::error::Error for HResult {}
+
+/// Adapter enumeration, as provided by a DXGI factory.
+pub trait FactoryBackend {
+    type Adapter: AdapterBackend;
+
+    /// Attempt to get the Nth adapter. Returns `None` once `n` is past the
+    /// last adapter.
+    fn enum_adapter(&self, n: u32) -> Option<Result<Self::Adapter, HResult>>;
+
+    /// Attempt to get the Nth adapter, sorted by the given preference.
+    fn enum_adapter_by_preference(
+        &self,
+        n: u32,
+        preference: GpuPreference,
+    ) -> Option<Result<Self::Adapter, HResult>>;
+
+    /// Iterates over all of the adapters. The first adapter returned will be
+    /// the adapter associated with the output on which the primary desktop is
+    /// displayed.
+    fn adapters(&self) -> Adapters<'_, Self>
+    where
+        Self: Sized,
+    {
+        Adapters {
+            factory: self,
+            adapter: 0,
+            preference: None,
+        }
+    }
+
+    /// Iterates over all of the adapters ordered by a `GpuPreference`.
+    fn adapters_by_preference(&self, preference: GpuPreference) -> Adapters<'_, Self>
+    where
+        Self: Sized,
+    {
+        Adapters {
+            factory: self,
+            adapter: 0,
+            preference: Some(preference),
+        }
+    }
+}
+
+/// A display sub-system (including one or more GPUs, DACs, and video
+/// memory).
+pub trait AdapterBackend {
+    type Output: OutputBackend;
+
+    /// Gets a description of the adapter.
+    fn desc1(&self) -> AdapterDesc1;
+
+    /// Gets the `AdapterDesc2` of the adapter, or `None` if the adapter does
+    /// not support it.
+    fn desc2(&self) -> Option<AdapterDesc2>;
+
+    /// Gets the `AdapterDesc3` of the adapter, or `None` if the adapter does
+    /// not support it.
+    fn desc3(&self) -> Option<AdapterDesc3>;
+
+    /// Attempt to get the Nth output. Returns `None` once `n` is past the
+    /// last output.
+    fn enum_output(&self, n: u32) -> Option<Result<Self::Output, HResult>>;
+
+    /// Iterates over the outputs associated with this adapter.
+    fn outputs(&self) -> Outputs<'_, Self>
+    where
+        Self: Sized,
+    {
+        Outputs {
+            adapter: self,
+            output: 0,
+        }
+    }
+}
+
+/// An adapter output (such as a monitor).
+pub trait OutputBackend {
+    /// Get a description of the output.
+    fn desc(&self) -> OutputDesc;
+
+    /// Gets the display modes that match the requested format, including
+    /// the scaled variants.
+    fn modes(&self, format: Format) -> Result<Vec<Mode>, HResult>;
+
+    /// Finds the display mode that most closely matches the requested display
+    /// mode. The format of `mode` must be specified.
+    fn find_closest_matching_mode(&self, mode: &Mode) -> Result<Mode, HResult>;
+}
+
+/// Iterator over the adapters of a [`FactoryBackend`].
+///
+/// Stops at the end of the list; failures are yielded as `Err` without
+/// ending the iteration.
+pub struct Adapters<'a, F: FactoryBackend> {
+    factory: &'a F,
+    adapter: u32,
+    preference: Option<GpuPreference>,
+}
+
+impl<'a, F: FactoryBackend> Iterator for Adapters<'a, F> {
+    type Item = Result<F::Adapter, HResult>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = match self.preference {
+            Some(preference) => self
+                .factory
+                .enum_adapter_by_preference(self.adapter, preference),
+            None => self.factory.enum_adapter(self.adapter),
+        };
+        self.adapter += 1;
+        result
+    }
+}
+
+/// Iterator over the outputs of an [`AdapterBackend`].
+///
+/// Stops at the end of the list; failures are yielded as `Err` without
+/// ending the iteration.
+pub struct Outputs<'a, A: AdapterBackend> {
+    adapter: &'a A,
+    output: u32,
+}
+
+impl<'a, A: AdapterBackend> Iterator for Outputs<'a, A> {
+    type Item = Result<A::Output, HResult>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.adapter.enum_output(self.output);
+        self.output += 1;
+        result
+    }
+}
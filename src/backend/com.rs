@@ -0,0 +1,103 @@
+use crate::adapter::{Adapter1, Adapter2, Adapter4, AdapterType};
+use crate::adapter::{IAdapter, IAdapter1, IAdapter2, IAdapter4};
+use crate::backend::{AdapterBackend, FactoryBackend, HResult, OutputBackend};
+use crate::descriptions::{AdapterDesc1, AdapterDesc2, AdapterDesc3, Mode, OutputDesc};
+use crate::enums::{Format, GpuPreference};
+use crate::factory::{Factory1, Factory6, IFactory1, IFactory6};
+use crate::output::{IOutput, Output};
+
+use com_wrapper::ComWrapper;
+use dcommon::error::Error;
+use winapi::shared::dxgi1_6::IDXGIFactory6;
+use winapi::shared::winerror::{DXGI_ERROR_NOT_FOUND, S_OK};
+
+impl From<Error> for HResult {
+    fn from(e: Error) -> HResult {
+        HResult(e.0)
+    }
+}
+
+impl From<HResult> for Error {
+    fn from(hr: HResult) -> Error {
+        Error(hr.0)
+    }
+}
+
+impl FactoryBackend for Factory1 {
+    type Adapter = Adapter1;
+
+    fn enum_adapter(&self, n: u32) -> Option<Result<Adapter1, HResult>> {
+        unsafe {
+            let mut ptr = std::ptr::null_mut();
+            match self.raw_f1().EnumAdapters1(n, &mut ptr) {
+                S_OK => Some(Ok(Adapter1::from_raw(ptr))),
+                DXGI_ERROR_NOT_FOUND => None,
+                hr => Some(Err(HResult(hr))),
+            }
+        }
+    }
+
+    /// Falls back to the plain enumeration order when the factory does not
+    /// support `IDXGIFactory6`, the same as DXGI does for `Unspecified`.
+    fn enum_adapter_by_preference(
+        &self,
+        n: u32,
+        preference: GpuPreference,
+    ) -> Option<Result<Adapter1, HResult>> {
+        let factory6 = unsafe {
+            self.clone()
+                .into_ptr()
+                .cast::<IDXGIFactory6>()
+                .ok()
+                .map(|ptr| Factory6::from_ptr(ptr))
+        };
+
+        match factory6 {
+            Some(factory6) => factory6
+                .enum_adapter_by_preference(n, preference)
+                .map(|result| result.map_err(HResult::from)),
+            None => self.enum_adapter(n),
+        }
+    }
+}
+
+impl AdapterBackend for Adapter1 {
+    type Output = Output;
+
+    fn desc1(&self) -> AdapterDesc1 {
+        IAdapter1::desc1(self)
+    }
+
+    fn desc2(&self) -> Option<AdapterDesc2> {
+        self.try_cast::<Adapter2>().map(|adapter| adapter.desc2())
+    }
+
+    fn desc3(&self) -> Option<AdapterDesc3> {
+        self.try_cast::<Adapter4>().map(|adapter| adapter.desc3())
+    }
+
+    fn enum_output(&self, n: u32) -> Option<Result<Output, HResult>> {
+        unsafe {
+            let mut ptr = std::ptr::null_mut();
+            match self.raw_adp().EnumOutputs(n, &mut ptr) {
+                S_OK => Some(Ok(Output::from_raw(ptr))),
+                DXGI_ERROR_NOT_FOUND => None,
+                hr => Some(Err(HResult(hr))),
+            }
+        }
+    }
+}
+
+impl OutputBackend for Output {
+    fn desc(&self) -> OutputDesc {
+        IOutput::desc(self)
+    }
+
+    fn modes(&self, format: Format) -> Result<Vec<Mode>, HResult> {
+        IOutput::modes(self, format).map_err(HResult::from)
+    }
+
+    fn find_closest_matching_mode(&self, mode: &Mode) -> Result<Mode, HResult> {
+        IOutput::find_closest_matching_mode(self, mode, None).map_err(HResult::from)
+    }
+}
@@ -0,0 +1,568 @@
+//! An in-memory backend for unit tests.
+//!
+//! A [`FakeFactory`] is built from plain descriptions: a list of adapters
+//! with their `AdapterDesc1`, `AdapterDesc2` or `AdapterDesc3`, the outputs
+//! of each adapter with their `OutputDesc` and mode lists, and `HResult`
+//! failures to return from specific calls. Everything is enumerated in the
+//! order it was added, so tests behave the same on every run and target.
+//!
+//! DXGI doesn't document how it ranks adapters by preference, so the fake
+//! uses a simple deterministic policy of its own: `enum_adapter_by_preference`
+//! with `HighPerformance` orders hardware adapters by dedicated video memory,
+//! largest first, and `MinimumPower` smallest first. Software adapters always
+//! come last, and ties keep the enumeration order. Don't rely on real systems
+//! picking the same adapter.
+//!
+//! Elsewhere the fake follows DXGI where its behavior is documented:
+//!
+//! - `modes` returns the modes of the requested format sorted by width,
+//!   height and refresh rate.
+//! - `find_closest_matching_mode` requires a format and takes an unspecified
+//!   (zero) width or height from the desktop coordinates of the output. The
+//!   rest is left to [`find_closest_mode`][1] over the modes of that format.
+//!
+//! [1]: ../../descriptions/fn.find_closest_mode.html
+
+use crate::backend::{AdapterBackend, FactoryBackend, HResult, OutputBackend};
+use crate::descriptions::{find_closest_mode, Mode};
+use crate::descriptions::{AdapterDesc1, AdapterDesc2, AdapterDesc3, OutputDesc};
+use crate::enums::{AdapterFlags, ComputePreemptionGranularity, Format};
+use crate::enums::{GpuPreference, GraphicsPreemptionGranularity};
+
+use std::cmp::Reverse;
+
+#[cfg(test)]
+use crate::helpers::fixtures::{mode, output_desc, wide};
+
+#[derive(Clone, Default)]
+/// A factory enumerating a fixed list of [`FakeAdapter`]s.
+pub struct FakeFactory {
+    adapters: Vec<FakeAdapter>,
+    preference_failure: Option<HResult>,
+}
+
+impl FakeFactory {
+    pub fn new() -> FakeFactory {
+        Default::default()
+    }
+
+    /// Adds an adapter to the end of the enumeration order. The first
+    /// adapter added is the one reported as driving the primary desktop.
+    pub fn with_adapter(mut self, adapter: FakeAdapter) -> Self {
+        self.adapters.push(adapter);
+        self
+    }
+
+    /// Makes `enum_adapter_by_preference` fail with `hr` for every adapter,
+    /// e.g. `DXGI_ERROR_INVALID_CALL` for an invalid preference.
+    pub fn with_preference_failure(mut self, hr: HResult) -> Self {
+        self.preference_failure = Some(hr);
+        self
+    }
+
+    fn preference_order(&self, preference: GpuPreference) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.adapters.len()).collect();
+        let is_software = |i: &usize| {
+            let flags = self.adapters[*i].desc.flags;
+            flags.0 & AdapterFlags::SOFTWARE.0 != 0
+        };
+        let memory = |i: &usize| self.adapters[*i].desc.dedicated_video_memory;
+
+        match preference {
+            GpuPreference::HighPerformance => {
+                order.sort_by_key(|i| (is_software(i), Reverse(memory(i))))
+            }
+            GpuPreference::MinimumPower => order.sort_by_key(|i| (is_software(i), memory(i))),
+            _ => (),
+        }
+        order
+    }
+
+    fn get(&self, index: usize) -> Option<Result<FakeAdapter, HResult>> {
+        let adapter = self.adapters.get(index)?;
+        Some(match adapter.failure {
+            Some(hr) => Err(hr),
+            None => Ok(adapter.clone()),
+        })
+    }
+}
+
+impl FactoryBackend for FakeFactory {
+    type Adapter = FakeAdapter;
+
+    fn enum_adapter(&self, n: u32) -> Option<Result<FakeAdapter, HResult>> {
+        self.get(n as usize)
+    }
+
+    fn enum_adapter_by_preference(
+        &self,
+        n: u32,
+        preference: GpuPreference,
+    ) -> Option<Result<FakeAdapter, HResult>> {
+        let index = *self.preference_order(preference).get(n as usize)?;
+        match self.preference_failure {
+            Some(hr) => Some(Err(hr)),
+            None => self.get(index),
+        }
+    }
+}
+
+#[derive(Clone)]
+/// An adapter with a fixed description and list of [`FakeOutput`]s.
+///
+/// Create one from the most recent description the adapter should support:
+/// an adapter made from an `AdapterDesc1` reports `None` from `desc2` and
+/// `desc3`, like a system without the newer interfaces.
+pub struct FakeAdapter {
+    desc: AdapterDesc3,
+    level: u32,
+    outputs: Vec<FakeOutput>,
+    failure: Option<HResult>,
+}
+
+impl FakeAdapter {
+    fn new(desc: AdapterDesc3, level: u32) -> FakeAdapter {
+        FakeAdapter {
+            desc,
+            level,
+            outputs: Vec::new(),
+            failure: None,
+        }
+    }
+
+    /// Adds an output to the end of the adapter's enumeration order.
+    pub fn with_output(mut self, output: FakeOutput) -> Self {
+        self.outputs.push(output);
+        self
+    }
+
+    /// Makes enumerating this adapter fail with `hr`, e.g.
+    /// `DXGI_ERROR_DEVICE_REMOVED`.
+    pub fn with_failure(mut self, hr: HResult) -> Self {
+        self.failure = Some(hr);
+        self
+    }
+}
+
+impl From<AdapterDesc1> for FakeAdapter {
+    fn from(desc: AdapterDesc1) -> FakeAdapter {
+        let desc = AdapterDesc3 {
+            description: desc.description,
+            vendor_id: desc.vendor_id,
+            device_id: desc.device_id,
+            subsys_id: desc.subsys_id,
+            revision: desc.revision,
+            dedicated_video_memory: desc.dedicated_video_memory,
+            dedicated_system_memory: desc.dedicated_system_memory,
+            shared_system_memory: desc.shared_system_memory,
+            adapter_luid: desc.adapter_luid,
+            flags: desc.flags,
+            graphics_preemption_granularity: GraphicsPreemptionGranularity::DmaBufferBoundary
+                .into(),
+            compute_preemption_granularity: ComputePreemptionGranularity::DmaBufferBoundary.into(),
+        };
+        FakeAdapter::new(desc, 1)
+    }
+}
+
+impl From<AdapterDesc2> for FakeAdapter {
+    fn from(desc: AdapterDesc2) -> FakeAdapter {
+        let desc = AdapterDesc3 {
+            description: desc.description,
+            vendor_id: desc.vendor_id,
+            device_id: desc.device_id,
+            subsys_id: desc.subsys_id,
+            revision: desc.revision,
+            dedicated_video_memory: desc.dedicated_video_memory,
+            dedicated_system_memory: desc.dedicated_system_memory,
+            shared_system_memory: desc.shared_system_memory,
+            adapter_luid: desc.adapter_luid,
+            flags: desc.flags,
+            graphics_preemption_granularity: desc.graphics_preemption_granularity,
+            compute_preemption_granularity: desc.compute_preemption_granularity,
+        };
+        FakeAdapter::new(desc, 2)
+    }
+}
+
+impl From<AdapterDesc3> for FakeAdapter {
+    fn from(desc: AdapterDesc3) -> FakeAdapter {
+        FakeAdapter::new(desc, 3)
+    }
+}
+
+impl AdapterBackend for FakeAdapter {
+    type Output = FakeOutput;
+
+    fn desc1(&self) -> AdapterDesc1 {
+        let desc = &self.desc;
+        AdapterDesc1 {
+            description: desc.description,
+            vendor_id: desc.vendor_id,
+            device_id: desc.device_id,
+            subsys_id: desc.subsys_id,
+            revision: desc.revision,
+            dedicated_video_memory: desc.dedicated_video_memory,
+            dedicated_system_memory: desc.dedicated_system_memory,
+            shared_system_memory: desc.shared_system_memory,
+            adapter_luid: desc.adapter_luid,
+            flags: desc.flags,
+        }
+    }
+
+    fn desc2(&self) -> Option<AdapterDesc2> {
+        if self.level < 2 {
+            return None;
+        }
+
+        let desc = &self.desc;
+        Some(AdapterDesc2 {
+            description: desc.description,
+            vendor_id: desc.vendor_id,
+            device_id: desc.device_id,
+            subsys_id: desc.subsys_id,
+            revision: desc.revision,
+            dedicated_video_memory: desc.dedicated_video_memory,
+            dedicated_system_memory: desc.dedicated_system_memory,
+            shared_system_memory: desc.shared_system_memory,
+            adapter_luid: desc.adapter_luid,
+            flags: desc.flags,
+            graphics_preemption_granularity: desc.graphics_preemption_granularity,
+            compute_preemption_granularity: desc.compute_preemption_granularity,
+        })
+    }
+
+    fn desc3(&self) -> Option<AdapterDesc3> {
+        if self.level < 3 {
+            return None;
+        }
+        Some(self.desc)
+    }
+
+    fn enum_output(&self, n: u32) -> Option<Result<FakeOutput, HResult>> {
+        let output = self.outputs.get(n as usize)?;
+        Some(match output.failure {
+            Some(hr) => Err(hr),
+            None => Ok(output.clone()),
+        })
+    }
+}
+
+#[derive(Clone)]
+/// An output with a fixed description and mode list.
+pub struct FakeOutput {
+    desc: OutputDesc,
+    modes: Vec<Mode>,
+    failure: Option<HResult>,
+    modes_failure: Option<HResult>,
+    find_failure: Option<HResult>,
+}
+
+impl FakeOutput {
+    pub fn new(desc: OutputDesc) -> FakeOutput {
+        FakeOutput {
+            desc,
+            modes: Vec::new(),
+            failure: None,
+            modes_failure: None,
+            find_failure: None,
+        }
+    }
+
+    /// Adds a display mode. Modes of every format can be mixed; `modes`
+    /// filters them by the requested format.
+    pub fn with_mode(mut self, mode: Mode) -> Self {
+        self.modes.push(mode);
+        self
+    }
+
+    /// Adds several display modes.
+    pub fn with_modes(mut self, modes: impl IntoIterator<Item = Mode>) -> Self {
+        self.modes.extend(modes);
+        self
+    }
+
+    /// Makes enumerating this output fail with `hr`.
+    pub fn with_failure(mut self, hr: HResult) -> Self {
+        self.failure = Some(hr);
+        self
+    }
+
+    /// Makes `modes` (and so `find_closest_matching_mode`) fail with `hr`,
+    /// e.g. `DXGI_ERROR_NOT_CURRENTLY_AVAILABLE` for a remote session.
+    pub fn with_modes_failure(mut self, hr: HResult) -> Self {
+        self.modes_failure = Some(hr);
+        self
+    }
+
+    /// Makes `find_closest_matching_mode` fail with `hr`.
+    pub fn with_find_closest_failure(mut self, hr: HResult) -> Self {
+        self.find_failure = Some(hr);
+        self
+    }
+}
+
+impl OutputBackend for FakeOutput {
+    fn desc(&self) -> OutputDesc {
+        self.desc
+    }
+
+    fn modes(&self, format: Format) -> Result<Vec<Mode>, HResult> {
+        if let Some(hr) = self.modes_failure {
+            return Err(hr);
+        }
+
+        let mut modes: Vec<Mode> = self
+            .modes
+            .iter()
+            .filter(|mode| mode.format.value == format as u32)
+            .cloned()
+            .collect();
+        modes.sort_by(|a, b| {
+            (a.width, a.height, a.refresh_rate).cmp(&(b.width, b.height, b.refresh_rate))
+        });
+        Ok(modes)
+    }
+
+    fn find_closest_matching_mode(&self, mode: &Mode) -> Result<Mode, HResult> {
+        if let Some(hr) = self.find_failure {
+            return Err(hr);
+        }
+
+        let format = match mode.format.as_enum() {
+            Some(Format::Unknown) | None => return Err(HResult::INVALID_CALL),
+            Some(format) => format,
+        };
+
+        let desktop = self.desc.desktop_coordinates;
+        let mut target = *mode;
+        if target.width == 0 {
+            target.width = (desktop.right - desktop.left) as u32;
+        }
+        if target.height == 0 {
+            target.height = (desktop.bottom - desktop.top) as u32;
+        }
+
+        let modes = self.modes(format)?;
+        find_closest_mode(&modes, &target)
+            .map(|found| found.mode)
+            .ok_or(HResult::NOT_FOUND)
+    }
+}
+
+#[cfg(test)]
+fn adapter(name: &str, memory: usize, flags: AdapterFlags) -> AdapterDesc1 {
+    AdapterDesc1 {
+        description: wide(name),
+        vendor_id: 0,
+        device_id: 0,
+        subsys_id: 0,
+        revision: 0,
+        dedicated_video_memory: memory,
+        dedicated_system_memory: 0,
+        shared_system_memory: 0,
+        adapter_luid: 0.into(),
+        flags,
+    }
+}
+
+#[cfg(test)]
+fn output(name: &str, left: i32, top: i32, right: i32, bottom: i32) -> FakeOutput {
+    use crate::enums::ModeRotation;
+    FakeOutput::new(output_desc(
+        name,
+        (left, top, right, bottom),
+        ModeRotation::Identity,
+    ))
+}
+
+#[cfg(test)]
+fn names(factory: &FakeFactory, preference: GpuPreference) -> Vec<String> {
+    factory
+        .adapters_by_preference(preference)
+        .map(|a| a.unwrap().desc1().description())
+        .collect()
+}
+
+#[cfg(test)]
+#[test]
+fn adapter_enumeration() {
+    let factory = FakeFactory::new()
+        .with_adapter(adapter("Integrated", 128 << 20, AdapterFlags(0)).into())
+        .with_adapter(
+            FakeAdapter::from(adapter("Removed", 4 << 30, AdapterFlags(0)))
+                .with_failure(HResult::DEVICE_REMOVED),
+        )
+        .with_adapter(adapter("Discrete", 8 << 30, AdapterFlags(0)).into())
+        .with_adapter(adapter("Basic Render", 0, AdapterFlags::SOFTWARE).into());
+
+    let adapters: Vec<_> = factory.adapters().collect();
+    assert_eq!(adapters.len(), 4);
+    assert_eq!(
+        adapters[0].as_ref().unwrap().desc1().description(),
+        "Integrated"
+    );
+    assert_eq!(adapters[1].as_ref().err(), Some(&HResult::DEVICE_REMOVED));
+    assert_eq!(
+        adapters[2].as_ref().unwrap().desc1().description(),
+        "Discrete"
+    );
+    assert!(factory.enum_adapter(4).is_none());
+
+    let factory = FakeFactory::new()
+        .with_adapter(adapter("Integrated", 128 << 20, AdapterFlags(0)).into())
+        .with_adapter(adapter("Basic Render", 0, AdapterFlags::SOFTWARE).into())
+        .with_adapter(adapter("Discrete", 8 << 30, AdapterFlags(0)).into())
+        .with_adapter(adapter("Discrete 2", 8 << 30, AdapterFlags(0)).into());
+    assert_eq!(
+        names(&factory, GpuPreference::Unspecified),
+        ["Integrated", "Basic Render", "Discrete", "Discrete 2"]
+    );
+    assert_eq!(
+        names(&factory, GpuPreference::HighPerformance),
+        ["Discrete", "Discrete 2", "Integrated", "Basic Render"]
+    );
+    assert_eq!(
+        names(&factory, GpuPreference::MinimumPower),
+        ["Integrated", "Discrete", "Discrete 2", "Basic Render"]
+    );
+    assert!(factory
+        .enum_adapter_by_preference(4, GpuPreference::HighPerformance)
+        .is_none());
+
+    let factory = factory.with_preference_failure(HResult::INVALID_CALL);
+    let results: Vec<_> = factory
+        .adapters_by_preference(GpuPreference::MinimumPower)
+        .collect();
+    assert_eq!(results.len(), 4);
+    assert_eq!(
+        factory
+            .enum_adapter_by_preference(0, GpuPreference::HighPerformance)
+            .unwrap()
+            .err(),
+        Some(HResult::INVALID_CALL)
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn adapter_descriptions() {
+    let desc1 = adapter("Old", 1 << 30, AdapterFlags(0));
+    let old = FakeAdapter::from(desc1);
+    assert_eq!(old.desc1().description(), "Old");
+    assert!(old.desc2().is_none());
+    assert!(old.desc3().is_none());
+
+    let new = FakeAdapter::from(AdapterDesc2 {
+        description: wide("New"),
+        vendor_id: 0x10DE,
+        device_id: 0x2684,
+        subsys_id: 0,
+        revision: 0,
+        dedicated_video_memory: 24 << 30,
+        dedicated_system_memory: 0,
+        shared_system_memory: 0,
+        adapter_luid: 42.into(),
+        flags: AdapterFlags(0),
+        graphics_preemption_granularity: GraphicsPreemptionGranularity::PixelBoundary.into(),
+        compute_preemption_granularity: ComputePreemptionGranularity::DispatchBoundary.into(),
+    });
+    assert_eq!(new.desc1().vendor_id, 0x10DE);
+    assert_eq!(new.desc1().adapter_luid.as_i64(), 42);
+    let desc2 = new.desc2().unwrap();
+    assert_eq!(
+        desc2.graphics_preemption_granularity.as_enum(),
+        Some(GraphicsPreemptionGranularity::PixelBoundary)
+    );
+    assert!(new.desc3().is_none());
+}
+
+#[cfg(test)]
+#[test]
+fn outputs_and_modes() {
+    let adapter = FakeAdapter::from(adapter("GPU", 1 << 30, AdapterFlags(0)))
+        .with_output(
+            output("\\\\.\\DISPLAY1", 0, 0, 1920, 1080)
+                .with_mode(mode(1920, 1080, (60, 1), Format::B8G8R8A8Unorm))
+                .with_mode(mode(1280, 720, (60, 1), Format::B8G8R8A8Unorm))
+                .with_mode(mode(1920, 1080, (60, 1), Format::R10G10B10A2Unorm))
+                .with_mode(mode(1920, 1080, (144, 1), Format::B8G8R8A8Unorm)),
+        )
+        .with_output(
+            output("\\\\.\\DISPLAY2", -1280, 0, 0, 1024)
+                .with_modes_failure(HResult::NOT_CURRENTLY_AVAILABLE),
+        )
+        .with_output(output("\\\\.\\DISPLAY3", 1920, 0, 3840, 1080).with_failure(HResult::FAIL));
+
+    let outputs: Vec<_> = adapter.outputs().collect();
+    assert_eq!(outputs.len(), 3);
+    assert_eq!(outputs[2].as_ref().err(), Some(&HResult::FAIL));
+
+    let first = outputs[0].as_ref().unwrap();
+    assert_eq!(first.desc().device_name(), "\\\\.\\DISPLAY1");
+    let modes = first.modes(Format::B8G8R8A8Unorm).unwrap();
+    let sizes: Vec<_> = modes
+        .iter()
+        .map(|m| (m.width, m.height, m.refresh_rate.numerator))
+        .collect();
+    assert_eq!(
+        sizes,
+        [(1280, 720, 60), (1920, 1080, 60), (1920, 1080, 144)]
+    );
+    assert!(first.modes(Format::R16G16B16A16Float).unwrap().is_empty());
+
+    let second = outputs[1].as_ref().unwrap();
+    assert_eq!(second.desc().desktop_coordinates.left, -1280);
+    assert_eq!(
+        second.modes(Format::B8G8R8A8Unorm).err(),
+        Some(HResult::NOT_CURRENTLY_AVAILABLE)
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn closest_matching_mode() {
+    let output = output("\\\\.\\DISPLAY1", 0, 0, 1920, 1080).with_modes(vec![
+        mode(1280, 720, (60, 1), Format::B8G8R8A8Unorm),
+        mode(1920, 1080, (60000, 1001), Format::B8G8R8A8Unorm),
+        mode(1920, 1080, (60, 1), Format::B8G8R8A8Unorm),
+        mode(1920, 1080, (144, 1), Format::B8G8R8A8Unorm),
+        mode(2560, 1440, (60, 1), Format::R10G10B10A2Unorm),
+    ]);
+    let find = |w, h, hz, format| output.find_closest_matching_mode(&mode(w, h, hz, format));
+
+    let m = find(1920, 1080, (60, 1), Format::B8G8R8A8Unorm).unwrap();
+    assert_eq!((m.width, m.height), (1920, 1080));
+    assert_eq!(m.refresh_rate, (60, 1).into());
+
+    let m = find(1900, 1000, (59, 1), Format::B8G8R8A8Unorm).unwrap();
+    assert_eq!((m.width, m.height), (1920, 1080));
+    assert_eq!(m.refresh_rate, (60000, 1001).into());
+
+    let m = find(0, 0, (0, 0), Format::B8G8R8A8Unorm).unwrap();
+    assert_eq!((m.width, m.height), (1920, 1080));
+    assert_eq!(m.refresh_rate, (144, 1).into());
+
+    let m = find(1280, 720, (144, 1), Format::B8G8R8A8Unorm).unwrap();
+    assert_eq!((m.width, m.height), (1280, 720));
+
+    let m = find(1920, 1080, (60, 1), Format::R10G10B10A2Unorm).unwrap();
+    assert_eq!((m.width, m.height), (2560, 1440));
+
+    assert_eq!(
+        find(1920, 1080, (60, 1), Format::Unknown).err(),
+        Some(HResult::INVALID_CALL)
+    );
+    assert_eq!(
+        find(1920, 1080, (60, 1), Format::R16G16B16A16Float).err(),
+        Some(HResult::NOT_FOUND)
+    );
+
+    let output = output.with_find_closest_failure(HResult::FAIL);
+    assert_eq!(
+        output
+            .find_closest_matching_mode(&mode(0, 0, (0, 0), Format::B8G8R8A8Unorm))
+            .err(),
+        Some(HResult::FAIL)
+    );
+}
@@ -0,0 +1,491 @@
+use crate::codec::float::{self, clamp};
+use crate::codec::CodecError;
+use crate::enums::{Format, NumericType};
+
+pub use self::encode::{encode_block, encode_surface, BcQuality};
+
+mod bc6h;
+mod bc7;
+mod encode;
+
+/// Decode one 4x4 block of a block-compressed format to RGBA.
+///
+/// Texels are returned in row-major order. Channels the format doesn't
+/// store default to `(0, 0, 0, 1)`, sRGB formats are converted to linear
+/// light, and snorm formats return values in `[-1, 1]`. `block` must be at
+/// least 8 bytes for `Bc1` and `Bc4`, and 16 bytes otherwise.
+///
+/// Supports the unorm, sRGB and snorm variants of `Bc1` through `Bc5`,
+/// `Bc6hUf16`, `Bc6hSf16`, `Bc7Unorm` and `Bc7UnormSrgb`. BC6H blocks
+/// decode to the half float values they store, with alpha set to 1.
+/// Typeless formats are rejected because they don't say how to interpret
+/// the block.
+pub fn decode_block(format: Format, block: &[u8]) -> Result<[[f32; 4]; 16], CodecError> {
+    let mut texels = decode_stored(format, block)?;
+    if format.info().numeric == NumericType::Srgb {
+        for texel in &mut texels {
+            for channel in &mut texel[..3] {
+                *channel = float::srgb_to_linear(*channel);
+            }
+        }
+    }
+    Ok(texels)
+}
+
+/// Decode one 4x4 block to 8-bit RGBA.
+///
+/// Unlike [`decode_block`][1], sRGB formats keep their sRGB encoding so the
+/// result can be written straight to an 8-bit image file. Snorm formats are
+/// biased from `[-1, 1]` to `[0, 255]`, the usual encoding for normal maps.
+///
+/// [1]: fn.decode_block.html
+pub fn decode_block_rgba8(format: Format, block: &[u8]) -> Result<[[u8; 4]; 16], CodecError> {
+    let texels = decode_stored(format, block)?;
+    let snorm = format.info().numeric == NumericType::Snorm;
+
+    let mut result = [[0; 4]; 16];
+    for (out, texel) in result.iter_mut().zip(&texels) {
+        for (out, &value) in out.iter_mut().zip(texel) {
+            let value = if snorm { value * 0.5 + 0.5 } else { value };
+            *out = (clamp(value, 0.0, 1.0) * 255.0).round() as u8;
+        }
+    }
+    Ok(result)
+}
+
+/// Decode a whole surface of blocks to RGBA, as laid out by
+/// [`Format::surface_layout`][1] or returned from a mapped surface.
+///
+/// `row_pitch` is the distance in bytes between rows of blocks in `src`.
+/// `dst` receives `width * height` tightly packed texels; the parts of the
+/// edge blocks that lie outside the surface are dropped. Texels are
+/// converted the same way as [`decode_block`][2].
+///
+/// [1]: ../enums/enum.Format.html#method.surface_layout
+/// [2]: fn.decode_block.html
+pub fn decode_surface(
+    format: Format,
+    src: &[u8],
+    row_pitch: usize,
+    width: u32,
+    height: u32,
+    dst: &mut [[f32; 4]],
+) -> Result<(), CodecError> {
+    decode_surface_with(format, src, row_pitch, width, height, dst, decode_block)
+}
+
+/// Decode a whole surface of blocks to 8-bit RGBA. See
+/// [`decode_surface`][1] for the layout and [`decode_block_rgba8`][2] for
+/// how texels are converted.
+///
+/// [1]: fn.decode_surface.html
+/// [2]: fn.decode_block_rgba8.html
+pub fn decode_surface_rgba8(
+    format: Format,
+    src: &[u8],
+    row_pitch: usize,
+    width: u32,
+    height: u32,
+    dst: &mut [[u8; 4]],
+) -> Result<(), CodecError> {
+    decode_surface_with(
+        format,
+        src,
+        row_pitch,
+        width,
+        height,
+        dst,
+        decode_block_rgba8,
+    )
+}
+
+fn decode_surface_with<T: Copy>(
+    format: Format,
+    src: &[u8],
+    row_pitch: usize,
+    width: u32,
+    height: u32,
+    dst: &mut [T],
+    decode: fn(Format, &[u8]) -> Result<[T; 16], CodecError>,
+) -> Result<(), CodecError> {
+    let info = format.info();
+    if !info.is_block_compressed() {
+        return Err(CodecError::UnsupportedFormat(format));
+    }
+
+    let (width, height) = (width as usize, height as usize);
+    if width == 0 || height == 0 {
+        return Ok(());
+    }
+
+    let block_size = info.bytes_per_element() as usize;
+    let blocks_wide = width.div_ceil(4);
+    let blocks_high = height.div_ceil(4);
+    let row_size = blocks_wide * block_size;
+    let src_size = (blocks_high - 1)
+        .checked_mul(row_pitch)
+        .and_then(|size| size.checked_add(row_size));
+    match src_size {
+        Some(size) if src.len() >= size && row_pitch >= row_size => (),
+        _ => return Err(CodecError::BufferTooSmall),
+    }
+    if dst.len() < width * height {
+        return Err(CodecError::BufferTooSmall);
+    }
+
+    for by in 0..blocks_high {
+        let row = &src[by * row_pitch..];
+        for bx in 0..blocks_wide {
+            let texels = decode(format, &row[bx * block_size..][..block_size])?;
+            for ty in 0..4.min(height - by * 4) {
+                let y = by * 4 + ty;
+                for tx in 0..4.min(width - bx * 4) {
+                    dst[y * width + bx * 4 + tx] = texels[ty * 4 + tx];
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Decode a block to the values it stores, without any sRGB conversion.
+fn decode_stored(format: Format, block: &[u8]) -> Result<[[f32; 4]; 16], CodecError> {
+    use crate::enums::Format::*;
+
+    let size = match format {
+        Bc1Unorm | Bc1UnormSrgb | Bc4Unorm | Bc4Snorm => 8,
+        Bc2Unorm | Bc2UnormSrgb | Bc3Unorm | Bc3UnormSrgb | Bc5Unorm | Bc5Snorm => 16,
+        Bc6hUf16 | Bc6hSf16 | Bc7Unorm | Bc7UnormSrgb => 16,
+        _ => return Err(CodecError::UnsupportedFormat(format)),
+    };
+    if block.len() < size {
+        return Err(CodecError::BufferTooSmall);
+    }
+
+    let mut texels = [[0.0, 0.0, 0.0, 1.0]; 16];
+    match format {
+        Bc1Unorm | Bc1UnormSrgb => decode_color(block, true, &mut texels),
+        Bc2Unorm | Bc2UnormSrgb => {
+            decode_color(&block[8..], false, &mut texels);
+            for (i, texel) in texels.iter_mut().enumerate() {
+                let alpha = (block[i / 2] >> (4 * (i & 1))) & 0xf;
+                texel[3] = alpha as f32 / 15.0;
+            }
+        }
+        Bc3Unorm | Bc3UnormSrgb => {
+            decode_color(&block[8..], false, &mut texels);
+            decode_alpha(&block[..8], false, 3, &mut texels);
+        }
+        Bc4Unorm | Bc4Snorm => decode_alpha(block, format == Bc4Snorm, 0, &mut texels),
+        Bc5Unorm | Bc5Snorm => {
+            decode_alpha(&block[..8], format == Bc5Snorm, 0, &mut texels);
+            decode_alpha(&block[8..], format == Bc5Snorm, 1, &mut texels);
+        }
+        Bc6hUf16 | Bc6hSf16 => texels = bc6h::decode(block, format == Bc6hSf16),
+        _ => {
+            for (texel, decoded) in texels.iter_mut().zip(&bc7::decode(block)) {
+                for (value, &decoded) in texel.iter_mut().zip(decoded) {
+                    *value = f32::from(decoded) / 255.0;
+                }
+            }
+        }
+    }
+    Ok(texels)
+}
+
+/// Decode the 8-byte color part shared by BC1, BC2 and BC3. Only BC1 uses
+/// the three color mode with transparent black.
+fn decode_color(block: &[u8], bc1: bool, texels: &mut [[f32; 4]; 16]) {
+    let c0 = u16::from(block[0]) | u16::from(block[1]) << 8;
+    let c1 = u16::from(block[2]) | u16::from(block[3]) << 8;
+    let palette = color_palette(c0, c1, bc1);
+
+    let indices = u32::from(block[4])
+        | u32::from(block[5]) << 8
+        | u32::from(block[6]) << 16
+        | u32::from(block[7]) << 24;
+    for (i, texel) in texels.iter_mut().enumerate() {
+        let color = palette[(indices >> (2 * i)) as usize & 3];
+        texel[..3].copy_from_slice(&color[..3]);
+        texel[3] = texel[3].min(color[3]);
+    }
+}
+
+/// The four colors selectable by a color block with 5:6:5 endpoints `c0`
+/// and `c1`.
+fn color_palette(c0: u16, c1: u16, bc1: bool) -> [[f32; 4]; 4] {
+    let (e0, e1) = (expand_565(c0), expand_565(c1));
+    let lerp = |a: f32, b: f32, num: f32, den: f32| (a * (den - num) + b * num) / den;
+
+    let mut palette = [[0.0, 0.0, 0.0, 1.0]; 4];
+    for ch in 0..3 {
+        palette[0][ch] = e0[ch];
+        palette[1][ch] = e1[ch];
+        if c0 > c1 || !bc1 {
+            palette[2][ch] = lerp(e0[ch], e1[ch], 1.0, 3.0);
+            palette[3][ch] = lerp(e0[ch], e1[ch], 2.0, 3.0);
+        } else {
+            palette[2][ch] = lerp(e0[ch], e1[ch], 1.0, 2.0);
+        }
+    }
+    if c0 <= c1 && bc1 {
+        palette[3][3] = 0.0;
+    }
+    palette
+}
+
+/// Decode an 8-byte BC4 style block into one channel of `texels`.
+fn decode_alpha(block: &[u8], snorm: bool, channel: usize, texels: &mut [[f32; 4]; 16]) {
+    let palette = alpha_palette(block[0], block[1], snorm);
+    let indices = block[2..8]
+        .iter()
+        .rev()
+        .fold(0u64, |bits, &b| bits << 8 | u64::from(b));
+    for (i, texel) in texels.iter_mut().enumerate() {
+        texel[channel] = palette[(indices >> (3 * i)) as usize & 7];
+    }
+}
+
+/// The eight values selectable by a BC4 style block with endpoint bytes
+/// `a0` and `a1`.
+fn alpha_palette(a0: u8, a1: u8, snorm: bool) -> [f32; 8] {
+    let (e0, e1, six) = if snorm {
+        let (a0, a1) = (a0 as i8, a1 as i8);
+        let e0 = f32::from(a0.max(-127)) / 127.0;
+        let e1 = f32::from(a1.max(-127)) / 127.0;
+        (e0, e1, a0 <= a1)
+    } else {
+        (f32::from(a0) / 255.0, f32::from(a1) / 255.0, a0 <= a1)
+    };
+
+    let mut palette = [e0, e1, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+    if six {
+        for i in 1..5 {
+            palette[i + 1] = (e0 * (5 - i) as f32 + e1 * i as f32) / 5.0;
+        }
+        palette[6] = if snorm { -1.0 } else { 0.0 };
+        palette[7] = 1.0;
+    } else {
+        for i in 1..7 {
+            palette[i + 1] = (e0 * (7 - i) as f32 + e1 * i as f32) / 7.0;
+        }
+    }
+    palette
+}
+
+fn expand_565(color: u16) -> [f32; 3] {
+    [
+        f32::from(color >> 11) / 31.0,
+        f32::from((color >> 5) & 0x3f) / 63.0,
+        f32::from(color & 0x1f) / 31.0,
+    ]
+}
+
+/// Reads fields from a 16-byte block, least significant bit first.
+struct BitReader {
+    bits: u128,
+}
+
+impl BitReader {
+    fn new(block: &[u8]) -> BitReader {
+        let mut bytes = [0; 16];
+        bytes.copy_from_slice(&block[..16]);
+        BitReader {
+            bits: u128::from_le_bytes(bytes),
+        }
+    }
+
+    fn read(&mut self, count: u32) -> u32 {
+        let value = self.bits & ((1 << count) - 1);
+        self.bits >>= count;
+        value as u32
+    }
+}
+
+#[cfg(test)]
+struct BitWriter {
+    bits: u128,
+    pos: u32,
+}
+
+#[cfg(test)]
+impl BitWriter {
+    fn new() -> BitWriter {
+        BitWriter { bits: 0, pos: 0 }
+    }
+
+    fn put(&mut self, value: u32, count: u32) {
+        if count != 0 {
+            assert!(self.pos + count <= 128);
+            self.bits |= (u128::from(value) & ((1 << count) - 1)) << self.pos;
+            self.pos += count;
+        }
+    }
+
+    fn finish(self) -> [u8; 16] {
+        self.bits.to_le_bytes()
+    }
+}
+
+#[cfg(test)]
+fn block8(e0: u16, e1: u16, indices: u32) -> [u8; 8] {
+    let mut block = [0; 8];
+    block[..2].copy_from_slice(&e0.to_le_bytes());
+    block[2..4].copy_from_slice(&e1.to_le_bytes());
+    block[4..].copy_from_slice(&indices.to_le_bytes());
+    block
+}
+
+#[cfg(test)]
+fn alpha_block(a0: u8, a1: u8, indices: &[u8; 16]) -> [u8; 8] {
+    let bits = indices
+        .iter()
+        .rev()
+        .fold(0u64, |bits, &i| bits << 3 | u64::from(i));
+    let mut block = [0; 8];
+    block[0] = a0;
+    block[1] = a1;
+    block[2..].copy_from_slice(&bits.to_le_bytes()[..6]);
+    block
+}
+
+#[cfg(test)]
+#[test]
+fn bc1_reference() {
+    // Red to blue, each row using indices 0, 1, 2, 3.
+    let block = block8(0xf800, 0x001f, 0xe4e4_e4e4);
+    let texels = decode_block_rgba8(Format::Bc1Unorm, &block).unwrap();
+    assert_eq!(texels[0], [255, 0, 0, 255]);
+    assert_eq!(texels[1], [0, 0, 255, 255]);
+    assert_eq!(texels[2], [170, 0, 85, 255]);
+    assert_eq!(texels[3], [85, 0, 170, 255]);
+    assert_eq!(texels[15], texels[3]);
+
+    // Swapped endpoints select the three color mode with 1-bit alpha.
+    let block = block8(0x001f, 0xf800, 0xe4e4_e4e4);
+    let texels = decode_block_rgba8(Format::Bc1Unorm, &block).unwrap();
+    assert_eq!(texels[0], [0, 0, 255, 255]);
+    assert_eq!(texels[2], [128, 0, 128, 255]);
+    assert_eq!(texels[3], [0, 0, 0, 0]);
+
+    // Equal endpoints are also the three color mode.
+    let block = block8(0x07e0, 0x07e0, 0xffff_ffff);
+    let texels = decode_block(Format::Bc1Unorm, &block).unwrap();
+    assert_eq!(texels[7], [0.0, 0.0, 0.0, 0.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn bc2_bc3_reference() {
+    let color = block8(0x001f, 0xf800, 0xe4e4_e4e4);
+
+    let mut block = [0u8; 16];
+    for (i, byte) in block[..8].iter_mut().enumerate() {
+        *byte = (2 * i as u8) | (2 * i as u8 + 1) << 4;
+    }
+    block[8..].copy_from_slice(&color);
+    let texels = decode_block_rgba8(Format::Bc2Unorm, &block).unwrap();
+    for (i, texel) in texels.iter().enumerate() {
+        assert_eq!(texel[3], i as u8 * 17);
+    }
+    // BC2 always uses the four color mode.
+    assert_eq!(texels[3][..3], [170, 0, 85]);
+
+    let alpha = alpha_block(255, 0, &[0, 1, 2, 3, 4, 5, 6, 7, 0, 0, 0, 0, 1, 1, 1, 1]);
+    block[..8].copy_from_slice(&alpha);
+    let texels = decode_block_rgba8(Format::Bc3Unorm, &block).unwrap();
+    let alphas: Vec<u8> = texels[..8].iter().map(|t| t[3]).collect();
+    assert_eq!(alphas, [255, 0, 219, 182, 146, 109, 73, 36]);
+    assert_eq!(texels[3][..3], [170, 0, 85]);
+
+    let alpha = alpha_block(0, 255, &[0, 1, 2, 3, 4, 5, 6, 7, 0, 0, 0, 0, 1, 1, 1, 1]);
+    block[..8].copy_from_slice(&alpha);
+    let texels = decode_block_rgba8(Format::Bc3Unorm, &block).unwrap();
+    let alphas: Vec<u8> = texels[..8].iter().map(|t| t[3]).collect();
+    assert_eq!(alphas, [0, 255, 51, 102, 153, 204, 0, 255]);
+}
+
+#[cfg(test)]
+#[test]
+fn bc4_bc5_reference() {
+    let indices = [0, 1, 2, 3, 4, 5, 6, 7, 7, 6, 5, 4, 3, 2, 1, 0];
+
+    let block = alpha_block(0x80, 0x7f, &indices);
+    let texels = decode_block(Format::Bc4Snorm, &block).unwrap();
+    assert_eq!(texels[0], [-1.0, 0.0, 0.0, 1.0]);
+    assert_eq!(texels[1], [1.0, 0.0, 0.0, 1.0]);
+    assert_eq!(texels[6][0], -1.0);
+    assert_eq!(texels[7][0], 1.0);
+    let texels = decode_block_rgba8(Format::Bc4Snorm, &block).unwrap();
+    assert_eq!(texels[0], [0, 128, 128, 255]);
+    assert_eq!(texels[1], [255, 128, 128, 255]);
+
+    let block = alpha_block(200, 100, &indices);
+    let texels = decode_block_rgba8(Format::Bc4Unorm, &block).unwrap();
+    let reds: Vec<u8> = texels[..8].iter().map(|t| t[0]).collect();
+    assert_eq!(reds, [200, 100, 186, 171, 157, 143, 129, 114]);
+    assert_eq!(texels[8], [114, 0, 0, 255]);
+
+    let mut bc5 = [0u8; 16];
+    bc5[..8].copy_from_slice(&alpha_block(200, 100, &indices));
+    bc5[8..].copy_from_slice(&alpha_block(0, 255, &indices));
+    let texels = decode_block_rgba8(Format::Bc5Unorm, &bc5).unwrap();
+    assert_eq!(texels[0], [200, 0, 0, 255]);
+    assert_eq!(texels[1], [100, 255, 0, 255]);
+    assert_eq!(texels[7], [114, 255, 0, 255]);
+    assert_eq!(texels[6], [129, 0, 0, 255]);
+}
+
+#[cfg(test)]
+#[test]
+fn bc_srgb_and_errors() {
+    let block = block8(0x8410, 0x8410, 0);
+    let stored = decode_block_rgba8(Format::Bc1UnormSrgb, &block).unwrap();
+    let linear = decode_block(Format::Bc1UnormSrgb, &block).unwrap();
+    assert_eq!(stored[0][0], 132);
+    assert_eq!(linear[0][0], float::srgb_to_linear(16.0 / 31.0));
+
+    assert_eq!(
+        decode_block(Format::Bc1Typeless, &block),
+        Err(CodecError::UnsupportedFormat(Format::Bc1Typeless))
+    );
+    assert_eq!(
+        decode_block(Format::Bc3Unorm, &block),
+        Err(CodecError::BufferTooSmall)
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn bc_surface() {
+    // A 5x5 surface is 2x2 blocks; each block is a solid color.
+    let colors = [0xf800u16, 0x07e0, 0x001f, 0xffff];
+    let row_pitch = 20;
+    let mut src = vec![0u8; row_pitch * 2];
+    for (i, &color) in colors.iter().enumerate() {
+        let offset = (i / 2) * row_pitch + (i % 2) * 8;
+        src[offset..offset + 8].copy_from_slice(&block8(color, 0, 0));
+    }
+
+    let mut dst = vec![[0u8; 4]; 25];
+    decode_surface_rgba8(Format::Bc1Unorm, &src, row_pitch, 5, 5, &mut dst).unwrap();
+    assert_eq!(dst[0], [255, 0, 0, 255]);
+    assert_eq!(dst[3], [255, 0, 0, 255]);
+    assert_eq!(dst[4], [0, 255, 0, 255]);
+    assert_eq!(dst[20], [0, 0, 255, 255]);
+    assert_eq!(dst[24], [255, 255, 255, 255]);
+
+    assert_eq!(
+        decode_surface_rgba8(Format::Bc1Unorm, &src[..30], row_pitch, 5, 5, &mut dst),
+        Err(CodecError::BufferTooSmall)
+    );
+    assert_eq!(
+        decode_surface_rgba8(Format::Bc1Unorm, &src, usize::MAX, 5, 5, &mut dst),
+        Err(CodecError::BufferTooSmall)
+    );
+    assert_eq!(
+        decode_surface_rgba8(Format::R8Unorm, &src, row_pitch, 5, 5, &mut dst),
+        Err(CodecError::UnsupportedFormat(Format::R8Unorm))
+    );
+}
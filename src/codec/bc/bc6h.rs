@@ -0,0 +1,416 @@
+use super::bc7::{ANCHORS2, PARTITIONS2, WEIGHTS3, WEIGHTS4};
+use super::BitReader;
+use crate::codec::float::f16_to_f32;
+
+// Fields of the block header. `W` and `X` are the endpoints of the first
+// region, `Y` and `Z` those of the second.
+const RW: u8 = 0;
+const GW: u8 = 1;
+const BW: u8 = 2;
+const RX: u8 = 3;
+const GX: u8 = 4;
+const BX: u8 = 5;
+const RY: u8 = 6;
+const GY: u8 = 7;
+const BY: u8 = 8;
+const RZ: u8 = 9;
+const GZ: u8 = 10;
+const BZ: u8 = 11;
+const D: u8 = 12;
+
+/// A run of header bits `(field, msb, lsb)`. Bits are stored starting with
+/// `lsb`; when `msb < lsb` the run is stored in reverse order.
+type Segment = (u8, u8, u8);
+
+struct Mode {
+    /// The mode bits, read least significant bit first.
+    code: u32,
+    code_bits: u32,
+    regions: usize,
+    transformed: bool,
+    endpoint_bits: u32,
+    delta_bits: [u32; 3],
+    layout: &'static [Segment],
+}
+
+#[rustfmt::skip]
+static MODES: [Mode; 14] = [
+    Mode { code: 0x00, code_bits: 2, regions: 2, transformed: true, endpoint_bits: 10,
+        delta_bits: [5, 5, 5], layout: &[
+        (GY, 4, 4), (BY, 4, 4), (BZ, 4, 4), (RW, 9, 0), (GW, 9, 0), (BW, 9, 0),
+        (RX, 4, 0), (GZ, 4, 4), (GY, 3, 0), (GX, 4, 0), (BZ, 0, 0), (GZ, 3, 0),
+        (BX, 4, 0), (BZ, 1, 1), (BY, 3, 0), (RY, 4, 0), (BZ, 2, 2), (RZ, 4, 0),
+        (BZ, 3, 3), (D, 4, 0),
+    ] },
+    Mode { code: 0x01, code_bits: 2, regions: 2, transformed: true, endpoint_bits: 7,
+        delta_bits: [6, 6, 6], layout: &[
+        (GY, 5, 5), (GZ, 4, 4), (GZ, 5, 5), (RW, 6, 0), (BZ, 0, 0), (BZ, 1, 1),
+        (BY, 4, 4), (GW, 6, 0), (BY, 5, 5), (BZ, 2, 2), (GY, 4, 4), (BW, 6, 0),
+        (BZ, 3, 3), (BZ, 5, 5), (BZ, 4, 4), (RX, 5, 0), (GY, 3, 0), (GX, 5, 0),
+        (GZ, 3, 0), (BX, 5, 0), (BY, 3, 0), (RY, 5, 0), (RZ, 5, 0), (D, 4, 0),
+    ] },
+    Mode { code: 0x02, code_bits: 5, regions: 2, transformed: true, endpoint_bits: 11,
+        delta_bits: [5, 4, 4], layout: &[
+        (RW, 9, 0), (GW, 9, 0), (BW, 9, 0), (RX, 4, 0), (RW, 10, 10), (GY, 3, 0),
+        (GX, 3, 0), (GW, 10, 10), (BZ, 0, 0), (GZ, 3, 0), (BX, 3, 0), (BW, 10, 10),
+        (BZ, 1, 1), (BY, 3, 0), (RY, 4, 0), (BZ, 2, 2), (RZ, 4, 0), (BZ, 3, 3),
+        (D, 4, 0),
+    ] },
+    Mode { code: 0x06, code_bits: 5, regions: 2, transformed: true, endpoint_bits: 11,
+        delta_bits: [4, 5, 4], layout: &[
+        (RW, 9, 0), (GW, 9, 0), (BW, 9, 0), (RX, 3, 0), (RW, 10, 10), (GZ, 4, 4),
+        (GY, 3, 0), (GX, 4, 0), (GW, 10, 10), (GZ, 3, 0), (BX, 3, 0), (BW, 10, 10),
+        (BZ, 1, 1), (BY, 3, 0), (RY, 3, 0), (BZ, 0, 0), (BZ, 2, 2), (RZ, 3, 0),
+        (GY, 4, 4), (BZ, 3, 3), (D, 4, 0),
+    ] },
+    Mode { code: 0x0a, code_bits: 5, regions: 2, transformed: true, endpoint_bits: 11,
+        delta_bits: [4, 4, 5], layout: &[
+        (RW, 9, 0), (GW, 9, 0), (BW, 9, 0), (RX, 3, 0), (RW, 10, 10), (BY, 4, 4),
+        (GY, 3, 0), (GX, 3, 0), (GW, 10, 10), (BZ, 0, 0), (GZ, 3, 0), (BX, 4, 0),
+        (BW, 10, 10), (BY, 3, 0), (RY, 3, 0), (BZ, 1, 1), (BZ, 2, 2), (RZ, 3, 0),
+        (BZ, 4, 4), (BZ, 3, 3), (D, 4, 0),
+    ] },
+    Mode { code: 0x0e, code_bits: 5, regions: 2, transformed: true, endpoint_bits: 9,
+        delta_bits: [5, 5, 5], layout: &[
+        (RW, 8, 0), (BY, 4, 4), (GW, 8, 0), (GY, 4, 4), (BW, 8, 0), (BZ, 4, 4),
+        (RX, 4, 0), (GZ, 4, 4), (GY, 3, 0), (GX, 4, 0), (BZ, 0, 0), (GZ, 3, 0),
+        (BX, 4, 0), (BZ, 1, 1), (BY, 3, 0), (RY, 4, 0), (BZ, 2, 2), (RZ, 4, 0),
+        (BZ, 3, 3), (D, 4, 0),
+    ] },
+    Mode { code: 0x12, code_bits: 5, regions: 2, transformed: true, endpoint_bits: 8,
+        delta_bits: [6, 5, 5], layout: &[
+        (RW, 7, 0), (GZ, 4, 4), (BY, 4, 4), (GW, 7, 0), (BZ, 2, 2), (GY, 4, 4),
+        (BW, 7, 0), (BZ, 3, 3), (BZ, 4, 4), (RX, 5, 0), (GY, 3, 0), (GX, 4, 0),
+        (BZ, 0, 0), (GZ, 3, 0), (BX, 4, 0), (BZ, 1, 1), (BY, 3, 0), (RY, 5, 0),
+        (RZ, 5, 0), (D, 4, 0),
+    ] },
+    Mode { code: 0x16, code_bits: 5, regions: 2, transformed: true, endpoint_bits: 8,
+        delta_bits: [5, 6, 5], layout: &[
+        (RW, 7, 0), (BZ, 0, 0), (BY, 4, 4), (GW, 7, 0), (GY, 5, 5), (GY, 4, 4),
+        (BW, 7, 0), (GZ, 5, 5), (BZ, 4, 4), (RX, 4, 0), (GZ, 4, 4), (GY, 3, 0),
+        (GX, 5, 0), (GZ, 3, 0), (BX, 4, 0), (BZ, 1, 1), (BY, 3, 0), (RY, 4, 0),
+        (BZ, 2, 2), (RZ, 4, 0), (BZ, 3, 3), (D, 4, 0),
+    ] },
+    Mode { code: 0x1a, code_bits: 5, regions: 2, transformed: true, endpoint_bits: 8,
+        delta_bits: [5, 5, 6], layout: &[
+        (RW, 7, 0), (BZ, 1, 1), (BY, 4, 4), (GW, 7, 0), (BY, 5, 5), (GY, 4, 4),
+        (BW, 7, 0), (BZ, 5, 5), (BZ, 4, 4), (RX, 4, 0), (GZ, 4, 4), (GY, 3, 0),
+        (GX, 4, 0), (BZ, 0, 0), (GZ, 3, 0), (BX, 5, 0), (BY, 3, 0), (RY, 4, 0),
+        (BZ, 2, 2), (RZ, 4, 0), (BZ, 3, 3), (D, 4, 0),
+    ] },
+    Mode { code: 0x1e, code_bits: 5, regions: 2, transformed: false, endpoint_bits: 6,
+        delta_bits: [6, 6, 6], layout: &[
+        (RW, 5, 0), (GZ, 4, 4), (BZ, 0, 0), (BZ, 1, 1), (BY, 4, 4), (GW, 5, 0),
+        (GY, 5, 5), (BY, 5, 5), (BZ, 2, 2), (GY, 4, 4), (BW, 5, 0), (GZ, 5, 5),
+        (BZ, 3, 3), (BZ, 5, 5), (BZ, 4, 4), (RX, 5, 0), (GY, 3, 0), (GX, 5, 0),
+        (GZ, 3, 0), (BX, 5, 0), (BY, 3, 0), (RY, 5, 0), (RZ, 5, 0), (D, 4, 0),
+    ] },
+    Mode { code: 0x03, code_bits: 5, regions: 1, transformed: false, endpoint_bits: 10,
+        delta_bits: [10, 10, 10], layout: &[
+        (RW, 9, 0), (GW, 9, 0), (BW, 9, 0), (RX, 9, 0), (GX, 9, 0), (BX, 9, 0),
+    ] },
+    Mode { code: 0x07, code_bits: 5, regions: 1, transformed: true, endpoint_bits: 11,
+        delta_bits: [9, 9, 9], layout: &[
+        (RW, 9, 0), (GW, 9, 0), (BW, 9, 0), (RX, 8, 0), (RW, 10, 10), (GX, 8, 0),
+        (GW, 10, 10), (BX, 8, 0), (BW, 10, 10),
+    ] },
+    Mode { code: 0x0b, code_bits: 5, regions: 1, transformed: true, endpoint_bits: 12,
+        delta_bits: [8, 8, 8], layout: &[
+        (RW, 9, 0), (GW, 9, 0), (BW, 9, 0), (RX, 7, 0), (RW, 10, 11), (GX, 7, 0),
+        (GW, 10, 11), (BX, 7, 0), (BW, 10, 11),
+    ] },
+    Mode { code: 0x0f, code_bits: 5, regions: 1, transformed: true, endpoint_bits: 16,
+        delta_bits: [4, 4, 4], layout: &[
+        (RW, 9, 0), (GW, 9, 0), (BW, 9, 0), (RX, 3, 0), (RW, 10, 15), (GX, 3, 0),
+        (GW, 10, 15), (BX, 3, 0), (BW, 10, 15),
+    ] },
+];
+
+/// Decode a 16-byte BC6H block to RGB floats, with alpha set to 1. Blocks
+/// using one of the reserved modes decode to black.
+pub(super) fn decode(block: &[u8], signed: bool) -> [[f32; 4]; 16] {
+    // The 2 bit mode codes never start with the low bits of a 5 bit one.
+    let header = u32::from(block[0]);
+    let mode = MODES
+        .iter()
+        .find(|mode| header & ((1 << mode.code_bits) - 1) == mode.code);
+    let mode = match mode {
+        Some(mode) => mode,
+        None => return [[0.0, 0.0, 0.0, 1.0]; 16],
+    };
+    let mut bits = BitReader::new(block);
+    bits.read(mode.code_bits);
+
+    let mut fields = [0i32; 13];
+    for &(field, msb, lsb) in mode.layout {
+        let (msb, lsb) = (u32::from(msb), u32::from(lsb));
+        if msb >= lsb {
+            for bit in lsb..=msb {
+                fields[field as usize] |= (bits.read(1) << bit) as i32;
+            }
+        } else {
+            for bit in (msb..=lsb).rev() {
+                fields[field as usize] |= (bits.read(1) << bit) as i32;
+            }
+        }
+    }
+
+    let endpoint_count = mode.regions * 2;
+    let mut endpoints = [[0i32; 3]; 4];
+    for (k, endpoint) in endpoints[..endpoint_count].iter_mut().enumerate() {
+        endpoint.copy_from_slice(&fields[k * 3..k * 3 + 3]);
+    }
+
+    let precision = mode.endpoint_bits;
+    if signed {
+        for value in &mut endpoints[0] {
+            *value = sign_extend(*value, precision);
+        }
+    }
+    if signed || mode.transformed {
+        for endpoint in &mut endpoints[1..endpoint_count] {
+            for (value, &bits) in endpoint.iter_mut().zip(&mode.delta_bits) {
+                *value = sign_extend(*value, bits);
+            }
+        }
+    }
+    if mode.transformed {
+        let base = endpoints[0];
+        for endpoint in &mut endpoints[1..endpoint_count] {
+            for (value, &base) in endpoint.iter_mut().zip(&base) {
+                *value = (base + *value) & ((1 << precision) - 1);
+                if signed {
+                    *value = sign_extend(*value, precision);
+                }
+            }
+        }
+    }
+    for endpoint in &mut endpoints[..endpoint_count] {
+        for value in endpoint.iter_mut() {
+            *value = unquantize(*value, precision, signed);
+        }
+    }
+
+    let partition = fields[D as usize] as usize;
+    let index_bits = if mode.regions == 2 { 3 } else { 4 };
+
+    let mut texels = [[0.0, 0.0, 0.0, 1.0]; 16];
+    for (i, texel) in texels.iter_mut().enumerate() {
+        let (region, anchor) = if mode.regions == 2 {
+            let region = (PARTITIONS2[partition] >> i) as usize & 1;
+            (region, i == 0 || i == ANCHORS2[partition] as usize)
+        } else {
+            (0, i == 0)
+        };
+
+        let index = bits.read(index_bits - anchor as u32) as usize;
+        let weight = if index_bits == 3 {
+            WEIGHTS3[index]
+        } else {
+            WEIGHTS4[index]
+        } as i32;
+
+        let (e0, e1) = (endpoints[region * 2], endpoints[region * 2 + 1]);
+        for c in 0..3 {
+            let value = (e0[c] * (64 - weight) + e1[c] * weight + 32) >> 6;
+            texel[c] = f16_to_f32(finish_unquantize(value, signed));
+        }
+    }
+    texels
+}
+
+fn sign_extend(value: i32, bits: u32) -> i32 {
+    let shift = 32 - bits;
+    (value << shift) >> shift
+}
+
+/// Scale an endpoint to 16 bits (or 15 bits plus sign).
+fn unquantize(value: i32, bits: u32, signed: bool) -> i32 {
+    if !signed {
+        if bits >= 15 || value == 0 {
+            value
+        } else if value == (1 << bits) - 1 {
+            0xffff
+        } else {
+            ((value << 16) + 0x8000) >> bits
+        }
+    } else if bits >= 16 {
+        value
+    } else {
+        let magnitude = value.abs();
+        let scaled = if magnitude == 0 {
+            0
+        } else if magnitude >= (1 << (bits - 1)) - 1 {
+            0x7fff
+        } else {
+            ((magnitude << 15) + 0x4000) >> (bits - 1)
+        };
+        if value < 0 {
+            -scaled
+        } else {
+            scaled
+        }
+    }
+}
+
+/// Scale an interpolated value to the bit pattern of a half float.
+fn finish_unquantize(value: i32, signed: bool) -> u16 {
+    if !signed {
+        ((value * 31) >> 6) as u16
+    } else if value < 0 {
+        0x8000 | ((-value * 31) >> 5) as u16
+    } else {
+        ((value * 31) >> 5) as u16
+    }
+}
+
+#[cfg(test)]
+use super::BitWriter;
+
+/// Build a block from header fields and per texel indices.
+#[cfg(test)]
+fn pack(mode: usize, fields: &[(u8, i32)], indices: &[u32; 16]) -> [u8; 16] {
+    let mode = &MODES[mode];
+    let field = |f: u8| fields.iter().find(|x| x.0 == f).map_or(0, |x| x.1);
+
+    let mut writer = BitWriter::new();
+    writer.put(mode.code, mode.code_bits);
+    for &(f, msb, lsb) in mode.layout {
+        let value = field(f) as u32;
+        if msb >= lsb {
+            for bit in lsb..=msb {
+                writer.put(value >> bit, 1);
+            }
+        } else {
+            for bit in (msb..=lsb).rev() {
+                writer.put(value >> bit, 1);
+            }
+        }
+    }
+
+    let partition = field(D) as usize;
+    for (i, &index) in indices.iter().enumerate() {
+        let anchor = i == 0 || (mode.regions == 2 && i == ANCHORS2[partition] as usize);
+        let bits = if mode.regions == 2 { 3 } else { 4 };
+        writer.put(index, bits - anchor as u32);
+    }
+    writer.finish()
+}
+
+#[cfg(test)]
+#[test]
+fn mode_layouts() {
+    for mode in MODES.iter() {
+        let mut seen = std::collections::HashSet::new();
+        let mut count = mode.code_bits;
+        for &(field, msb, lsb) in mode.layout {
+            let (lo, hi) = (msb.min(lsb), msb.max(lsb));
+            for bit in lo..=hi {
+                assert!(seen.insert((field, bit)), "mode {:#x}", mode.code);
+                count += 1;
+            }
+        }
+        assert_eq!(count, if mode.regions == 2 { 82 } else { 65 });
+
+        let mut expected = std::collections::HashSet::new();
+        for c in 0..3 {
+            for bit in 0..mode.endpoint_bits as u8 {
+                expected.insert((RW + c as u8, bit));
+            }
+            for k in 1..mode.regions * 2 {
+                for bit in 0..mode.delta_bits[c] as u8 {
+                    expected.insert((k as u8 * 3 + c as u8, bit));
+                }
+            }
+        }
+        if mode.regions == 2 {
+            for bit in 0..5 {
+                expected.insert((D, bit));
+            }
+        }
+        assert_eq!(seen, expected, "mode {:#x}", mode.code);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn solid_blocks_all_modes() {
+    for (index, mode) in MODES.iter().enumerate() {
+        let max = (1 << mode.endpoint_bits) - 1;
+        let mut fields = vec![(RW, max), (GW, max), (BW, max)];
+        if !mode.transformed {
+            for k in 1..mode.regions * 2 {
+                for c in 0..3 {
+                    fields.push((k as u8 * 3 + c, max));
+                }
+            }
+        }
+
+        let indices = [0, 1, 2, 3, 3, 2, 1, 0, 0, 1, 2, 3, 3, 2, 1, 0];
+        let texels = decode(&pack(index, &fields, &indices), false);
+        for texel in texels.iter() {
+            assert_eq!(
+                *texel,
+                [65504.0, 65504.0, 65504.0, 1.0],
+                "mode {}",
+                index + 1
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn unsigned_values() {
+    // Mode 11: one region, untransformed 10-bit endpoints.
+    let mut indices = [0; 16];
+    for (i, index) in indices.iter_mut().enumerate() {
+        *index = i as u32;
+    }
+    let block = pack(10, &[(RW, 512), (GX, 1023), (BX, 1023)], &indices);
+    let texels = decode(&block, false);
+    assert_eq!(texels[0][0], f16_to_f32(0x3e0f));
+    assert_eq!(texels[0][1], 0.0);
+    assert_eq!(texels[8][1], f16_to_f32(0x41df));
+    assert_eq!(texels[15][2], 65504.0);
+
+    // Mode 1: deltas relative to the base endpoint in both regions.
+    let mut indices = [0; 16];
+    indices[1] = 7;
+    let block = pack(
+        0,
+        &[(RW, 100), (RX, -1), (RY, 2), (RZ, 0), (D, 0)],
+        &indices,
+    );
+    let texels = decode(&block, false);
+    assert_eq!(texels[0][0], f16_to_f32(3115));
+    assert_eq!(texels[1][0], f16_to_f32(3084));
+    assert_eq!(texels[2][0], f16_to_f32(3177));
+}
+
+#[cfg(test)]
+#[test]
+fn signed_values() {
+    let block = pack(10, &[(RW, -512), (GW, 511)], &[0; 16]);
+    let texels = decode(&block, true);
+    assert_eq!(texels[0][0], -65504.0);
+    assert_eq!(texels[0][1], 65504.0);
+    assert_eq!(texels[0][2], 0.0);
+
+    let mut indices = [0; 16];
+    indices[1] = 7;
+    let block = pack(0, &[(RW, -100), (RX, 1)], &indices);
+    let texels = decode(&block, true);
+    assert_eq!(texels[0][0], f16_to_f32(0x9857));
+    assert_eq!(texels[1][0], f16_to_f32(0x9819));
+}
+
+#[cfg(test)]
+#[test]
+fn reserved_modes() {
+    for &code in &[0x13u8, 0x17, 0x1b, 0x1f] {
+        let mut block = [0xffu8; 16];
+        block[0] = code;
+        assert_eq!(decode(&block, false), [[0.0, 0.0, 0.0, 1.0]; 16]);
+    }
+}
@@ -0,0 +1,496 @@
+use super::BitReader;
+
+struct Mode {
+    subsets: usize,
+    partition_bits: u32,
+    rotation_bits: u32,
+    index_select_bits: u32,
+    color_bits: u32,
+    alpha_bits: u32,
+    endpoint_pbits: bool,
+    shared_pbits: bool,
+    index_bits: u32,
+    index2_bits: u32,
+}
+
+macro_rules! mode {
+    ($ns:expr, $pb:expr, $rb:expr, $isb:expr, $cb:expr, $ab:expr, $epb:expr, $spb:expr,
+     $ib:expr, $ib2:expr) => {
+        Mode {
+            subsets: $ns,
+            partition_bits: $pb,
+            rotation_bits: $rb,
+            index_select_bits: $isb,
+            color_bits: $cb,
+            alpha_bits: $ab,
+            endpoint_pbits: $epb != 0,
+            shared_pbits: $spb != 0,
+            index_bits: $ib,
+            index2_bits: $ib2,
+        }
+    };
+}
+
+#[rustfmt::skip]
+static MODES: [Mode; 8] = [
+    mode!(3, 4, 0, 0, 4, 0, 1, 0, 3, 0),
+    mode!(2, 6, 0, 0, 6, 0, 0, 1, 3, 0),
+    mode!(3, 6, 0, 0, 5, 0, 0, 0, 2, 0),
+    mode!(2, 6, 0, 0, 7, 0, 1, 0, 2, 0),
+    mode!(1, 0, 2, 1, 5, 6, 0, 0, 2, 3),
+    mode!(1, 0, 2, 0, 7, 8, 0, 0, 2, 2),
+    mode!(1, 0, 0, 0, 7, 7, 1, 0, 4, 0),
+    mode!(2, 6, 0, 0, 5, 5, 1, 0, 2, 0),
+];
+
+/// Two subset partitions, bit `i` is the subset of texel `i`. BC6H uses the
+/// first 32.
+#[rustfmt::skip]
+pub(super) static PARTITIONS2: [u16; 64] = [
+    0xcccc, 0x8888, 0xeeee, 0xecc8, 0xc880, 0xfeec, 0xfec8, 0xec80,
+    0xc800, 0xffec, 0xfe80, 0xe800, 0xffe8, 0xff00, 0xfff0, 0xf000,
+    0xf710, 0x008e, 0x7100, 0x08ce, 0x008c, 0x7310, 0x3100, 0x8cce,
+    0x088c, 0x3110, 0x6666, 0x366c, 0x17e8, 0x0ff0, 0x718e, 0x399c,
+    0xaaaa, 0xf0f0, 0x5a5a, 0x33cc, 0x3c3c, 0x55aa, 0x9696, 0xa55a,
+    0x73ce, 0x13c8, 0x324c, 0x3bdc, 0x6996, 0xc33c, 0x9966, 0x0660,
+    0x0272, 0x04e4, 0x4e40, 0x2720, 0xc936, 0x936c, 0x39c6, 0x639c,
+    0x9336, 0x9cc6, 0x817e, 0xe718, 0xccf0, 0x0fcc, 0x7744, 0xee22,
+];
+
+/// The anchor texel of the second subset of each two subset partition.
+#[rustfmt::skip]
+pub(super) static ANCHORS2: [u8; 64] = [
+    15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15,
+    15,  2,  8,  2,  2,  8,  8, 15,  2,  8,  2,  2,  8,  8,  2,  2,
+    15, 15,  6,  8,  2,  8, 15, 15,  2,  8,  2,  2,  2, 15, 15,  6,
+     6,  2,  6,  8, 15, 15,  2,  2, 15, 15, 15, 15, 15,  2,  2, 15,
+];
+
+#[rustfmt::skip]
+static PARTITIONS3: [[u8; 16]; 64] = [
+    [0, 0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 1, 2, 2, 2, 2],
+    [0, 0, 0, 1, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2, 2, 1],
+    [0, 0, 0, 0, 2, 0, 0, 1, 2, 2, 1, 1, 2, 2, 1, 1],
+    [0, 2, 2, 2, 0, 0, 2, 2, 0, 0, 1, 1, 0, 1, 1, 1],
+    [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2],
+    [0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 2, 2, 0, 0, 2, 2],
+    [0, 0, 2, 2, 0, 0, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1],
+    [0, 0, 1, 1, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2, 1, 1],
+    [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2],
+    [0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2],
+    [0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2],
+    [0, 1, 1, 2, 0, 1, 1, 2, 0, 1, 1, 2, 0, 1, 1, 2],
+    [0, 1, 2, 2, 0, 1, 2, 2, 0, 1, 2, 2, 0, 1, 2, 2],
+    [0, 0, 1, 1, 0, 1, 1, 2, 1, 1, 2, 2, 1, 2, 2, 2],
+    [0, 0, 1, 1, 2, 0, 0, 1, 2, 2, 0, 0, 2, 2, 2, 0],
+    [0, 0, 0, 1, 0, 0, 1, 1, 0, 1, 1, 2, 1, 1, 2, 2],
+    [0, 1, 1, 1, 0, 0, 1, 1, 2, 0, 0, 1, 2, 2, 0, 0],
+    [0, 0, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2, 1, 1, 2, 2],
+    [0, 0, 2, 2, 0, 0, 2, 2, 0, 0, 2, 2, 1, 1, 1, 1],
+    [0, 1, 1, 1, 0, 1, 1, 1, 0, 2, 2, 2, 0, 2, 2, 2],
+    [0, 0, 0, 1, 0, 0, 0, 1, 2, 2, 2, 1, 2, 2, 2, 1],
+    [0, 0, 0, 0, 0, 0, 1, 1, 0, 1, 2, 2, 0, 1, 2, 2],
+    [0, 0, 0, 0, 1, 1, 0, 0, 2, 2, 1, 0, 2, 2, 1, 0],
+    [0, 1, 2, 2, 0, 1, 2, 2, 0, 0, 1, 1, 0, 0, 0, 0],
+    [0, 0, 1, 2, 0, 0, 1, 2, 1, 1, 2, 2, 2, 2, 2, 2],
+    [0, 1, 1, 0, 1, 2, 2, 1, 1, 2, 2, 1, 0, 1, 1, 0],
+    [0, 0, 0, 0, 0, 1, 1, 0, 1, 2, 2, 1, 1, 2, 2, 1],
+    [0, 0, 2, 2, 1, 1, 0, 2, 1, 1, 0, 2, 0, 0, 2, 2],
+    [0, 1, 1, 0, 0, 1, 1, 0, 2, 0, 0, 2, 2, 2, 2, 2],
+    [0, 0, 1, 1, 0, 1, 2, 2, 0, 1, 2, 2, 0, 0, 1, 1],
+    [0, 0, 0, 0, 2, 0, 0, 0, 2, 2, 1, 1, 2, 2, 2, 1],
+    [0, 0, 0, 0, 0, 0, 0, 2, 1, 1, 2, 2, 1, 2, 2, 2],
+    [0, 2, 2, 2, 0, 0, 2, 2, 0, 0, 1, 2, 0, 0, 1, 1],
+    [0, 0, 1, 1, 0, 0, 1, 2, 0, 0, 2, 2, 0, 2, 2, 2],
+    [0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2, 0],
+    [0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 0, 0, 0, 0],
+    [0, 1, 2, 0, 1, 2, 0, 1, 2, 0, 1, 2, 0, 1, 2, 0],
+    [0, 1, 2, 0, 2, 0, 1, 2, 1, 2, 0, 1, 0, 1, 2, 0],
+    [0, 0, 1, 1, 2, 2, 0, 0, 1, 1, 2, 2, 0, 0, 1, 1],
+    [0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 0, 0, 0, 0, 1, 1],
+    [0, 1, 0, 1, 0, 1, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 0, 0, 0, 0, 0, 0, 0, 2, 1, 2, 1, 2, 1, 2, 1],
+    [0, 0, 2, 2, 1, 1, 2, 2, 0, 0, 2, 2, 1, 1, 2, 2],
+    [0, 0, 2, 2, 0, 0, 1, 1, 0, 0, 2, 2, 0, 0, 1, 1],
+    [0, 2, 2, 0, 1, 2, 2, 1, 0, 2, 2, 0, 1, 2, 2, 1],
+    [0, 1, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2, 0, 1, 0, 1],
+    [0, 0, 0, 0, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1],
+    [0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 2, 2, 2, 2],
+    [0, 2, 2, 2, 0, 1, 1, 1, 0, 2, 2, 2, 0, 1, 1, 1],
+    [0, 0, 0, 2, 1, 1, 1, 2, 0, 0, 0, 2, 1, 1, 1, 2],
+    [0, 0, 0, 0, 2, 1, 1, 2, 2, 1, 1, 2, 2, 1, 1, 2],
+    [0, 2, 2, 2, 0, 1, 1, 1, 0, 1, 1, 1, 0, 2, 2, 2],
+    [0, 0, 0, 2, 1, 1, 1, 2, 1, 1, 1, 2, 0, 0, 0, 2],
+    [0, 1, 1, 0, 0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 2, 2],
+    [0, 0, 0, 0, 0, 0, 0, 0, 2, 1, 1, 2, 2, 1, 1, 2],
+    [0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 0, 2, 2, 0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 2, 2],
+    [0, 0, 2, 2, 1, 1, 2, 2, 1, 1, 2, 2, 0, 0, 2, 2],
+    [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 1, 1, 2],
+    [0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 1],
+    [0, 2, 2, 2, 1, 2, 2, 2, 0, 2, 2, 2, 1, 2, 2, 2],
+    [0, 1, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 1, 1, 1, 2, 0, 1, 1, 2, 2, 0, 1, 2, 2, 2, 0],
+];
+
+/// The anchor texels of the second and third subsets of each three subset
+/// partition.
+#[rustfmt::skip]
+static ANCHORS3: [[u8; 64]; 2] = [
+    [
+         3,  3, 15, 15,  8,  3, 15, 15,  8,  8,  6,  6,  6,  5,  3,  3,
+         3,  3,  8, 15,  3,  3,  6, 10,  5,  8,  8,  6,  8,  5, 15, 15,
+         8, 15,  3,  5,  6, 10,  8, 15, 15,  3, 15,  5, 15, 15, 15, 15,
+         3, 15,  5,  5,  5,  8,  5, 10,  5, 10,  8, 13, 15, 12,  3,  3,
+    ],
+    [
+        15,  8,  8,  3, 15, 15,  3,  8, 15, 15, 15, 15, 15, 15, 15,  8,
+        15,  8, 15,  3, 15,  8, 15,  8,  3, 15,  6, 10, 15, 15, 10,  8,
+        15,  3, 15, 10, 10,  8,  9, 10,  6, 15,  8, 15,  3,  6,  6,  8,
+        15,  3, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15,  3, 15, 15,  8,
+    ],
+];
+
+static WEIGHTS2: [u32; 4] = [0, 21, 43, 64];
+pub(super) static WEIGHTS3: [u32; 8] = [0, 9, 18, 27, 37, 46, 55, 64];
+pub(super) static WEIGHTS4: [u32; 16] =
+    [0, 4, 9, 13, 17, 21, 26, 30, 34, 38, 43, 47, 51, 55, 60, 64];
+
+/// Interpolate between two endpoints with the weight for `index`.
+fn interpolate(e0: u32, e1: u32, index: u32, bits: u32) -> u32 {
+    let weight = match bits {
+        2 => WEIGHTS2[index as usize],
+        3 => WEIGHTS3[index as usize],
+        _ => WEIGHTS4[index as usize],
+    };
+    (e0 * (64 - weight) + e1 * weight + 32) >> 6
+}
+
+/// The subset texel `i` belongs to, and whether it is an anchor texel.
+fn subset(subsets: usize, partition: usize, i: usize) -> (usize, bool) {
+    match subsets {
+        1 => (0, i == 0),
+        2 => {
+            let subset = (PARTITIONS2[partition] >> i) as usize & 1;
+            (subset, i == 0 || i == ANCHORS2[partition] as usize)
+        }
+        _ => {
+            let anchors = [0, ANCHORS3[0][partition], ANCHORS3[1][partition]];
+            let subset = PARTITIONS3[partition][i] as usize;
+            (subset, i == anchors[subset] as usize)
+        }
+    }
+}
+
+/// Decode a 16-byte BC7 block to 8-bit RGBA. Reserved modes decode to zero
+/// in every channel.
+pub(super) fn decode(block: &[u8]) -> [[u8; 4]; 16] {
+    let mut bits = BitReader::new(block);
+    let mode = match (0..8).find(|_| bits.read(1) == 1) {
+        Some(mode) => &MODES[mode],
+        None => return [[0; 4]; 16],
+    };
+
+    let partition = bits.read(mode.partition_bits) as usize;
+    let rotation = bits.read(mode.rotation_bits);
+    let index_select = bits.read(mode.index_select_bits);
+
+    // Endpoints are stored channel by channel, then the P-bits follow.
+    let mut endpoints = [[[0u32; 4]; 2]; 3];
+    for channel in 0..4 {
+        let channel_bits = if channel < 3 {
+            mode.color_bits
+        } else {
+            mode.alpha_bits
+        };
+        for subset in &mut endpoints[..mode.subsets] {
+            for endpoint in subset.iter_mut() {
+                endpoint[channel] = bits.read(channel_bits);
+            }
+        }
+    }
+
+    let mut precision = [
+        mode.color_bits,
+        mode.color_bits,
+        mode.color_bits,
+        mode.alpha_bits,
+    ];
+    if mode.endpoint_pbits || mode.shared_pbits {
+        for subset in &mut endpoints[..mode.subsets] {
+            let shared = if mode.shared_pbits { bits.read(1) } else { 0 };
+            for endpoint in subset.iter_mut() {
+                let pbit = if mode.endpoint_pbits {
+                    bits.read(1)
+                } else {
+                    shared
+                };
+                for value in endpoint.iter_mut() {
+                    *value = (*value << 1) | pbit;
+                }
+            }
+        }
+        for p in precision.iter_mut().filter(|p| **p != 0) {
+            *p += 1;
+        }
+    }
+
+    for subset in &mut endpoints[..mode.subsets] {
+        for endpoint in subset.iter_mut() {
+            for (value, &bits) in endpoint.iter_mut().zip(&precision) {
+                *value = if bits == 0 {
+                    255
+                } else {
+                    (*value << (8 - bits)) | (*value >> (2 * bits - 8))
+                };
+            }
+        }
+    }
+
+    let mut indices = [0u32; 16];
+    for (i, index) in indices.iter_mut().enumerate() {
+        let anchor = subset(mode.subsets, partition, i).1;
+        *index = bits.read(mode.index_bits - anchor as u32);
+    }
+    let mut indices2 = [0u32; 16];
+    if mode.index2_bits != 0 {
+        for (i, index) in indices2.iter_mut().enumerate() {
+            *index = bits.read(mode.index2_bits - (i == 0) as u32);
+        }
+    }
+
+    let mut texels = [[0u8; 4]; 16];
+    for (i, texel) in texels.iter_mut().enumerate() {
+        let [e0, e1] = endpoints[subset(mode.subsets, partition, i).0];
+
+        let (color, alpha) = if mode.index2_bits == 0 {
+            ((indices[i], mode.index_bits), (indices[i], mode.index_bits))
+        } else if index_select == 0 {
+            (
+                (indices[i], mode.index_bits),
+                (indices2[i], mode.index2_bits),
+            )
+        } else {
+            (
+                (indices2[i], mode.index2_bits),
+                (indices[i], mode.index_bits),
+            )
+        };
+
+        for c in 0..3 {
+            texel[c] = interpolate(e0[c], e1[c], color.0, color.1) as u8;
+        }
+        texel[3] = interpolate(e0[3], e1[3], alpha.0, alpha.1) as u8;
+
+        if rotation != 0 {
+            texel.swap(3, rotation as usize - 1);
+        }
+    }
+    texels
+}
+
+#[cfg(test)]
+use super::BitWriter;
+
+#[cfg(test)]
+fn unquantize(value: u32, bits: u32) -> u8 {
+    ((value << (8 - bits)) | (value >> (2 * bits - 8))) as u8
+}
+
+#[cfg(test)]
+#[test]
+fn partition_anchors() {
+    for partition in 0..64 {
+        assert_eq!(subset(2, partition, 0), (0, true));
+        let anchor = ANCHORS2[partition] as usize;
+        assert_eq!(subset(2, partition, anchor), (1, true));
+
+        assert_eq!(subset(3, partition, 0), (0, true));
+        for (k, anchors) in ANCHORS3.iter().enumerate() {
+            let anchor = anchors[partition] as usize;
+            assert_eq!(subset(3, partition, anchor), (k + 1, true));
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn solid_blocks_all_modes() {
+    // (subsets, partition bits, rotation bits, index select bits, color bits,
+    // alpha bits, endpoint P-bits, shared P-bits) straight from the spec.
+    let modes = [
+        (3, 4, 0, 0, 4, 0, true, false),
+        (2, 6, 0, 0, 6, 0, false, true),
+        (3, 6, 0, 0, 5, 0, false, false),
+        (2, 6, 0, 0, 7, 0, true, false),
+        (1, 0, 2, 1, 5, 6, false, false),
+        (1, 0, 2, 0, 7, 8, false, false),
+        (1, 0, 0, 0, 7, 7, true, false),
+        (2, 6, 0, 0, 5, 5, true, false),
+    ];
+
+    for (mode, &(subsets, pb, rb, isb, cb, ab, epb, spb)) in modes.iter().enumerate() {
+        // Each subset gets a different color, using partition 0.
+        let raw = |subset: usize, channel: usize| {
+            let bits = if channel == 3 { ab } else { cb };
+            let max = (1u32 << bits) - 1;
+            [max, 0, max / 2, max / 3][(subset + channel) % 4]
+        };
+
+        let mut writer = BitWriter::new();
+        writer.put(1 << mode, mode as u32 + 1);
+        writer.put(0, pb + rb + isb);
+        for channel in 0..4 {
+            let bits = if channel == 3 { ab } else { cb };
+            for subset in 0..subsets {
+                writer.put(raw(subset, channel), bits);
+                writer.put(raw(subset, channel), bits);
+            }
+        }
+        let pbits = if epb {
+            subsets * 2
+        } else if spb {
+            subsets
+        } else {
+            0
+        };
+        writer.put((1 << pbits) - 1, pbits as u32);
+        let texels = decode(&writer.finish());
+
+        let expected = |subset: usize| {
+            let mut color = [255u8; 4];
+            for (channel, value) in color.iter_mut().enumerate() {
+                let bits = if channel == 3 { ab } else { cb };
+                if bits == 0 {
+                    continue;
+                }
+                *value = if epb || spb {
+                    unquantize(raw(subset, channel) << 1 | 1, bits + 1)
+                } else {
+                    unquantize(raw(subset, channel), bits)
+                };
+            }
+            color
+        };
+
+        for (i, texel) in texels.iter().enumerate() {
+            let subset = match subsets {
+                1 => 0,
+                2 => (0xccccu16 >> i) as usize & 1,
+                _ => [0, 0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 1, 2, 2, 2, 2][i],
+            };
+            assert_eq!(*texel, expected(subset), "mode {} texel {}", mode, i);
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn mode6_interpolation() {
+    let mut writer = BitWriter::new();
+    writer.put(1 << 6, 7);
+    for _ in 0..3 {
+        writer.put(0, 7);
+        writer.put(127, 7);
+    }
+    writer.put(127, 7);
+    writer.put(127, 7);
+    writer.put(0b10, 2);
+    for i in 0..16 {
+        writer.put(i, if i == 0 { 3 } else { 4 });
+    }
+
+    let texels = decode(&writer.finish());
+    let reds: Vec<u8> = texels.iter().map(|t| t[0]).collect();
+    assert_eq!(
+        reds,
+        [0, 16, 36, 52, 68, 84, 104, 120, 135, 151, 171, 187, 203, 219, 239, 255]
+    );
+    assert!(texels.iter().all(|t| t[1] == t[0] && t[2] == t[0]));
+}
+
+#[cfg(test)]
+#[test]
+fn mode2_anchors() {
+    let indices = [1, 1, 2, 1, 0, 1, 2, 3, 3, 2, 1, 0, 1, 2, 3, 1];
+
+    let mut writer = BitWriter::new();
+    writer.put(0b100, 3);
+    writer.put(0, 6);
+    for _ in 0..9 {
+        writer.put(0, 5);
+        writer.put(31, 5);
+    }
+    for (i, &index) in indices.iter().enumerate() {
+        let anchor = i == 0 || i == 3 || i == 15;
+        writer.put(index, if anchor { 1 } else { 2 });
+    }
+
+    let texels = decode(&writer.finish());
+    let reds: Vec<u8> = texels.iter().map(|t| t[0]).collect();
+    assert_eq!(
+        reds,
+        [84, 84, 171, 84, 0, 84, 171, 255, 255, 171, 84, 0, 84, 171, 255, 84]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn mode4_rotation_and_index_select() {
+    let mut writer = BitWriter::new();
+    writer.put(1 << 4, 5);
+    writer.put(1, 2);
+    writer.put(1, 1);
+    for _ in 0..3 {
+        writer.put(0, 5);
+        writer.put(31, 5);
+    }
+    writer.put(63, 6);
+    writer.put(63, 6);
+    writer.put(0, 31);
+    for i in 0..16 {
+        writer.put(i % 8, if i == 0 { 2 } else { 3 });
+    }
+
+    let texels = decode(&writer.finish());
+    let ramp = [0, 36, 72, 108, 147, 183, 219, 255];
+    for (i, texel) in texels.iter().enumerate() {
+        let value = ramp[i % 8];
+        assert_eq!(*texel, [255, value, value, value]);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn mode5_separate_alpha() {
+    let mut writer = BitWriter::new();
+    writer.put(1 << 5, 6);
+    writer.put(0, 2);
+    for _ in 0..3 {
+        writer.put(0, 7);
+        writer.put(127, 7);
+    }
+    writer.put(255, 8);
+    writer.put(0, 8);
+    for i in 0..16 {
+        writer.put(i % 4, if i == 0 { 1 } else { 2 });
+    }
+    for i in 0..16 {
+        writer.put((i + 1) % 4, if i == 0 { 1 } else { 2 });
+    }
+
+    let texels = decode(&writer.finish());
+    let color = [0, 84, 171, 255];
+    let alpha = [255, 171, 84, 0];
+    for (i, texel) in texels.iter().enumerate() {
+        let c = color[i % 4];
+        assert_eq!(*texel, [c, c, c, alpha[(i + 1) % 4]]);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn reserved_mode() {
+    assert_eq!(decode(&[0; 16]), [[0; 4]; 16]);
+}
@@ -0,0 +1,651 @@
+use super::{alpha_palette, color_palette};
+use crate::codec::float::clamp;
+use crate::codec::CodecError;
+use crate::enums::Format;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+/// How much effort the block encoder spends searching for endpoints.
+pub enum BcQuality {
+    /// Endpoints taken from the bounding box of each block.
+    Fast,
+    /// Endpoints along the principal axis of each block, refined with one
+    /// least squares pass.
+    #[default]
+    Balanced,
+    /// Like `Balanced` with more refinement passes. Also tries the three
+    /// color mode of BC1 and the six value mode of BC4, keeping whichever
+    /// has the lowest error.
+    Best,
+}
+
+impl BcQuality {
+    fn passes(self) -> u32 {
+        match self {
+            BcQuality::Fast => 0,
+            BcQuality::Balanced => 1,
+            BcQuality::Best => 4,
+        }
+    }
+}
+
+/// Compress one 4x4 block of 8-bit RGBA texels (row-major) into `dst`.
+///
+/// Supports the unorm and sRGB variants of `Bc1` and `Bc3`, and `Bc4` and
+/// `Bc5` in unorm and snorm. sRGB input is compressed as stored, without
+/// converting to linear. `Bc1` makes texels with alpha below 128
+/// transparent. `Bc4` compresses the red channel and `Bc5` red and green;
+/// for snorm formats these are biased the same way
+/// [`decode_block_rgba8`][1] produces them, so 0 maps to -1 and 255 to 1.
+///
+/// `dst` must be at least 8 bytes for `Bc1` and `Bc4`, and 16 otherwise.
+///
+/// [1]: fn.decode_block_rgba8.html
+pub fn encode_block(
+    format: Format,
+    texels: &[[u8; 4]; 16],
+    quality: BcQuality,
+    dst: &mut [u8],
+) -> Result<(), CodecError> {
+    use crate::enums::Format::*;
+
+    let size = match format {
+        Bc1Unorm | Bc1UnormSrgb | Bc4Unorm | Bc4Snorm => 8,
+        Bc3Unorm | Bc3UnormSrgb | Bc5Unorm | Bc5Snorm => 16,
+        _ => return Err(CodecError::UnsupportedFormat(format)),
+    };
+    if dst.len() < size {
+        return Err(CodecError::BufferTooSmall);
+    }
+
+    let channel = |c: usize| {
+        let mut values = [0u8; 16];
+        for (value, texel) in values.iter_mut().zip(texels) {
+            *value = texel[c];
+        }
+        values
+    };
+
+    match format {
+        Bc1Unorm | Bc1UnormSrgb => dst[..8].copy_from_slice(&encode_color(texels, true, quality)),
+        Bc3Unorm | Bc3UnormSrgb => {
+            dst[..8].copy_from_slice(&encode_single(&channel(3), false, quality));
+            dst[8..16].copy_from_slice(&encode_color(texels, false, quality));
+        }
+        Bc4Unorm | Bc4Snorm => {
+            dst[..8].copy_from_slice(&encode_single(&channel(0), format == Bc4Snorm, quality));
+        }
+        _ => {
+            let snorm = format == Bc5Snorm;
+            dst[..8].copy_from_slice(&encode_single(&channel(0), snorm, quality));
+            dst[8..16].copy_from_slice(&encode_single(&channel(1), snorm, quality));
+        }
+    }
+    Ok(())
+}
+
+/// Compress a surface of tightly packed 8-bit RGBA texels, `width * height`
+/// long, into `dst`.
+///
+/// Rows of blocks are written with the row pitch reported by
+/// [`Format::surface_layout`][1], so `dst` must hold at least its
+/// `slice_pitch`. Edge blocks of surfaces that aren't a multiple of 4 are
+/// padded by repeating the last row and column. Supports the same formats as
+/// [`encode_block`][2].
+///
+/// [1]: ../enums/enum.Format.html#method.surface_layout
+/// [2]: fn.encode_block.html
+pub fn encode_surface(
+    format: Format,
+    src: &[[u8; 4]],
+    width: u32,
+    height: u32,
+    quality: BcQuality,
+    dst: &mut [u8],
+) -> Result<(), CodecError> {
+    if !format.info().is_block_compressed() {
+        return Err(CodecError::UnsupportedFormat(format));
+    }
+
+    let layout = format
+        .checked_surface_layout(width, height, 1)
+        .ok_or(CodecError::BufferTooSmall)?;
+    let (width, height) = (width as usize, height as usize);
+    if src.len() < width * height || dst.len() < layout.slice_pitch {
+        return Err(CodecError::BufferTooSmall);
+    }
+    if width == 0 || height == 0 {
+        return Ok(());
+    }
+
+    let block_size = format.info().bytes_per_element() as usize;
+    for by in 0..layout.num_rows as usize {
+        let row = &mut dst[by * layout.row_pitch..];
+        for (bx, block) in row[..layout.row_pitch].chunks_mut(block_size).enumerate() {
+            let mut texels = [[0; 4]; 16];
+            for (i, texel) in texels.iter_mut().enumerate() {
+                let x = (bx * 4 + i % 4).min(width - 1);
+                let y = (by * 4 + i / 4).min(height - 1);
+                *texel = src[y * width + x];
+            }
+            encode_block(format, &texels, quality, block)?;
+        }
+    }
+
+    Ok(())
+}
+
+type Color = [f32; 3];
+
+/// Encode the 8-byte color part of a BC1 or BC3 block.
+fn encode_color(texels: &[[u8; 4]; 16], bc1: bool, quality: BcQuality) -> [u8; 8] {
+    let mut colors = [[0.0; 3]; 16];
+    let mut transparent = [false; 16];
+    for (i, texel) in texels.iter().enumerate() {
+        for c in 0..3 {
+            colors[i][c] = f32::from(texel[c]) / 255.0;
+        }
+        transparent[i] = bc1 && texel[3] < 128;
+    }
+
+    let opaque: Vec<Color> = (0..16)
+        .filter(|&i| !transparent[i])
+        .map(|i| colors[i])
+        .collect();
+    if opaque.is_empty() {
+        return [0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff];
+    }
+
+    let (e0, e1) = if quality == BcQuality::Fast {
+        bounding_box(&opaque)
+    } else {
+        principal_axis(&opaque)
+    };
+
+    let has_transparent = transparent.iter().any(|&t| t);
+    let modes: &[bool] = if has_transparent {
+        &[true]
+    } else if bc1 && quality == BcQuality::Best {
+        &[false, true]
+    } else {
+        &[false]
+    };
+
+    let mut best: Option<ColorFit> = None;
+    for &three_color in modes {
+        let mut fit = fit_color(&colors, &transparent, e0, e1, bc1, three_color);
+        for _ in 0..quality.passes() {
+            let (r0, r1) = match refine_color(&colors, &transparent, &fit, three_color) {
+                Some(endpoints) => endpoints,
+                None => break,
+            };
+            let refined = fit_color(&colors, &transparent, r0, r1, bc1, three_color);
+            if refined.error >= fit.error {
+                break;
+            }
+            fit = refined;
+        }
+        match best {
+            Some(ref best) if best.error <= fit.error => {}
+            _ => best = Some(fit),
+        }
+    }
+
+    let fit = best.unwrap();
+    let mut block = [0; 8];
+    block[..2].copy_from_slice(&fit.c0.to_le_bytes());
+    block[2..4].copy_from_slice(&fit.c1.to_le_bytes());
+    let indices = fit
+        .indices
+        .iter()
+        .enumerate()
+        .fold(0u32, |bits, (i, &index)| bits | u32::from(index) << (2 * i));
+    block[4..].copy_from_slice(&indices.to_le_bytes());
+    block
+}
+
+struct ColorFit {
+    c0: u16,
+    c1: u16,
+    indices: [u8; 16],
+    error: f32,
+}
+
+/// Quantize a pair of endpoints and pick the best palette entry for each
+/// texel, using the palette the decoder will actually produce.
+fn fit_color(
+    colors: &[Color; 16],
+    transparent: &[bool; 16],
+    e0: Color,
+    e1: Color,
+    bc1: bool,
+    three_color: bool,
+) -> ColorFit {
+    let (mut c0, mut c1) = (quantize_565(e0), quantize_565(e1));
+    if (three_color && c0 > c1) || (!three_color && c0 < c1) {
+        std::mem::swap(&mut c0, &mut c1);
+    }
+    let palette = color_palette(c0, c1, bc1);
+
+    let mut indices = [0; 16];
+    let mut error = 0.0;
+    for i in 0..16 {
+        if transparent[i] {
+            indices[i] = 3;
+            continue;
+        }
+        let (index, e) = palette
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry[3] != 0.0)
+            .map(|(index, entry)| (index, distance(&entry[..3], &colors[i])))
+            .fold((0, f32::INFINITY), |a, b| if b.1 < a.1 { b } else { a });
+        indices[i] = index as u8;
+        error += e;
+    }
+
+    ColorFit {
+        c0,
+        c1,
+        indices,
+        error,
+    }
+}
+
+/// Least squares endpoints for the current index assignment.
+fn refine_color(
+    colors: &[Color; 16],
+    transparent: &[bool; 16],
+    fit: &ColorFit,
+    three_color: bool,
+) -> Option<(Color, Color)> {
+    let weights: [f32; 4] = if three_color {
+        [0.0, 1.0, 0.5, 0.0]
+    } else {
+        [0.0, 1.0, 1.0 / 3.0, 2.0 / 3.0]
+    };
+
+    let mut samples = [(0.0, 0.0); 16];
+    for (i, sample) in samples.iter_mut().enumerate() {
+        if !transparent[i] {
+            *sample = (weights[fit.indices[i] as usize], 1.0);
+        }
+    }
+
+    let mut e0 = [0.0; 3];
+    let mut e1 = [0.0; 3];
+    for c in 0..3 {
+        let values: Vec<f32> = colors.iter().map(|color| color[c]).collect();
+        let (a, b) = least_squares(&samples, &values)?;
+        e0[c] = clamp(a, 0.0, 1.0);
+        e1[c] = clamp(b, 0.0, 1.0);
+    }
+    Some((e0, e1))
+}
+
+/// Solve for the endpoints `a`, `b` minimizing the error of
+/// `a * (1 - t) + b * t` against `values`. Each sample is `(t, weight)`.
+fn least_squares(samples: &[(f32, f32)], values: &[f32]) -> Option<(f32, f32)> {
+    let (mut aa, mut ab, mut bb, mut ax, mut bx) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for (&(t, w), &x) in samples.iter().zip(values) {
+        let s = 1.0 - t;
+        aa += w * s * s;
+        ab += w * s * t;
+        bb += w * t * t;
+        ax += w * s * x;
+        bx += w * t * x;
+    }
+
+    let det = aa * bb - ab * ab;
+    if det.abs() < 1e-6 {
+        return None;
+    }
+    Some(((bb * ax - ab * bx) / det, (aa * bx - ab * ax) / det))
+}
+
+fn bounding_box(colors: &[Color]) -> (Color, Color) {
+    let mut min = [1.0f32; 3];
+    let mut max = [0.0f32; 3];
+    for color in colors {
+        for c in 0..3 {
+            min[c] = min[c].min(color[c]);
+            max[c] = max[c].max(color[c]);
+        }
+    }
+
+    // Pull the endpoints in a little, the interpolated colors cover the
+    // middle of the box better that way.
+    for c in 0..3 {
+        let inset = (max[c] - min[c]) / 16.0;
+        min[c] += inset;
+        max[c] -= inset;
+    }
+    (max, min)
+}
+
+fn principal_axis(colors: &[Color]) -> (Color, Color) {
+    let n = colors.len() as f32;
+    let mut mean = [0.0; 3];
+    for color in colors {
+        for c in 0..3 {
+            mean[c] += color[c] / n;
+        }
+    }
+
+    let mut cov = [[0.0f32; 3]; 3];
+    for color in colors {
+        let d = [color[0] - mean[0], color[1] - mean[1], color[2] - mean[2]];
+        for i in 0..3 {
+            for j in 0..3 {
+                cov[i][j] += d[i] * d[j];
+            }
+        }
+    }
+
+    // Power iteration, starting from the channel with the most variance.
+    let start = (0..3)
+        .max_by(|&a, &b| cov[a][a].partial_cmp(&cov[b][b]).unwrap())
+        .unwrap();
+    let mut axis = [0.0; 3];
+    axis[start] = 1.0;
+    for _ in 0..8 {
+        let mut next = [0.0; 3];
+        for i in 0..3 {
+            next[i] = cov[i][0] * axis[0] + cov[i][1] * axis[1] + cov[i][2] * axis[2];
+        }
+        let len = (next[0] * next[0] + next[1] * next[1] + next[2] * next[2]).sqrt();
+        if len < 1e-9 {
+            return bounding_box(colors);
+        }
+        axis = [next[0] / len, next[1] / len, next[2] / len];
+    }
+
+    let project = |color: &Color| (0..3).map(|c| (color[c] - mean[c]) * axis[c]).sum::<f32>();
+    let (mut lo, mut hi) = (f32::INFINITY, f32::NEG_INFINITY);
+    for color in colors {
+        let t = project(color);
+        lo = lo.min(t);
+        hi = hi.max(t);
+    }
+
+    let point = |t: f32| {
+        let mut color = [0.0; 3];
+        for c in 0..3 {
+            color[c] = clamp(mean[c] + axis[c] * t, 0.0, 1.0);
+        }
+        color
+    };
+    (point(hi), point(lo))
+}
+
+fn quantize_565(color: Color) -> u16 {
+    let r = (color[0] * 31.0).round() as u16;
+    let g = (color[1] * 63.0).round() as u16;
+    let b = (color[2] * 31.0).round() as u16;
+    r << 11 | g << 5 | b
+}
+
+fn distance(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| (a - b) * (a - b)).sum()
+}
+
+/// Encode an 8-byte BC4 style block for one channel.
+fn encode_single(values: &[u8; 16], snorm: bool, quality: BcQuality) -> [u8; 8] {
+    let mut targets = [0.0f32; 16];
+    for (target, &value) in targets.iter_mut().zip(values) {
+        let value = f32::from(value) / 255.0;
+        *target = if snorm { value * 2.0 - 1.0 } else { value };
+    }
+    let (lo, hi) = if snorm { (-1.0, 1.0) } else { (0.0, 1.0) };
+
+    let min = targets.iter().cloned().fold(hi, f32::min);
+    let max = targets.iter().cloned().fold(lo, f32::max);
+
+    // The eight value mode spans the whole range, the six value mode has
+    // exact extremes and spends its endpoints on everything in between.
+    let mut candidates = vec![(max, min, false)];
+    if quality != BcQuality::Fast {
+        let inner = targets.iter().filter(|&&t| t != lo && t != hi);
+        let inner_min = inner.clone().cloned().fold(hi, f32::min);
+        let inner_max = inner.cloned().fold(lo, f32::max);
+        if inner_min <= inner_max {
+            candidates.push((inner_min, inner_max, true));
+        }
+    }
+
+    let mut best: Option<SingleFit> = None;
+    for (e0, e1, six) in candidates {
+        let mut fit = fit_single(&targets, e0, e1, snorm, six);
+        for _ in 0..quality.passes() {
+            let (r0, r1) = match refine_single(&targets, &fit, six) {
+                Some(endpoints) => endpoints,
+                None => break,
+            };
+            let refined = fit_single(&targets, clamp(r0, lo, hi), clamp(r1, lo, hi), snorm, six);
+            if refined.error >= fit.error {
+                break;
+            }
+            fit = refined;
+        }
+        match best {
+            Some(ref best) if best.error <= fit.error => {}
+            _ => best = Some(fit),
+        }
+    }
+
+    let fit = best.unwrap();
+    let indices = fit
+        .indices
+        .iter()
+        .enumerate()
+        .fold(0u64, |bits, (i, &index)| bits | u64::from(index) << (3 * i));
+    let mut block = [0; 8];
+    block[0] = fit.a0;
+    block[1] = fit.a1;
+    block[2..].copy_from_slice(&indices.to_le_bytes()[..6]);
+    block
+}
+
+struct SingleFit {
+    a0: u8,
+    a1: u8,
+    indices: [u8; 16],
+    error: f32,
+}
+
+fn fit_single(targets: &[f32; 16], e0: f32, e1: f32, snorm: bool, six: bool) -> SingleFit {
+    let quantize = |value: f32| {
+        if snorm {
+            (value * 127.0).round() as i8 as u8
+        } else {
+            (value * 255.0).round() as u8
+        }
+    };
+    let (mut a0, mut a1) = (quantize(e0), quantize(e1));
+    let ordered = |a0: u8, a1: u8| {
+        if snorm {
+            (a0 as i8) <= (a1 as i8)
+        } else {
+            a0 <= a1
+        }
+    };
+    // The endpoint order selects the mode; equal endpoints are always the
+    // six value mode, which reproduces them exactly anyway.
+    if ordered(a0, a1) != six && a0 != a1 {
+        std::mem::swap(&mut a0, &mut a1);
+    }
+    let palette = alpha_palette(a0, a1, snorm);
+
+    let mut indices = [0; 16];
+    let mut error = 0.0;
+    for (index, &target) in indices.iter_mut().zip(targets) {
+        let (best, e) = palette
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| (i, (value - target) * (value - target)))
+            .fold((0, f32::INFINITY), |a, b| if b.1 < a.1 { b } else { a });
+        *index = best as u8;
+        error += e;
+    }
+
+    SingleFit {
+        a0,
+        a1,
+        indices,
+        error,
+    }
+}
+
+fn refine_single(targets: &[f32; 16], fit: &SingleFit, six: bool) -> Option<(f32, f32)> {
+    let mut samples = [(0.0, 0.0); 16];
+    for (sample, &index) in samples.iter_mut().zip(&fit.indices) {
+        *sample = match (index, six) {
+            (0, _) => (0.0, 1.0),
+            (1, _) => (1.0, 1.0),
+            (6, true) | (7, true) => (0.0, 0.0),
+            (i, true) => (f32::from(i - 1) / 5.0, 1.0),
+            (i, false) => (f32::from(i - 1) / 7.0, 1.0),
+        };
+    }
+    least_squares(&samples, targets)
+}
+
+#[cfg(test)]
+fn test_image(width: usize, height: usize, alpha: bool) -> Vec<[u8; 4]> {
+    let mut seed = 0x1234_5678u32;
+    let mut noise = move || {
+        seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        (seed >> 24) as i32 % 9 - 4
+    };
+
+    let mut image = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for x in 0..width {
+            let r = (x * 255 / (width - 1)) as i32 + noise();
+            let g = (y * 255 / (height - 1)) as i32 + noise();
+            let b = ((x + y) * 255 / (width + height - 2)) as i32 + noise();
+            let a = if alpha {
+                (((x as f32 / 5.0).sin() * 0.5 + 0.5) * 255.0) as i32
+            } else {
+                255
+            };
+            let clamp = |v: i32| v.clamp(0, 255) as u8;
+            image.push([clamp(r), clamp(g), clamp(b), clamp(a)]);
+        }
+    }
+    image
+}
+
+#[cfg(test)]
+fn psnr(format: Format, quality: BcQuality, channels: &[usize]) -> f64 {
+    let (width, height) = (30, 34);
+    let image = test_image(width, height, channels.contains(&3));
+    let layout = format.surface_layout(width as u32, height as u32, 1);
+
+    let mut blocks = vec![0; layout.slice_pitch];
+    encode_surface(
+        format,
+        &image,
+        width as u32,
+        height as u32,
+        quality,
+        &mut blocks,
+    )
+    .unwrap();
+    let mut decoded = vec![[0; 4]; width * height];
+    super::decode_surface_rgba8(
+        format,
+        &blocks,
+        layout.row_pitch,
+        width as u32,
+        height as u32,
+        &mut decoded,
+    )
+    .unwrap();
+
+    let mut error = 0.0;
+    for (a, b) in image.iter().zip(&decoded) {
+        for &c in channels {
+            let d = f64::from(a[c]) - f64::from(b[c]);
+            error += d * d;
+        }
+    }
+    let mse = error / (image.len() * channels.len()) as f64;
+    10.0 * (255.0 * 255.0 / mse).log10()
+}
+
+#[cfg(test)]
+#[test]
+fn psnr_regression() {
+    let cases: &[(Format, &[usize], [f64; 3])] = &[
+        (Format::Bc1Unorm, &[0, 1, 2], [32.0, 32.5, 32.5]),
+        (Format::Bc3Unorm, &[0, 1, 2, 3], [33.0, 33.5, 33.5]),
+        (Format::Bc4Unorm, &[0], [46.0, 46.5, 46.5]),
+        (Format::Bc4Snorm, &[0], [46.0, 46.5, 46.5]),
+        (Format::Bc5Unorm, &[0, 1], [46.0, 46.5, 46.5]),
+        (Format::Bc5Snorm, &[0, 1], [46.0, 46.5, 46.5]),
+    ];
+
+    for &(format, channels, minimums) in cases {
+        let results: Vec<f64> = [BcQuality::Fast, BcQuality::Balanced, BcQuality::Best]
+            .iter()
+            .map(|&quality| psnr(format, quality, channels))
+            .collect();
+        for (value, minimum) in results.iter().zip(&minimums) {
+            assert!(value >= minimum, "{:?}: {:?}", format, results);
+        }
+        assert!(results[0] <= results[1], "{:?}: {:?}", format, results);
+        assert!(results[1] <= results[2], "{:?}: {:?}", format, results);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn exact_blocks() {
+    // Solid blocks and two color blocks must survive exactly.
+    let mut texels = [[255, 0, 0, 255]; 16];
+    for texel in &mut texels[8..] {
+        *texel = [0, 0, 255, 255];
+    }
+    let mut block = [0; 8];
+    encode_block(Format::Bc1Unorm, &texels, BcQuality::Balanced, &mut block).unwrap();
+    assert_eq!(
+        super::decode_block_rgba8(Format::Bc1Unorm, &block).unwrap(),
+        texels
+    );
+
+    // Transparent texels select the three color mode.
+    texels[3] = [0, 0, 0, 0];
+    encode_block(Format::Bc1Unorm, &texels, BcQuality::Balanced, &mut block).unwrap();
+    let decoded = super::decode_block_rgba8(Format::Bc1Unorm, &block).unwrap();
+    assert_eq!(decoded, texels);
+
+    let values = [0u8, 255, 17, 200];
+    let texels: Vec<[u8; 4]> = (0..16).map(|i| [values[i % 4], 0, 0, 255]).collect();
+    let mut input = [[0; 4]; 16];
+    input.copy_from_slice(&texels);
+    encode_block(Format::Bc4Unorm, &input, BcQuality::Best, &mut block).unwrap();
+    let decoded = super::decode_block_rgba8(Format::Bc4Unorm, &block).unwrap();
+    for (a, b) in decoded.iter().zip(&input) {
+        assert!((i32::from(a[0]) - i32::from(b[0])).abs() <= 1);
+    }
+
+    assert_eq!(
+        encode_block(Format::Bc7Unorm, &input, BcQuality::Fast, &mut block),
+        Err(CodecError::UnsupportedFormat(Format::Bc7Unorm))
+    );
+    assert_eq!(
+        encode_block(Format::Bc3Unorm, &input, BcQuality::Fast, &mut block),
+        Err(CodecError::BufferTooSmall)
+    );
+    assert_eq!(
+        encode_surface(
+            Format::Bc1Unorm,
+            &[],
+            u32::MAX,
+            4,
+            BcQuality::Fast,
+            &mut block
+        ),
+        Err(CodecError::BufferTooSmall)
+    );
+}
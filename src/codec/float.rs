@@ -0,0 +1,232 @@
+//! Conversions between `f32` and the reduced precision encodings used by
+//! DXGI formats.
+
+/// Convert an IEEE 754 half-precision float to `f32`. Denormals, infinities
+/// and NaNs are preserved.
+pub fn f16_to_f32(half: u16) -> f32 {
+    let sign = ((half & 0x8000) as u32) << 16;
+    let exp = ((half >> 10) & 0x1f) as u32;
+    let mant = (half & 0x3ff) as u32;
+
+    match exp {
+        0 => {
+            let value = mant as f32 / (1 << 24) as f32;
+            if sign != 0 {
+                -value
+            } else {
+                value
+            }
+        }
+        0x1f => f32::from_bits(sign | 0x7f80_0000 | (mant << 13)),
+        _ => f32::from_bits(sign | ((exp + 112) << 23) | (mant << 13)),
+    }
+}
+
+/// Convert an `f32` to an IEEE 754 half-precision float, rounding to the
+/// nearest representable value (ties to even). Values too large for a half
+/// become infinity.
+pub fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+
+    if value.is_nan() {
+        return sign | 0x7e00;
+    }
+
+    sign | encode_minifloat(bits & 0x7fff_ffff, 10) as u16
+}
+
+/// Convert one of the unsigned 11-bit or 10-bit floats used by
+/// `R11G11B10Float` (5-bit exponent, `mant_bits` bits of mantissa) to `f32`.
+pub fn ufloat_to_f32(value: u32, mant_bits: u32) -> f32 {
+    let exp = (value >> mant_bits) & 0x1f;
+    let mant = value & ((1 << mant_bits) - 1);
+    let scale = (1 << mant_bits) as f32;
+
+    match exp {
+        0 => mant as f32 / scale / (1 << 14) as f32,
+        0x1f if mant == 0 => f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => (1.0 + mant as f32 / scale) * 2f32.powi(exp as i32 - 15),
+    }
+}
+
+/// Convert an `f32` to an unsigned 11-bit or 10-bit float. Negative values
+/// become 0 and values too large to be represented are clamped to the
+/// largest finite value, matching the Direct3D conversion rules.
+pub fn f32_to_ufloat(value: f32, mant_bits: u32) -> u32 {
+    let max_finite = (0x1e << mant_bits) | ((1 << mant_bits) - 1);
+    if value.is_nan() {
+        (0x1f << mant_bits) | (1 << (mant_bits - 1))
+    } else if value.is_infinite() && value > 0.0 {
+        0x1f << mant_bits
+    } else if value <= 0.0 {
+        0
+    } else {
+        std::cmp::min(encode_minifloat(value.to_bits(), mant_bits), max_finite)
+    }
+}
+
+/// Round the absolute value of an `f32` (given as bits) to a float with a
+/// 5-bit exponent (bias 15) and `mant_bits` bits of mantissa. Overflow
+/// produces infinity.
+fn encode_minifloat(bits: u32, mant_bits: u32) -> u32 {
+    let exp = (bits >> 23) as i32 - 127 + 15;
+    let mant = bits & 0x7f_ffff;
+    let drop = 23 - mant_bits;
+
+    if exp >= 0x1f {
+        return 0x1f << mant_bits;
+    }
+
+    let (value, rem, shift) = if exp <= 0 {
+        // Denormal result, shift the implicit one into the mantissa.
+        let shift = drop + 1 + (-exp) as u32;
+        if shift > 24 {
+            return 0;
+        }
+        let mant = mant | 0x80_0000;
+        (mant >> shift, mant & ((1 << shift) - 1), shift)
+    } else {
+        let value = ((exp as u32) << mant_bits) | (mant >> drop);
+        (value, mant & ((1 << drop) - 1), drop)
+    };
+
+    let halfway = 1 << (shift - 1);
+    if rem > halfway || (rem == halfway && value & 1 == 1) {
+        // A carry out of the mantissa correctly bumps the exponent.
+        value + 1
+    } else {
+        value
+    }
+}
+
+/// Convert an sRGB encoded value in `[0, 1]` to linear light.
+pub fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Convert a linear light value to sRGB encoding. The value is clamped to
+/// `[0, 1]` first.
+pub fn linear_to_srgb(value: f32) -> f32 {
+    let value = clamp(value, 0.0, 1.0);
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Decode an `R9G9B9E5SharedExponent` element.
+pub fn rgb9e5_to_f32(packed: u32) -> [f32; 3] {
+    let exp = (packed >> 27) as i32;
+    let scale = 2f32.powi(exp - 15 - 9);
+    [
+        (packed & 0x1ff) as f32 * scale,
+        ((packed >> 9) & 0x1ff) as f32 * scale,
+        ((packed >> 18) & 0x1ff) as f32 * scale,
+    ]
+}
+
+/// Encode an `R9G9B9E5SharedExponent` element. Negative values and NaNs
+/// become 0, and values larger than the format can hold are clamped.
+pub fn f32_to_rgb9e5(rgb: [f32; 3]) -> u32 {
+    const MAX: f32 = 511.0 / 512.0 * 65536.0;
+
+    let r = clamp(rgb[0], 0.0, MAX);
+    let g = clamp(rgb[1], 0.0, MAX);
+    let b = clamp(rgb[2], 0.0, MAX);
+    let max_c = r.max(g).max(b);
+
+    let mut exp = if max_c > 0.0 {
+        (max_c.log2().floor() as i32).max(-16) + 16
+    } else {
+        0
+    };
+    let mut denom = 2f32.powi(exp - 15 - 9);
+    if (max_c / denom + 0.5).floor() as u32 == 512 {
+        denom *= 2.0;
+        exp += 1;
+    }
+
+    let quantize = |v: f32| std::cmp::min((v / denom + 0.5).floor() as u32, 511);
+    quantize(r) | (quantize(g) << 9) | (quantize(b) << 18) | ((exp as u32) << 27)
+}
+
+/// Clamp a value to a range, mapping NaN to `min`.
+#[inline]
+pub(crate) fn clamp(value: f32, min: f32, max: f32) -> f32 {
+    if value >= min {
+        if value <= max {
+            value
+        } else {
+            max
+        }
+    } else {
+        min
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn half_conversions() {
+    assert_eq!(f32_to_f16(1.0), 0x3c00);
+    assert_eq!(f32_to_f16(-2.0), 0xc000);
+    assert_eq!(f32_to_f16(65504.0), 0x7bff);
+    assert_eq!(f32_to_f16(65520.0), 0x7c00);
+    assert_eq!(f32_to_f16(5.960_464_5e-8), 0x0001);
+    assert_eq!(f32_to_f16(1.0 + 1.0 / 2048.0), 0x3c00);
+    assert_eq!(f32_to_f16(1.0 + 3.0 / 2048.0), 0x3c02);
+    assert_eq!(f32_to_f16(f32::INFINITY), 0x7c00);
+    assert!(f16_to_f32(f32_to_f16(f32::NAN)).is_nan());
+
+    for half in 0..=0xffffu16 {
+        let value = f16_to_f32(half);
+        if !value.is_nan() {
+            assert_eq!(f32_to_f16(value), half);
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn ufloat_conversions() {
+    assert_eq!(f32_to_ufloat(1.0, 6), 15 << 6);
+    assert_eq!(f32_to_ufloat(-1.0, 6), 0);
+    assert_eq!(f32_to_ufloat(1.0e9, 6), 0x7bf);
+    assert_eq!(f32_to_ufloat(1.0e9, 5), 0x3df);
+    assert_eq!(f32_to_ufloat(f32::INFINITY, 5), 0x3e0);
+
+    for value in 0..0x7c0 {
+        assert_eq!(f32_to_ufloat(ufloat_to_f32(value, 6), 6), value);
+    }
+    for value in 0..0x3e0 {
+        assert_eq!(f32_to_ufloat(ufloat_to_f32(value, 5), 5), value);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn shared_exponent() {
+    let packed = f32_to_rgb9e5([1.0, 0.5, 0.25]);
+    assert_eq!(rgb9e5_to_f32(packed), [1.0, 0.5, 0.25]);
+    assert_eq!(f32_to_rgb9e5([0.0, 0.0, 0.0]) & 0x7ff_ffff, 0);
+    assert_eq!(rgb9e5_to_f32(f32_to_rgb9e5([1.0e9, -1.0, 0.0]))[1], 0.0);
+    assert_eq!(rgb9e5_to_f32(f32_to_rgb9e5([1.0e9, 0.0, 0.0]))[0], 65408.0);
+}
+
+#[cfg(test)]
+#[test]
+fn srgb_transfer() {
+    assert_eq!(linear_to_srgb(0.0), 0.0);
+    assert!((linear_to_srgb(1.0) - 1.0).abs() < 1e-6);
+    assert_eq!((linear_to_srgb(0.5) * 255.0).round(), 188.0);
+    for i in 0..=255 {
+        let value = i as f32 / 255.0;
+        assert!((linear_to_srgb(srgb_to_linear(value)) - value).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,49 @@
+//! Pure Rust conversion of pixel data to and from the layouts described by
+//! [`Format`](../enums/enum.Format.html). Everything in here works on plain
+//! byte slices, so it is available on every target.
+
+use crate::enums::Format;
+
+use std::fmt;
+
+#[doc(inline)]
+pub use self::bc::{
+    decode_block, decode_block_rgba8, decode_surface, decode_surface_rgba8, encode_block,
+    encode_surface, BcQuality,
+};
+#[doc(inline)]
+pub use self::float::{
+    f16_to_f32, f32_to_f16, f32_to_rgb9e5, f32_to_ufloat, linear_to_srgb, rgb9e5_to_f32,
+    srgb_to_linear, ufloat_to_f32,
+};
+#[doc(inline)]
+pub use self::pixel::{decode_pixel, decode_row, encode_pixel, encode_row};
+#[doc(inline)]
+pub use self::yuv::{decode_yuv, encode_yuv, ChromaSiting, YuvMatrix, YuvOptions, YuvRange};
+
+mod bc;
+mod float;
+mod pixel;
+mod yuv;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+/// Errors produced while converting pixel data.
+pub enum CodecError {
+    /// The format isn't handled by the function it was passed to.
+    UnsupportedFormat(Format),
+    /// A source or destination buffer is smaller than the data requires.
+    BufferTooSmall,
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CodecError::UnsupportedFormat(format) => {
+                write!(fmt, "the format {:?} is not supported here", format)
+            }
+            CodecError::BufferTooSmall => fmt.write_str("the buffer is too small"),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
@@ -0,0 +1,500 @@
+use crate::codec::float::{self, clamp};
+use crate::codec::CodecError;
+use crate::enums::{Format, FormatChannel, FormatComponent, FormatInfo, NumericType};
+
+use std::convert::TryFrom;
+
+/// Decode a single pixel to RGBA.
+///
+/// Channels the format doesn't have default to `(0, 0, 0, 1)`. Depth is
+/// returned in red and stencil in green, the same way Direct3D exposes them
+/// to shaders. Integer formats return the integer value converted to `f32`,
+/// so 32-bit integers above 2<sup>24</sup> lose precision. sRGB formats are
+/// converted to linear light.
+///
+/// Block-compressed, planar, video, palettized and typeless formats are
+/// rejected, as are `R1Unorm`, `R8G8_B8G8Unorm` and `G8R8_G8B8Unorm` which
+/// pack several pixels into one element; use [`decode_row`][1] for those.
+///
+/// [1]: fn.decode_row.html
+pub fn decode_pixel(format: Format, src: &[u8]) -> Result<[f32; 4], CodecError> {
+    let info = element_info(format)?;
+    if info.block_width != 1 || info.bits_per_element < 8 {
+        return Err(CodecError::UnsupportedFormat(format));
+    }
+
+    let bytes = info.bytes_per_element() as usize;
+    if src.len() < bytes {
+        return Err(CodecError::BufferTooSmall);
+    }
+
+    Ok(decode_element(format, &info, read_element(&src[..bytes])))
+}
+
+/// Encode a single RGBA pixel into `dst`.
+///
+/// Values are clamped to the range of each channel: `[0, 1]` for unorm,
+/// `[-1, 1]` for snorm, and the representable range for integer and
+/// unsigned float channels. Integers are rounded to the nearest value. Linear
+/// values are converted to sRGB encoding for sRGB formats. Channels with
+/// no meaning (`X`) are written as zero.
+///
+/// Supports the same formats as [`decode_pixel`][1].
+///
+/// [1]: fn.decode_pixel.html
+pub fn encode_pixel(format: Format, rgba: [f32; 4], dst: &mut [u8]) -> Result<(), CodecError> {
+    let info = element_info(format)?;
+    if info.block_width != 1 || info.bits_per_element < 8 {
+        return Err(CodecError::UnsupportedFormat(format));
+    }
+
+    let bytes = info.bytes_per_element() as usize;
+    if dst.len() < bytes {
+        return Err(CodecError::BufferTooSmall);
+    }
+
+    write_element(encode_element(format, &info, rgba), &mut dst[..bytes]);
+    Ok(())
+}
+
+/// Decode a row of `dst.len()` pixels.
+///
+/// `src` must hold at least the row pitch for that width (see
+/// [`Format::surface_layout`][1]). In addition to the formats supported
+/// by [`decode_pixel`][2], this handles `R1Unorm` (most significant bit
+/// first) and the packed `R8G8_B8G8Unorm` / `G8R8_G8B8Unorm` formats,
+/// whose pairs of pixels share red and blue.
+///
+/// [1]: ../enums/enum.Format.html#method.surface_layout
+/// [2]: fn.decode_pixel.html
+pub fn decode_row(format: Format, src: &[u8], dst: &mut [[f32; 4]]) -> Result<(), CodecError> {
+    let info = element_info(format)?;
+    match row_size(format, dst.len()) {
+        Some(size) if src.len() >= size => (),
+        _ => return Err(CodecError::BufferTooSmall),
+    }
+
+    if info.bits_per_element == 1 {
+        for (i, pixel) in dst.iter_mut().enumerate() {
+            let bit = (src[i / 8] >> (7 - i % 8)) & 1;
+            *pixel = [bit as f32, 0.0, 0.0, 1.0];
+        }
+    } else if info.block_width == 2 {
+        let bytes = info.bytes_per_element() as usize;
+        for (pair, chunk) in dst.chunks_mut(2).zip(src.chunks(bytes)) {
+            let decoded = decode_pair(&info, read_element(chunk));
+            for (pixel, value) in pair.iter_mut().zip(&decoded) {
+                *pixel = *value;
+            }
+        }
+    } else {
+        let bytes = info.bytes_per_element() as usize;
+        for (pixel, chunk) in dst.iter_mut().zip(src.chunks(bytes)) {
+            *pixel = decode_element(format, &info, read_element(chunk));
+        }
+    }
+
+    Ok(())
+}
+
+/// Encode a row of `src.len()` pixels.
+///
+/// `dst` must hold at least the row pitch for that width. Supports the same
+/// formats as [`decode_row`][1]. `R1Unorm` sets a bit for red values of
+/// 0.5 and above, and the packed 2x1 formats store the average red and
+/// blue of each pair. Unused bits at the end of the row are zeroed.
+///
+/// [1]: fn.decode_row.html
+pub fn encode_row(format: Format, src: &[[f32; 4]], dst: &mut [u8]) -> Result<(), CodecError> {
+    let info = element_info(format)?;
+    let size = match row_size(format, src.len()) {
+        Some(size) if dst.len() >= size => size,
+        _ => return Err(CodecError::BufferTooSmall),
+    };
+
+    if info.bits_per_element == 1 {
+        for byte in &mut dst[..size] {
+            *byte = 0;
+        }
+        for (i, pixel) in src.iter().enumerate() {
+            if pixel[0] >= 0.5 {
+                dst[i / 8] |= 0x80 >> (i % 8);
+            }
+        }
+    } else if info.block_width == 2 {
+        let bytes = info.bytes_per_element() as usize;
+        for (pair, chunk) in src.chunks(2).zip(dst.chunks_mut(bytes)) {
+            let second = if pair.len() == 2 { pair[1] } else { pair[0] };
+            write_element(encode_pair(&info, [pair[0], second]), chunk);
+        }
+    } else {
+        let bytes = info.bytes_per_element() as usize;
+        for (pixel, chunk) in src.iter().zip(dst.chunks_mut(bytes)) {
+            write_element(encode_element(format, &info, *pixel), chunk);
+        }
+    }
+
+    Ok(())
+}
+
+fn element_info(format: Format) -> Result<FormatInfo, CodecError> {
+    let info = format.info();
+    let usable = info.channels.iter().any(|c| {
+        c.numeric != NumericType::Typeless
+            && (slot(c.component).is_some() || c.component == FormatComponent::E)
+    });
+    let foreign = info.channels.iter().any(|c| {
+        matches!(
+            c.component,
+            FormatComponent::Y | FormatComponent::U | FormatComponent::V | FormatComponent::P
+        )
+    });
+
+    if !usable || foreign || info.planar || info.is_block_compressed() {
+        return Err(CodecError::UnsupportedFormat(format));
+    }
+    Ok(info)
+}
+
+fn row_size(format: Format, width: usize) -> Option<usize> {
+    let width = u32::try_from(width).ok()?;
+    Some(format.checked_surface_layout(width, 1, 1)?.row_pitch)
+}
+
+/// The output slot for a component, or `None` if it doesn't map to one.
+fn slot(component: FormatComponent) -> Option<usize> {
+    match component {
+        FormatComponent::R | FormatComponent::D => Some(0),
+        FormatComponent::G | FormatComponent::S => Some(1),
+        FormatComponent::B => Some(2),
+        FormatComponent::A => Some(3),
+        _ => None,
+    }
+}
+
+fn read_element(bytes: &[u8]) -> u128 {
+    bytes
+        .iter()
+        .enumerate()
+        .fold(0, |value, (i, &b)| value | (b as u128) << (8 * i))
+}
+
+fn write_element(value: u128, bytes: &mut [u8]) {
+    for (i, b) in bytes.iter_mut().enumerate() {
+        *b = (value >> (8 * i)) as u8;
+    }
+}
+
+fn extract(element: u128, channel: &FormatChannel) -> u32 {
+    let mask = (1u128 << channel.bits) - 1;
+    ((element >> channel.offset) & mask) as u32
+}
+
+fn insert(element: &mut u128, channel: &FormatChannel, raw: u32) {
+    let mask = (1u128 << channel.bits) - 1;
+    *element |= (raw as u128 & mask) << channel.offset;
+}
+
+fn decode_element(format: Format, info: &FormatInfo, element: u128) -> [f32; 4] {
+    match format {
+        Format::R9G9B9E5SharedExponent => {
+            let rgb = float::rgb9e5_to_f32(element as u32);
+            return [rgb[0], rgb[1], rgb[2], 1.0];
+        }
+        Format::R10G10B10XrBiasA2Unorm => {
+            let xr = |shift: u32| ((element as u32 >> shift) & 0x3ff) as f32 - 384.0;
+            let alpha = (element as u32 >> 30) as f32 / 3.0;
+            return [xr(0) / 510.0, xr(10) / 510.0, xr(20) / 510.0, alpha];
+        }
+        _ => {}
+    }
+
+    let mut rgba = [0.0, 0.0, 0.0, 1.0];
+    for channel in info.channels {
+        if let Some(slot) = slot(channel.component) {
+            rgba[slot] = decode_channel(channel, extract(element, channel));
+        }
+    }
+    rgba
+}
+
+fn encode_element(format: Format, info: &FormatInfo, rgba: [f32; 4]) -> u128 {
+    match format {
+        Format::R9G9B9E5SharedExponent => {
+            return float::f32_to_rgb9e5([rgba[0], rgba[1], rgba[2]]) as u128;
+        }
+        Format::R10G10B10XrBiasA2Unorm => {
+            let xr = |v: f32| (clamp(v * 510.0 + 384.0, 0.0, 1023.0).round() as u32) as u128;
+            let alpha = (clamp(rgba[3], 0.0, 1.0) * 3.0).round() as u128;
+            return xr(rgba[0]) | xr(rgba[1]) << 10 | xr(rgba[2]) << 20 | alpha << 30;
+        }
+        _ => {}
+    }
+
+    let mut element = 0;
+    for channel in info.channels {
+        if let Some(slot) = slot(channel.component) {
+            insert(&mut element, channel, encode_channel(channel, rgba[slot]));
+        }
+    }
+    element
+}
+
+/// Decode a 2x1 element. Components that appear twice (the greens of
+/// `R8G8_B8G8Unorm`) belong to one pixel each, the rest are shared.
+fn decode_pair(info: &FormatInfo, element: u128) -> [[f32; 4]; 2] {
+    let mut pixels = [[0.0, 0.0, 0.0, 1.0]; 2];
+    for (i, channel) in info.channels.iter().enumerate() {
+        let slot = match slot(channel.component) {
+            Some(slot) => slot,
+            None => continue,
+        };
+        let value = decode_channel(channel, extract(element, channel));
+        match pair_index(info, i) {
+            Some(pixel) => pixels[pixel][slot] = value,
+            None => {
+                pixels[0][slot] = value;
+                pixels[1][slot] = value;
+            }
+        }
+    }
+    pixels
+}
+
+fn encode_pair(info: &FormatInfo, pixels: [[f32; 4]; 2]) -> u128 {
+    let mut element = 0;
+    for (i, channel) in info.channels.iter().enumerate() {
+        let slot = match slot(channel.component) {
+            Some(slot) => slot,
+            None => continue,
+        };
+        let value = match pair_index(info, i) {
+            Some(pixel) => pixels[pixel][slot],
+            None => (pixels[0][slot] + pixels[1][slot]) * 0.5,
+        };
+        insert(&mut element, channel, encode_channel(channel, value));
+    }
+    element
+}
+
+/// Which pixel of a pair the channel at `index` belongs to, or `None` when
+/// it is shared by both.
+fn pair_index(info: &FormatInfo, index: usize) -> Option<usize> {
+    let component = info.channels[index].component;
+    let same = |c: &FormatChannel| c.component == component;
+    if info.channels.iter().filter(|c| same(c)).count() < 2 {
+        return None;
+    }
+    Some(info.channels[..index].iter().filter(|c| same(c)).count())
+}
+
+fn decode_channel(channel: &FormatChannel, raw: u32) -> f32 {
+    let bits = channel.bits as u32;
+    match channel.numeric {
+        NumericType::Unorm => unorm_to_f32(raw, bits),
+        NumericType::Srgb => float::srgb_to_linear(unorm_to_f32(raw, bits)),
+        NumericType::Snorm => {
+            let max = ((1u64 << (bits - 1)) - 1) as f64;
+            (sign_extend(raw, bits) as f64 / max).max(-1.0) as f32
+        }
+        NumericType::Uint => raw as f32,
+        NumericType::Sint => sign_extend(raw, bits) as f32,
+        NumericType::Float => match bits {
+            32 => f32::from_bits(raw),
+            16 => float::f16_to_f32(raw as u16),
+            _ => float::ufloat_to_f32(raw, bits - 5),
+        },
+        NumericType::Typeless | NumericType::SharedExp => 0.0,
+    }
+}
+
+fn encode_channel(channel: &FormatChannel, value: f32) -> u32 {
+    let bits = channel.bits as u32;
+    let max = ((1u64 << bits) - 1) as f64;
+    match channel.numeric {
+        NumericType::Unorm => f32_to_unorm(value, bits),
+        NumericType::Srgb => f32_to_unorm(float::linear_to_srgb(value), bits),
+        NumericType::Snorm => {
+            let max = ((1u64 << (bits - 1)) - 1) as f64;
+            let value = clamp(value, -1.0, 1.0) as f64;
+            (value * max).round() as i32 as u32
+        }
+        NumericType::Uint => clamp_f64(value, 0.0, max).round() as u32,
+        NumericType::Sint => {
+            let half = (1u64 << (bits - 1)) as f64;
+            clamp_f64(value, -half, half - 1.0).round() as i32 as u32
+        }
+        NumericType::Float => match bits {
+            32 => value.to_bits(),
+            16 => float::f32_to_f16(value) as u32,
+            _ => float::f32_to_ufloat(value, bits - 5),
+        },
+        NumericType::Typeless | NumericType::SharedExp => 0,
+    }
+}
+
+fn unorm_to_f32(raw: u32, bits: u32) -> f32 {
+    (raw as f64 / ((1u64 << bits) - 1) as f64) as f32
+}
+
+fn f32_to_unorm(value: f32, bits: u32) -> u32 {
+    let max = ((1u64 << bits) - 1) as f64;
+    (clamp(value, 0.0, 1.0) as f64 * max).round() as u32
+}
+
+fn sign_extend(raw: u32, bits: u32) -> i32 {
+    let shift = 32 - bits;
+    ((raw << shift) as i32) >> shift
+}
+
+fn clamp_f64(value: f32, min: f64, max: f64) -> f64 {
+    let value = value as f64;
+    if value >= min {
+        value.min(max)
+    } else {
+        min
+    }
+}
+
+#[cfg(test)]
+fn all_formats() -> impl Iterator<Item = Format> {
+    (0..=132).filter_map(Format::from_u32)
+}
+
+#[cfg(test)]
+#[test]
+fn round_trip() {
+    let values = [
+        [0.0, 0.25, 0.5, 1.0],
+        [1.0, 0.75, 0.125, 0.0],
+        [0.333, 0.666, 0.999, 0.5],
+        [-0.5, -1.0, 0.1, 0.9],
+    ];
+
+    for format in all_formats() {
+        if let Ok(info) = element_info(format) {
+            let width = 4;
+            let size = row_size(format, width).unwrap();
+            let mut first = vec![0u8; size];
+            let mut second = vec![0u8; size];
+            let mut decoded = [[0.0; 4]; 4];
+
+            encode_row(format, &values, &mut first).unwrap();
+            decode_row(format, &first, &mut decoded).unwrap();
+            encode_row(format, &decoded, &mut second).unwrap();
+            assert_eq!(first, second, "{:?}", format);
+
+            if info.block_width == 1 && info.bits_per_element >= 8 {
+                let bytes = info.bytes_per_element() as usize;
+                for (i, value) in values.iter().enumerate() {
+                    let mut pixel = vec![0u8; bytes];
+                    encode_pixel(format, *value, &mut pixel).unwrap();
+                    assert_eq!(pixel[..], first[i * bytes..][..bytes], "{:?}", format);
+                    assert_eq!(decode_pixel(format, &pixel).unwrap(), decoded[i]);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn known_encodings() {
+    let encode = |format, rgba| {
+        let mut buf = [0u8; 16];
+        encode_pixel(format, rgba, &mut buf).unwrap();
+        buf
+    };
+
+    let buf = encode(Format::R10G10B10A2Unorm, [1.0, 0.0, 0.0, 0.34]);
+    assert_eq!(&buf[..4], &(0x3ffu32 | 1 << 30).to_le_bytes());
+    let buf = encode(Format::B5G6R5Unorm, [1.0, 0.0, 0.0, 1.0]);
+    assert_eq!(&buf[..2], &0xf800u16.to_le_bytes());
+    let buf = encode(Format::B4G4R4A4Unorm, [0.0, 0.0, 1.0, 1.0]);
+    assert_eq!(&buf[..2], &0xf00fu16.to_le_bytes());
+    let buf = encode(Format::B8G8R8A8UnormSrgb, [0.5, 1.0, 0.0, 0.5]);
+    assert_eq!(&buf[..4], &[0x00, 0xff, 0xbc, 0x80]);
+    let buf = encode(Format::R11G11B10Float, [1.0, 2.0, 0.5, 1.0]);
+    assert_eq!(
+        &buf[..4],
+        &(0x3c0u32 | 0x400 << 11 | 0x1c0 << 22).to_le_bytes()
+    );
+    let buf = encode(Format::R16G16B16A16Float, [1.0, -2.0, 0.0, 1.0]);
+    assert_eq!(&buf[..8], &[0x00, 0x3c, 0x00, 0xc0, 0x00, 0x00, 0x00, 0x3c]);
+    let buf = encode(Format::R8G8Snorm, [-2.0, 1.0, 0.0, 0.0]);
+    assert_eq!(&buf[..2], &[0x81, 0x7f]);
+    let buf = encode(Format::R16Sint, [-70000.0, 0.0, 0.0, 0.0]);
+    assert_eq!(&buf[..2], &0x8000u16.to_le_bytes());
+    let buf = encode(Format::R10G10B10XrBiasA2Unorm, [0.0, 1.0, 0.0, 1.0]);
+    assert_eq!(
+        &buf[..4],
+        &(0x180u32 | 894 << 10 | 0x180 << 20 | 3 << 30).to_le_bytes()
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn decode_rules() {
+    assert_eq!(
+        decode_pixel(Format::R8Snorm, &[0x80]),
+        Ok([-1.0, 0.0, 0.0, 1.0])
+    );
+    assert_eq!(
+        decode_pixel(Format::R8Snorm, &[0x81]),
+        Ok([-1.0, 0.0, 0.0, 1.0])
+    );
+    assert_eq!(
+        decode_pixel(Format::A8Unorm, &[0xff]),
+        Ok([0.0, 0.0, 0.0, 1.0])
+    );
+    assert_eq!(
+        decode_pixel(Format::D24UnormS8Uint, &[0xff, 0xff, 0xff, 7]),
+        Ok([1.0, 7.0, 0.0, 1.0])
+    );
+    assert_eq!(
+        decode_pixel(Format::B8G8R8X8Unorm, &[0, 0, 0xff, 0x12]),
+        Ok([1.0, 0.0, 0.0, 1.0])
+    );
+    assert_eq!(
+        decode_pixel(Format::R8G8B8A8Unorm, &[0; 3]),
+        Err(CodecError::BufferTooSmall)
+    );
+    for &format in &[
+        Format::R8G8B8A8Typeless,
+        Format::Bc1Unorm,
+        Format::Nv12,
+        Format::Yuy2,
+        Format::P8,
+        Format::R1Unorm,
+        Format::R8G8_B8G8Unorm,
+    ] {
+        assert_eq!(
+            decode_pixel(format, &[0; 16]),
+            Err(CodecError::UnsupportedFormat(format))
+        );
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn multi_pixel_rows() {
+    let mut pixels = [[0.0; 4]; 10];
+    decode_row(Format::R1Unorm, &[0b1010_0000, 0b0100_0000], &mut pixels).unwrap();
+    let reds: Vec<f32> = pixels.iter().map(|p| p[0]).collect();
+    assert_eq!(reds, [1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
+
+    let mut bits = [0xffu8; 2];
+    encode_row(Format::R1Unorm, &pixels, &mut bits).unwrap();
+    assert_eq!(bits, [0b1010_0000, 0b0100_0000]);
+
+    let mut pixels = [[0.0; 4]; 2];
+    decode_row(
+        Format::G8R8_G8B8Unorm,
+        &[0x00, 0xff, 0x80, 0x33],
+        &mut pixels,
+    )
+    .unwrap();
+    assert_eq!(pixels[0], [1.0, 0.0, 0.2, 1.0]);
+    assert_eq!(pixels[1][1], 128.0 / 255.0);
+    assert_eq!(pixels[1][0], 1.0);
+}
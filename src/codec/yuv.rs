@@ -0,0 +1,634 @@
+use crate::codec::float::clamp;
+use crate::codec::CodecError;
+use crate::enums::{Format, FormatComponent};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+/// The matrix used to derive luma and chroma from R'G'B'.
+pub enum YuvMatrix {
+    /// ITU-R BT.601, used for standard definition video.
+    Bt601,
+    /// ITU-R BT.709, used for high definition video.
+    #[default]
+    Bt709,
+    /// ITU-R BT.2020 (non-constant luminance), used for UHD and HDR video.
+    Bt2020,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+/// The range of code values used by luma and chroma.
+pub enum YuvRange {
+    /// Every code value is used (the "PC" or "JPEG" range).
+    Full,
+    /// Luma is stored in `[16, 235]` and chroma in `[16, 240]`, scaled up
+    /// for formats with more than 8 bits (the "studio" or "TV" range).
+    #[default]
+    Limited,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+/// Where sub-sampled chroma samples sit relative to the luma samples. This
+/// matches the `_LEFT` and `_TOPLEFT` variants of `DXGI_COLOR_SPACE_TYPE`.
+pub enum ChromaSiting {
+    /// Chroma sits halfway between luma samples in both directions (MPEG-1
+    /// and JPEG).
+    Center,
+    /// Chroma is co-sited with the left luma sample and sits halfway
+    /// between rows (MPEG-2, H.264 and HEVC).
+    #[default]
+    Left,
+    /// Chroma is co-sited with the top left luma sample (common for
+    /// BT.2020 content).
+    TopLeft,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+/// How to interpret the samples of a YUV format. The default is limited
+/// range BT.709 with left siting, the usual layout of HD video.
+pub struct YuvOptions {
+    pub matrix: YuvMatrix,
+    pub range: YuvRange,
+    pub siting: ChromaSiting,
+}
+
+impl YuvMatrix {
+    /// The luma weights of red and blue, `(Kr, Kb)`.
+    pub fn coefficients(self) -> (f32, f32) {
+        match self {
+            YuvMatrix::Bt601 => (0.299, 0.114),
+            YuvMatrix::Bt709 => (0.2126, 0.0722),
+            YuvMatrix::Bt2020 => (0.2627, 0.0593),
+        }
+    }
+
+    /// Convert gamma encoded R'G'B' to Y'CbCr, with luma in `[0, 1]` and
+    /// chroma in `[-0.5, 0.5]`.
+    pub fn rgb_to_yuv(self, rgb: [f32; 3]) -> [f32; 3] {
+        let (kr, kb) = self.coefficients();
+        let [r, g, b] = rgb;
+        let y = kr * r + (1.0 - kr - kb) * g + kb * b;
+        [y, (b - y) / (2.0 - 2.0 * kb), (r - y) / (2.0 - 2.0 * kr)]
+    }
+
+    /// The inverse of [`rgb_to_yuv`][1]. The result is not clamped.
+    ///
+    /// [1]: #method.rgb_to_yuv
+    pub fn yuv_to_rgb(self, yuv: [f32; 3]) -> [f32; 3] {
+        let (kr, kb) = self.coefficients();
+        let [y, u, v] = yuv;
+        let r = y + (2.0 - 2.0 * kr) * v;
+        let b = y + (2.0 - 2.0 * kb) * u;
+        let g = (y - kr * r - kb * b) / (1.0 - kr - kb);
+        [r, g, b]
+    }
+}
+
+impl YuvRange {
+    fn decode(self, code: u32, bits: u32, chroma: bool) -> f32 {
+        let code = code as f32;
+        match (self, chroma) {
+            (YuvRange::Full, false) => code / max_code(bits),
+            (YuvRange::Full, true) => (code - half_code(bits)) / max_code(bits),
+            (YuvRange::Limited, false) => (code / scale(bits) - 16.0) / 219.0,
+            (YuvRange::Limited, true) => (code / scale(bits) - 128.0) / 224.0,
+        }
+    }
+
+    fn encode(self, value: f32, bits: u32, chroma: bool) -> u32 {
+        let code = match (self, chroma) {
+            (YuvRange::Full, false) => value * max_code(bits),
+            (YuvRange::Full, true) => value * max_code(bits) + half_code(bits),
+            (YuvRange::Limited, false) => (value * 219.0 + 16.0) * scale(bits),
+            (YuvRange::Limited, true) => (value * 224.0 + 128.0) * scale(bits),
+        };
+        clamp(code.round(), 0.0, max_code(bits)) as u32
+    }
+}
+
+impl ChromaSiting {
+    // Whether chroma is co-sited with luma horizontally and vertically.
+    fn cosited(self) -> (bool, bool) {
+        match self {
+            ChromaSiting::Center => (false, false),
+            ChromaSiting::Left => (true, false),
+            ChromaSiting::TopLeft => (true, true),
+        }
+    }
+}
+
+fn max_code(bits: u32) -> f32 {
+    ((1u64 << bits) - 1) as f32
+}
+
+fn half_code(bits: u32) -> f32 {
+    (1u32 << (bits - 1)) as f32
+}
+
+fn scale(bits: u32) -> f32 {
+    (1u32 << (bits - 8)) as f32
+}
+
+/// Convert a YUV surface to gamma encoded RGBA.
+///
+/// Supports `Ayuv`, `Y410`, `Y416`, `Yuy2`, `Y210`, `Y216`, `Nv12`, `Nv11`,
+/// `P010`, `P016`, `P208`, `V208` and `V408`. `src` holds one depth slice
+/// laid out as described by [`Format::surface_layout`][1] and `dst` receives
+/// `width * height` pixels in row-major order. Sub-sampled chroma is
+/// bilinearly interpolated according to the siting in `options`. Formats
+/// without alpha return an alpha of 1, and colors outside of the RGB cube
+/// are clamped to `[0, 1]`.
+///
+/// [1]: ../enums/enum.Format.html#method.surface_layout
+pub fn decode_yuv(
+    format: Format,
+    src: &[u8],
+    width: u32,
+    height: u32,
+    options: YuvOptions,
+    dst: &mut [[f32; 4]],
+) -> Result<(), CodecError> {
+    let surface = Surface::new(format, width, height)?;
+    if src.len() < surface.size || dst.len() < width as usize * height as usize {
+        return Err(CodecError::BufferTooSmall);
+    }
+
+    let range = options.range;
+    let chroma: Vec<[f32; 2]> = (0..surface.chroma_height)
+        .flat_map(|cy| (0..surface.chroma_width).map(move |cx| (cx, cy)))
+        .map(|(cx, cy)| {
+            let u = surface.u.read(src, cx, cy);
+            let v = surface.v.read(src, cx, cy);
+            [
+                range.decode(u, surface.u.bits, true),
+                range.decode(v, surface.v.bits, true),
+            ]
+        })
+        .collect();
+
+    let (cosited_x, cosited_y) = options.siting.cosited();
+    for y in 0..height {
+        let (y0, y1, ty) = chroma_taps(y, surface.sub_y, cosited_y, surface.chroma_height);
+        for x in 0..width {
+            let (x0, x1, tx) = chroma_taps(x, surface.sub_x, cosited_x, surface.chroma_width);
+            let sample = |cx: u32, cy: u32| chroma[(cy * surface.chroma_width + cx) as usize];
+            let mut uv = [0.0; 2];
+            for (c, value) in uv.iter_mut().enumerate() {
+                let top = lerp(sample(x0, y0)[c], sample(x1, y0)[c], tx);
+                let bottom = lerp(sample(x0, y1)[c], sample(x1, y1)[c], tx);
+                *value = lerp(top, bottom, ty);
+            }
+
+            let luma = range.decode(surface.y.read(src, x, y), surface.y.bits, false);
+            let rgb = options.matrix.yuv_to_rgb([luma, uv[0], uv[1]]);
+            let alpha = match surface.a {
+                Some(ref a) => a.read(src, x, y) as f32 / max_code(a.bits),
+                None => 1.0,
+            };
+
+            dst[(y * width + x) as usize] = [
+                clamp(rgb[0], 0.0, 1.0),
+                clamp(rgb[1], 0.0, 1.0),
+                clamp(rgb[2], 0.0, 1.0),
+                alpha,
+            ];
+        }
+    }
+
+    Ok(())
+}
+
+/// Convert gamma encoded RGBA pixels to a YUV surface.
+///
+/// Accepts the same formats as [`decode_yuv`][1]. `src` holds
+/// `width * height` pixels in row-major order and `dst` receives one depth
+/// slice laid out as described by [`Format::surface_layout`][2]. Chroma is
+/// down-sampled with a triangle filter centered on each chroma sample
+/// position. Padding added to reach the sub-sampling alignment is filled by
+/// repeating the last row and column.
+///
+/// [1]: fn.decode_yuv.html
+/// [2]: ../enums/enum.Format.html#method.surface_layout
+pub fn encode_yuv(
+    format: Format,
+    src: &[[f32; 4]],
+    width: u32,
+    height: u32,
+    options: YuvOptions,
+    dst: &mut [u8],
+) -> Result<(), CodecError> {
+    let surface = Surface::new(format, width, height)?;
+    if width == 0 || height == 0 {
+        return Ok(());
+    }
+    if src.len() < width as usize * height as usize || dst.len() < surface.size {
+        return Err(CodecError::BufferTooSmall);
+    }
+
+    let range = options.range;
+    let padded_width = surface.chroma_width * surface.sub_x;
+    let padded_height = surface.chroma_height * surface.sub_y;
+    let mut chroma = Vec::with_capacity(padded_width as usize * padded_height as usize);
+    for y in 0..padded_height {
+        for x in 0..padded_width {
+            let pixel = src[(y.min(height - 1) * width + x.min(width - 1)) as usize];
+            let rgb = [
+                clamp(pixel[0], 0.0, 1.0),
+                clamp(pixel[1], 0.0, 1.0),
+                clamp(pixel[2], 0.0, 1.0),
+            ];
+            let [luma, u, v] = options.matrix.rgb_to_yuv(rgb);
+            chroma.push([u, v]);
+
+            // Planar formats have no luma rows past the height.
+            if y >= height {
+                continue;
+            }
+            surface
+                .y
+                .write(dst, x, y, range.encode(luma, surface.y.bits, false));
+            if let Some(ref a) = surface.a {
+                let code = (clamp(pixel[3], 0.0, 1.0) * max_code(a.bits)).round();
+                a.write(dst, x, y, code as u32);
+            }
+        }
+    }
+
+    let (cosited_x, cosited_y) = options.siting.cosited();
+    for cy in 0..surface.chroma_height {
+        let rows = filter_taps(cy, surface.sub_y, cosited_y, padded_height);
+        for cx in 0..surface.chroma_width {
+            let columns = filter_taps(cx, surface.sub_x, cosited_x, padded_width);
+            let (mut uv, mut total) = ([0.0; 2], 0.0);
+            for &(y, wy) in &rows {
+                for &(x, wx) in &columns {
+                    let sample = chroma[(y * padded_width + x) as usize];
+                    uv[0] += sample[0] * wy * wx;
+                    uv[1] += sample[1] * wy * wx;
+                    total += wy * wx;
+                }
+            }
+
+            let u = range.encode(uv[0] / total, surface.u.bits, true);
+            let v = range.encode(uv[1] / total, surface.v.bits, true);
+            surface.u.write(dst, cx, cy, u);
+            surface.v.write(dst, cx, cy, v);
+        }
+    }
+
+    Ok(())
+}
+
+// Position of the chroma sample (in luma pixels) at `index`.
+fn chroma_center(index: u32, sub: u32, cosited: bool) -> f32 {
+    let offset = if cosited { 0.0 } else { (sub - 1) as f32 / 2.0 };
+    (index * sub) as f32 + offset
+}
+
+// The two chroma samples surrounding the luma pixel at `pos`, and the
+// weight of the second one.
+fn chroma_taps(pos: u32, sub: u32, cosited: bool, count: u32) -> (u32, u32, f32) {
+    let offset = chroma_center(0, sub, cosited);
+    let at = clamp((pos as f32 - offset) / sub as f32, 0.0, (count - 1) as f32);
+    let first = at.floor() as u32;
+    (first, (first + 1).min(count - 1), at - first as f32)
+}
+
+// The luma pixels contributing to the chroma sample at `index` and their
+// weights, repeating the edge pixels.
+fn filter_taps(index: u32, sub: u32, cosited: bool, count: u32) -> Vec<(u32, f32)> {
+    let center = chroma_center(index, sub, cosited);
+    let first = (center - sub as f32).floor() as i64 + 1;
+    let last = (center + sub as f32).ceil() as i64 - 1;
+    (first..=last)
+        .map(|pos| {
+            let weight = 1.0 - (pos as f32 - center).abs() / sub as f32;
+            (pos.clamp(0, i64::from(count) - 1) as u32, weight)
+        })
+        .filter(|&(_, weight)| weight > 0.0)
+        .collect()
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+// The placement of every component of a YUV surface.
+struct Surface {
+    size: usize,
+    y: Sampler,
+    u: Sampler,
+    v: Sampler,
+    a: Option<Sampler>,
+    sub_x: u32,
+    sub_y: u32,
+    chroma_width: u32,
+    chroma_height: u32,
+}
+
+// A bit field repeated over a grid of elements in one plane.
+struct Sampler {
+    offset: usize,
+    row_pitch: usize,
+    stride: usize,
+    shift: u32,
+    bits: u32,
+}
+
+impl Surface {
+    fn new(format: Format, width: u32, height: u32) -> Result<Surface, CodecError> {
+        let info = format.info();
+        let chroma = match info.channel(FormatComponent::U) {
+            Some(channel) if format != Format::Yuv420Opaque => channel,
+            _ => return Err(CodecError::UnsupportedFormat(format)),
+        };
+
+        // A surface too large to lay out can't fit in any buffer.
+        let layout = format
+            .checked_surface_layout(width, height, 1)
+            .ok_or(CodecError::BufferTooSmall)?;
+        let planes = layout.planes();
+        let element_bytes = |plane: u8| {
+            if info.planar {
+                let bits = info
+                    .channels
+                    .iter()
+                    .filter(|c| c.plane == plane)
+                    .map(|c| u32::from(c.offset + c.bits))
+                    .max()
+                    .unwrap_or(0);
+                bits.div_ceil(8) as usize
+            } else {
+                info.bytes_per_element() as usize
+            }
+        };
+        let sampler = |component: FormatComponent| {
+            let channel = info.channel(component)?;
+            let plane = &planes[channel.plane as usize];
+            let mut stride = element_bytes(channel.plane);
+            if component == FormatComponent::Y {
+                // Packed formats store one luma sample per pixel.
+                stride /= info.block_width as usize;
+            }
+            Some(Sampler {
+                offset: plane.offset,
+                row_pitch: plane.row_pitch,
+                stride,
+                shift: channel.offset.into(),
+                bits: channel.bits.into(),
+            })
+        };
+
+        let (sub_x, sub_y) = if info.planar {
+            let plane = &planes[chroma.plane as usize];
+            (plane.subsample_x, plane.subsample_y)
+        } else {
+            (info.block_width, 1)
+        };
+
+        Ok(Surface {
+            size: layout.slice_pitch,
+            y: sampler(FormatComponent::Y).unwrap(),
+            u: sampler(FormatComponent::U).unwrap(),
+            v: sampler(FormatComponent::V).unwrap(),
+            a: sampler(FormatComponent::A),
+            sub_x,
+            sub_y,
+            chroma_width: layout.padded_width / sub_x,
+            chroma_height: layout.padded_height / sub_y,
+        })
+    }
+}
+
+impl Sampler {
+    fn range(&self, x: u32, y: u32) -> std::ops::Range<usize> {
+        let start = self.offset
+            + y as usize * self.row_pitch
+            + x as usize * self.stride
+            + (self.shift / 8) as usize;
+        let bytes = (self.shift % 8 + self.bits).div_ceil(8) as usize;
+        start..start + bytes
+    }
+
+    fn mask(&self) -> u64 {
+        ((1u64 << self.bits) - 1) << (self.shift % 8)
+    }
+
+    fn read(&self, src: &[u8], x: u32, y: u32) -> u32 {
+        let word = src[self.range(x, y)]
+            .iter()
+            .rev()
+            .fold(0u64, |word, &byte| word << 8 | u64::from(byte));
+        ((word & self.mask()) >> (self.shift % 8)) as u32
+    }
+
+    fn write(&self, dst: &mut [u8], x: u32, y: u32, value: u32) {
+        let mask = self.mask();
+        let bits = u64::from(value) << (self.shift % 8) & mask;
+        for (i, byte) in dst[self.range(x, y)].iter_mut().enumerate() {
+            let keep = !(mask >> (8 * i)) as u8;
+            *byte = *byte & keep | (bits >> (8 * i)) as u8;
+        }
+    }
+}
+
+#[cfg(test)]
+const VIDEO_FORMATS: [Format; 13] = [
+    Format::Ayuv,
+    Format::Y410,
+    Format::Y416,
+    Format::Yuy2,
+    Format::Y210,
+    Format::Y216,
+    Format::Nv12,
+    Format::Nv11,
+    Format::P010,
+    Format::P016,
+    Format::P208,
+    Format::V208,
+    Format::V408,
+];
+
+#[cfg(test)]
+#[test]
+fn matrix_round_trip() {
+    for &matrix in &[YuvMatrix::Bt601, YuvMatrix::Bt709, YuvMatrix::Bt2020] {
+        for &rgb in &[[1.0, 0.0, 0.0], [0.2, 0.7, 0.4], [0.0, 0.0, 1.0]] {
+            let back = matrix.yuv_to_rgb(matrix.rgb_to_yuv(rgb));
+            for c in 0..3 {
+                assert!((back[c] - rgb[c]).abs() < 1e-5);
+            }
+        }
+
+        let white = matrix.rgb_to_yuv([1.0; 3]);
+        assert!((white[0] - 1.0).abs() < 1e-6);
+        assert!(white[1].abs() < 1e-6 && white[2].abs() < 1e-6);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn known_encodings() {
+    let bt601 = YuvOptions {
+        matrix: YuvMatrix::Bt601,
+        ..Default::default()
+    };
+
+    // 100% red in 8-bit BT.601 studio range.
+    let mut nv12 = [0; 6];
+    encode_yuv(
+        Format::Nv12,
+        &[[1.0, 0.0, 0.0, 1.0]; 4],
+        2,
+        2,
+        bt601,
+        &mut nv12,
+    )
+    .unwrap();
+    assert_eq!(nv12, [81, 81, 81, 81, 90, 240]);
+
+    let mut ayuv = [0; 4];
+    encode_yuv(
+        Format::Ayuv,
+        &[[1.0, 0.0, 0.0, 0.5]],
+        1,
+        1,
+        bt601,
+        &mut ayuv,
+    )
+    .unwrap();
+    assert_eq!(ayuv, [240, 90, 81, 128]);
+
+    // White and black in 10 bits, stored in the high bits of each word.
+    let full = YuvOptions {
+        range: YuvRange::Full,
+        ..Default::default()
+    };
+    let pixels = [[1.0, 1.0, 1.0, 1.0], [0.0, 0.0, 0.0, 1.0]];
+    // One row of luma, then one row of chroma.
+    let mut p010 = [0; 8];
+    encode_yuv(Format::P010, &pixels, 2, 1, Default::default(), &mut p010).unwrap();
+    assert_eq!(p010[..4], [0x00, 0xeb, 0x00, 0x10]);
+    assert_eq!(p010[4..], [0x00, 0x80, 0x00, 0x80]);
+    encode_yuv(Format::P010, &pixels, 2, 1, full, &mut p010).unwrap();
+    assert_eq!(p010[..4], [0xc0, 0xff, 0x00, 0x00]);
+    assert_eq!(p010[4..], [0x00, 0x80, 0x00, 0x80]);
+
+    let mut y410 = [0; 4];
+    encode_yuv(
+        Format::Y410,
+        &pixels[..1],
+        1,
+        1,
+        Default::default(),
+        &mut y410,
+    )
+    .unwrap();
+    let word = u32::from_le_bytes(y410);
+    assert_eq!(word, 512 | 940 << 10 | 512 << 20 | 3 << 30);
+
+    let mut yuy2 = [0; 4];
+    encode_yuv(Format::Yuy2, &pixels, 2, 1, full, &mut yuy2).unwrap();
+    assert_eq!(yuy2, [255, 128, 0, 128]);
+}
+
+#[cfg(test)]
+#[test]
+fn round_trip_all_formats() {
+    let (width, height) = (7, 5);
+    let src: Vec<[f32; 4]> = (0..width * height)
+        .map(|i| {
+            let (x, y) = ((i % width) as f32, (i / width) as f32);
+            [0.2 + 0.01 * x, 0.3 + 0.015 * y, 0.8 - 0.01 * (x + y), 1.0]
+        })
+        .collect();
+
+    for &format in &VIDEO_FORMATS {
+        for &matrix in &[YuvMatrix::Bt601, YuvMatrix::Bt709, YuvMatrix::Bt2020] {
+            for &range in &[YuvRange::Full, YuvRange::Limited] {
+                let options = YuvOptions {
+                    matrix,
+                    range,
+                    ..Default::default()
+                };
+                let size = format.surface_layout(width, height, 1).slice_pitch;
+                let mut yuv = vec![0; size];
+                encode_yuv(format, &src, width, height, options, &mut yuv).unwrap();
+                let mut rgba = vec![[0.0; 4]; src.len()];
+                decode_yuv(format, &yuv, width, height, options, &mut rgba).unwrap();
+
+                // Smooth gradients survive chroma sub-sampling with small
+                // errors, mostly along the edges.
+                for (a, b) in src.iter().zip(&rgba) {
+                    for c in 0..4 {
+                        assert!((a[c] - b[c]).abs() < 0.03, "{:?} {:?}", format, range);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn chroma_siting() {
+    // Two Yuy2 elements with Cb of 64 and 192 (full range), so chroma
+    // samples sit at -0.25 and 0.25.
+    let src = [128, 64, 128, 128, 128, 192, 128, 128];
+    let cb = |siting: ChromaSiting| {
+        let options = YuvOptions {
+            matrix: YuvMatrix::Bt601,
+            range: YuvRange::Full,
+            siting,
+        };
+        let mut rgba = [[0.0; 4]; 4];
+        decode_yuv(Format::Yuy2, &src, 4, 1, options, &mut rgba).unwrap();
+        rgba.iter()
+            .map(|p| YuvMatrix::Bt601.rgb_to_yuv([p[0], p[1], p[2]])[1])
+            .collect::<Vec<_>>()
+    };
+
+    let step = 128.0 / 255.0;
+    let first = (64.0 - 128.0) / 255.0;
+    let left = cb(ChromaSiting::Left);
+    let expected = [first, first + step / 2.0, first + step, first + step];
+    for (a, b) in left.iter().zip(&expected) {
+        assert!((a - b).abs() < 1e-3, "{:?}", left);
+    }
+
+    let center = cb(ChromaSiting::Center);
+    let expected = [
+        first,
+        first + step / 4.0,
+        first + step * 3.0 / 4.0,
+        first + step,
+    ];
+    for (a, b) in center.iter().zip(&expected) {
+        assert!((a - b).abs() < 1e-3, "{:?}", center);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn errors() {
+    let mut rgba = [[0.0; 4]; 4];
+    let options = YuvOptions::default();
+    assert_eq!(
+        decode_yuv(Format::R8G8B8A8Unorm, &[0; 16], 2, 2, options, &mut rgba),
+        Err(CodecError::UnsupportedFormat(Format::R8G8B8A8Unorm))
+    );
+    assert_eq!(
+        decode_yuv(Format::Yuv420Opaque, &[0; 6], 2, 2, options, &mut rgba),
+        Err(CodecError::UnsupportedFormat(Format::Yuv420Opaque))
+    );
+    assert_eq!(
+        decode_yuv(Format::Nv12, &[0; 5], 2, 2, options, &mut rgba),
+        Err(CodecError::BufferTooSmall)
+    );
+    assert_eq!(
+        encode_yuv(Format::Nv12, &rgba, 2, 2, options, &mut [0; 5]),
+        Err(CodecError::BufferTooSmall)
+    );
+    assert_eq!(
+        decode_yuv(Format::Nv12, &[0; 6], u32::MAX, 2, options, &mut rgba),
+        Err(CodecError::BufferTooSmall)
+    );
+}
@@ -0,0 +1,831 @@
+//! Reading and writing DirectDraw Surface (`.dds`) files. Both the legacy
+//! header and the `DX10` extended header are supported, and the surface
+//! data is laid out as described by
+//! [`MipChainLayout`](../enums/struct.MipChainLayout.html).
+
+use crate::enums::{Format, MipChainLayout};
+
+use std::fmt;
+
+const MAGIC: u32 = 0x2053_4444; // "DDS "
+const HEADER_SIZE: usize = 124;
+const DX10_HEADER_SIZE: usize = 20;
+
+const FLAG_CAPS: u32 = 0x1;
+const FLAG_HEIGHT: u32 = 0x2;
+const FLAG_WIDTH: u32 = 0x4;
+const FLAG_PITCH: u32 = 0x8;
+const FLAG_PIXEL_FORMAT: u32 = 0x1000;
+const FLAG_MIPMAP_COUNT: u32 = 0x2_0000;
+const FLAG_LINEAR_SIZE: u32 = 0x8_0000;
+const FLAG_DEPTH: u32 = 0x80_0000;
+
+const CAPS_COMPLEX: u32 = 0x8;
+const CAPS_TEXTURE: u32 = 0x1000;
+const CAPS_MIPMAP: u32 = 0x40_0000;
+
+const CAPS2_CUBEMAP: u32 = 0x200;
+const CAPS2_CUBEMAP_ALL_FACES: u32 = 0xfc00;
+const CAPS2_VOLUME: u32 = 0x20_0000;
+
+const DIMENSION_TEXTURE1D: u32 = 2;
+const DIMENSION_TEXTURE2D: u32 = 3;
+const DIMENSION_TEXTURE3D: u32 = 4;
+const MISC_TEXTURECUBE: u32 = 0x4;
+
+// The Direct3D 11 resource limits, which DirectXTex also enforces when
+// loading.
+const MAX_TEXTURE1D_DIMENSION: u32 = 16384;
+const MAX_TEXTURE2D_DIMENSION: u32 = 16384;
+const MAX_TEXTURE3D_DIMENSION: u32 = 2048;
+const MAX_ARRAY_SIZE: u32 = 2048;
+
+const TOO_LARGE: DdsError = DdsError::InvalidHeader("texture too large");
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+/// Errors produced while reading or writing a DDS file.
+pub enum DdsError {
+    /// The data does not start with the `DDS ` magic number.
+    NotDds,
+    /// The data ends before the end of the headers or the surface data.
+    Truncated,
+    /// A header field has an invalid or inconsistent value.
+    InvalidHeader(&'static str),
+    /// The legacy pixel format has no `Format` equivalent.
+    UnsupportedPixelFormat(PixelFormat),
+    /// The format can't be stored in the requested kind of header.
+    UnsupportedFormat(Format),
+}
+
+impl fmt::Display for DdsError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DdsError::NotDds => fmt.write_str("the data is not a DDS file"),
+            DdsError::Truncated => fmt.write_str("the DDS file is truncated"),
+            DdsError::InvalidHeader(reason) => write!(fmt, "invalid DDS header: {}", reason),
+            DdsError::UnsupportedPixelFormat(ref pf) => {
+                write!(fmt, "the DDS pixel format {:?} is not supported", pf)
+            }
+            DdsError::UnsupportedFormat(format) => {
+                write!(
+                    fmt,
+                    "the format {:?} can't be stored in this DDS header",
+                    format
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for DdsError {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+/// The dimensionality of the texture stored in a DDS file.
+pub enum TextureDimension {
+    Texture1D,
+    Texture2D,
+    Texture3D,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+/// Which header to use when writing a DDS file.
+pub enum HeaderKind {
+    /// The legacy header when the texture can be described by it, and the
+    /// `DX10` header otherwise.
+    Auto,
+    /// Only the legacy header. Fails for formats without a legacy pixel
+    /// format, texture arrays and 1D textures.
+    Legacy,
+    /// Always add the `DX10` header.
+    Dx10,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+/// Describes the texture stored in a DDS file.
+pub struct DdsDesc {
+    pub format: Format,
+    pub dimension: TextureDimension,
+    pub width: u32,
+    /// Always 1 for 1D textures.
+    pub height: u32,
+    /// Always 1 for 1D and 2D textures.
+    pub depth: u32,
+    /// The number of mip levels stored for each array element, at least 1.
+    pub mip_levels: u32,
+    /// The number of array elements. For cube maps this counts whole cubes,
+    /// so the file stores `6 * array_size` faces.
+    pub array_size: u32,
+    /// The texture is a cube map (or an array of cube maps).
+    pub cube: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+/// The `DDS_PIXELFORMAT` structure of the legacy header.
+pub struct PixelFormat {
+    pub flags: u32,
+    pub four_cc: u32,
+    pub rgb_bit_count: u32,
+    /// The red, green, blue and alpha bit masks.
+    pub masks: [u32; 4],
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// A parsed DDS file, borrowing the surface data from the input.
+pub struct Dds<'a> {
+    pub desc: DdsDesc,
+    /// The surface data of every subresource, ordered by array element
+    /// (cube face) and then by mip level. Any bytes past the end of the
+    /// last subresource are not included.
+    pub data: &'a [u8],
+}
+
+impl PixelFormat {
+    /// Flag: `masks[3]` is valid.
+    pub const ALPHA_PIXELS: u32 = 0x1;
+    /// Flag: alpha-only data in `masks[3]`.
+    pub const ALPHA: u32 = 0x2;
+    /// Flag: `four_cc` is valid.
+    pub const FOUR_CC: u32 = 0x4;
+    /// Flag: uncompressed RGB data in `masks`.
+    pub const RGB: u32 = 0x40;
+    /// Flag: single channel luminance data in `masks[0]`.
+    pub const LUMINANCE: u32 = 0x2_0000;
+    /// Flag: signed bump map data in `masks`.
+    pub const BUMP_DU_DV: u32 = 0x8_0000;
+
+    /// The FourCC code of the `DX10` extended header.
+    pub const DX10: u32 = four_cc(b"DX10");
+
+    /// Find the `Format` matching a legacy pixel format, following the same
+    /// rules as DirectXTex. The alpha-premultiplied `DXT2` and `DXT4` map
+    /// to `Bc2Unorm` and `Bc3Unorm`.
+    pub fn to_format(&self) -> Option<Format> {
+        let kind = self.flags & !PixelFormat::ALPHA_PIXELS;
+        LEGACY_FORMATS
+            .iter()
+            .find(|(_, pf)| {
+                if kind & PixelFormat::FOUR_CC != 0 {
+                    pf.four_cc == self.four_cc
+                } else {
+                    pf.flags & !PixelFormat::ALPHA_PIXELS == kind
+                        && pf.rgb_bit_count == self.rgb_bit_count
+                        && pf.masks == self.masks
+                }
+            })
+            .map(|&(format, _)| format)
+    }
+
+    /// The legacy pixel format used to store `format`, if there is one.
+    pub fn from_format(format: Format) -> Option<PixelFormat> {
+        LEGACY_FORMATS
+            .iter()
+            .find(|&&(f, _)| f == format)
+            .map(|&(_, pf)| pf)
+    }
+}
+
+impl DdsDesc {
+    /// The layout of the surface data, with cube faces counted as array
+    /// slices. `None` if the data would be too large to address.
+    pub fn layout(&self) -> Option<MipChainLayout> {
+        self.format.mip_chain_layout(
+            self.width,
+            self.height,
+            self.depth,
+            self.mip_levels,
+            self.slice_count()?,
+        )
+    }
+
+    /// The number of array slices, counting each cube face.
+    fn slice_count(&self) -> Option<u32> {
+        let faces = if self.cube { 6 } else { 1 };
+        self.array_size.checked_mul(faces)
+    }
+
+    /// The size of the top mip level of every array slice, which the data
+    /// must hold at the very least.
+    fn min_data_size(&self) -> Option<usize> {
+        let top = self
+            .format
+            .checked_surface_layout(self.width, self.height, self.depth)?;
+        top.total_size().checked_mul(self.slice_count()? as usize)
+    }
+
+    /// Serialize this description followed by `data`, which must hold
+    /// exactly [`layout().total_size`][1] bytes.
+    ///
+    /// [1]: #method.layout
+    pub fn write(&self, header: HeaderKind, data: &[u8]) -> Result<Vec<u8>, DdsError> {
+        self.validate()?;
+        let layout = self.layout().ok_or(TOO_LARGE)?;
+        if data.len() != layout.total_size {
+            return Err(DdsError::InvalidHeader(
+                "data size doesn't match the layout",
+            ));
+        }
+
+        let pf = PixelFormat::from_format(self.format);
+        let fits = self.array_size == 1 && self.dimension != TextureDimension::Texture1D;
+        let legacy = match header {
+            HeaderKind::Auto => pf.filter(|_| fits),
+            HeaderKind::Legacy if !fits => {
+                return Err(DdsError::InvalidHeader(
+                    "the legacy header can't store arrays or 1D textures",
+                ));
+            }
+            HeaderKind::Legacy => Some(pf.ok_or(DdsError::UnsupportedFormat(self.format))?),
+            HeaderKind::Dx10 => None,
+        };
+
+        let top = &layout.subresources[0].layout;
+        let mut flags = FLAG_CAPS | FLAG_HEIGHT | FLAG_WIDTH | FLAG_PIXEL_FORMAT;
+        let mut caps = CAPS_TEXTURE;
+        let mut caps2 = 0;
+        let pitch = if self.format.info().is_block_compressed() {
+            flags |= FLAG_LINEAR_SIZE;
+            top.slice_pitch
+        } else {
+            flags |= FLAG_PITCH;
+            top.row_pitch
+        };
+        if self.mip_levels > 1 {
+            flags |= FLAG_MIPMAP_COUNT;
+            caps |= CAPS_COMPLEX | CAPS_MIPMAP;
+        }
+        if self.cube {
+            caps |= CAPS_COMPLEX;
+            caps2 |= CAPS2_CUBEMAP | CAPS2_CUBEMAP_ALL_FACES;
+        }
+        if self.dimension == TextureDimension::Texture3D {
+            flags |= FLAG_DEPTH;
+            caps2 |= CAPS2_VOLUME;
+        }
+
+        let pf = legacy.unwrap_or(PixelFormat {
+            flags: PixelFormat::FOUR_CC,
+            four_cc: PixelFormat::DX10,
+            rgb_bit_count: 0,
+            masks: [0; 4],
+        });
+
+        let mut out = Vec::with_capacity(4 + HEADER_SIZE + DX10_HEADER_SIZE + data.len());
+        let mut put = |value: u32| out.extend_from_slice(&value.to_le_bytes());
+        put(MAGIC);
+        put(HEADER_SIZE as u32);
+        put(flags);
+        put(self.height);
+        put(self.width);
+        put(pitch as u32);
+        put(if flags & FLAG_DEPTH != 0 {
+            self.depth
+        } else {
+            0
+        });
+        put(self.mip_levels);
+        (0..11).for_each(|_| put(0));
+        put(32);
+        put(pf.flags);
+        put(pf.four_cc);
+        put(pf.rgb_bit_count);
+        pf.masks.iter().for_each(|&mask| put(mask));
+        put(caps);
+        put(caps2);
+        (0..3).for_each(|_| put(0));
+
+        if legacy.is_none() {
+            put(self.format as u32);
+            put(match self.dimension {
+                TextureDimension::Texture1D => DIMENSION_TEXTURE1D,
+                TextureDimension::Texture2D => DIMENSION_TEXTURE2D,
+                TextureDimension::Texture3D => DIMENSION_TEXTURE3D,
+            });
+            put(if self.cube { MISC_TEXTURECUBE } else { 0 });
+            put(self.array_size);
+            put(0);
+        }
+
+        out.extend_from_slice(data);
+        Ok(out)
+    }
+
+    fn validate(&self) -> Result<(), DdsError> {
+        if self.format == Format::Unknown {
+            return Err(DdsError::UnsupportedFormat(self.format));
+        }
+        if self.width == 0 || self.height == 0 || self.depth == 0 {
+            return Err(DdsError::InvalidHeader("zero sized texture"));
+        }
+        if self.array_size == 0 {
+            return Err(DdsError::InvalidHeader("zero array size"));
+        }
+        if self.mip_levels == 0
+            || self.mip_levels > MipChainLayout::max_levels(self.width, self.height, self.depth)
+        {
+            return Err(DdsError::InvalidHeader("invalid mip level count"));
+        }
+
+        let valid = match self.dimension {
+            TextureDimension::Texture1D => self.height == 1 && self.depth == 1 && !self.cube,
+            TextureDimension::Texture2D => {
+                self.depth == 1 && (!self.cube || self.width == self.height)
+            }
+            TextureDimension::Texture3D => self.array_size == 1 && !self.cube,
+        };
+        if !valid {
+            return Err(DdsError::InvalidHeader(
+                "dimensions don't match the texture type",
+            ));
+        }
+
+        let max_dimension = match self.dimension {
+            TextureDimension::Texture1D => MAX_TEXTURE1D_DIMENSION,
+            TextureDimension::Texture2D => MAX_TEXTURE2D_DIMENSION,
+            TextureDimension::Texture3D => MAX_TEXTURE3D_DIMENSION,
+        };
+        if self.width > max_dimension || self.height > max_dimension || self.depth > max_dimension {
+            return Err(TOO_LARGE);
+        }
+        match self.slice_count() {
+            Some(slices) if slices <= MAX_ARRAY_SIZE => Ok(()),
+            _ => Err(DdsError::InvalidHeader("array size too large")),
+        }
+    }
+}
+
+impl<'a> Dds<'a> {
+    /// Parse the headers of a DDS file and locate its surface data.
+    pub fn parse(bytes: &'a [u8]) -> Result<Dds<'a>, DdsError> {
+        if bytes.len() < 4 || read_u32(bytes, 0) != MAGIC {
+            return Err(DdsError::NotDds);
+        }
+        if bytes.len() < 4 + HEADER_SIZE {
+            return Err(DdsError::Truncated);
+        }
+
+        let field = |index: usize| read_u32(bytes, 4 + index * 4);
+        if field(0) as usize != HEADER_SIZE || field(18) != 32 {
+            return Err(DdsError::InvalidHeader("wrong header size"));
+        }
+
+        let flags = field(1);
+        let height = field(2);
+        let width = field(3);
+        let depth = field(5);
+        let mip_levels = field(6).max(1);
+        let pf = PixelFormat {
+            flags: field(19),
+            four_cc: field(20),
+            rgb_bit_count: field(21),
+            masks: [field(22), field(23), field(24), field(25)],
+        };
+        let caps2 = field(27);
+
+        let mut data_offset = 4 + HEADER_SIZE;
+        let desc = if pf.flags & PixelFormat::FOUR_CC != 0 && pf.four_cc == PixelFormat::DX10 {
+            if bytes.len() < data_offset + DX10_HEADER_SIZE {
+                return Err(DdsError::Truncated);
+            }
+            let dx10 = move |index: usize| read_u32(bytes, data_offset + index * 4);
+            data_offset += DX10_HEADER_SIZE;
+
+            let format =
+                Format::from_u32(dx10(0)).ok_or(DdsError::InvalidHeader("unknown DXGI format"))?;
+            let (dimension, height, depth) = match dx10(1) {
+                DIMENSION_TEXTURE1D => (TextureDimension::Texture1D, 1, 1),
+                DIMENSION_TEXTURE2D => (TextureDimension::Texture2D, height, 1),
+                DIMENSION_TEXTURE3D => (TextureDimension::Texture3D, height, depth),
+                _ => return Err(DdsError::InvalidHeader("unknown resource dimension")),
+            };
+            DdsDesc {
+                format,
+                dimension,
+                width,
+                height,
+                depth,
+                mip_levels,
+                array_size: dx10(3),
+                cube: dx10(2) & MISC_TEXTURECUBE != 0,
+            }
+        } else {
+            let format = pf.to_format().ok_or(DdsError::UnsupportedPixelFormat(pf))?;
+            let cube = caps2 & CAPS2_CUBEMAP != 0;
+            if cube && caps2 & CAPS2_CUBEMAP_ALL_FACES != CAPS2_CUBEMAP_ALL_FACES {
+                return Err(DdsError::InvalidHeader(
+                    "partial cube maps are not supported",
+                ));
+            }
+            let volume = flags & FLAG_DEPTH != 0 || caps2 & CAPS2_VOLUME != 0;
+            DdsDesc {
+                format,
+                dimension: if volume {
+                    TextureDimension::Texture3D
+                } else {
+                    TextureDimension::Texture2D
+                },
+                width,
+                height,
+                depth: if volume { depth } else { 1 },
+                mip_levels,
+                array_size: 1,
+                cube,
+            }
+        };
+
+        desc.validate()?;
+        // Check the headers against the amount of data before laying out
+        // every subresource.
+        let available = bytes.len() - data_offset;
+        if desc.min_data_size().ok_or(TOO_LARGE)? > available {
+            return Err(DdsError::Truncated);
+        }
+        let size = desc.layout().ok_or(TOO_LARGE)?.total_size;
+        let data = bytes
+            .get(data_offset..data_offset + size)
+            .ok_or(DdsError::Truncated)?;
+        Ok(Dds { desc, data })
+    }
+
+    /// The data of one subresource. For cube maps `array_slice` is
+    /// `6 * cube + face`. `None` if the subresource doesn't exist or `data`
+    /// is too short to hold it.
+    pub fn subresource(&self, mip_level: u32, array_slice: u32) -> Option<&'a [u8]> {
+        let layout = self.desc.layout()?;
+        let subresource = layout.subresource(mip_level, array_slice)?;
+        let end = subresource.offset + subresource.layout.total_size();
+        self.data.get(subresource.offset..end)
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    let mut word = [0; 4];
+    word.copy_from_slice(&bytes[offset..offset + 4]);
+    u32::from_le_bytes(word)
+}
+
+const fn four_cc(code: &[u8; 4]) -> u32 {
+    code[0] as u32 | (code[1] as u32) << 8 | (code[2] as u32) << 16 | (code[3] as u32) << 24
+}
+
+const fn fourcc_format(code: u32) -> PixelFormat {
+    PixelFormat {
+        flags: PixelFormat::FOUR_CC,
+        four_cc: code,
+        rgb_bit_count: 0,
+        masks: [0; 4],
+    }
+}
+
+const fn mask_format(flags: u32, rgb_bit_count: u32, masks: [u32; 4]) -> PixelFormat {
+    PixelFormat {
+        flags,
+        four_cc: 0,
+        rgb_bit_count,
+        masks,
+    }
+}
+
+const RGB: u32 = PixelFormat::RGB;
+const RGBA: u32 = PixelFormat::RGB | PixelFormat::ALPHA_PIXELS;
+const LUMINANCE: u32 = PixelFormat::LUMINANCE;
+
+// Legacy pixel formats. The first entry for a format is the one written, the
+// rest are aliases that are only recognized when reading.
+static LEGACY_FORMATS: &[(Format, PixelFormat)] = &[
+    (Format::Bc1Unorm, fourcc_format(four_cc(b"DXT1"))),
+    (Format::Bc2Unorm, fourcc_format(four_cc(b"DXT3"))),
+    (Format::Bc3Unorm, fourcc_format(four_cc(b"DXT5"))),
+    (Format::Bc4Unorm, fourcc_format(four_cc(b"BC4U"))),
+    (Format::Bc4Snorm, fourcc_format(four_cc(b"BC4S"))),
+    (Format::Bc5Unorm, fourcc_format(four_cc(b"ATI2"))),
+    (Format::Bc5Snorm, fourcc_format(four_cc(b"BC5S"))),
+    (Format::R8G8_B8G8Unorm, fourcc_format(four_cc(b"RGBG"))),
+    (Format::G8R8_G8B8Unorm, fourcc_format(four_cc(b"GRGB"))),
+    (Format::Yuy2, fourcc_format(four_cc(b"YUY2"))),
+    // Direct3D 9 `D3DFORMAT` values stored as a FourCC.
+    (Format::R16G16B16A16Unorm, fourcc_format(36)),
+    (Format::R16G16B16A16Snorm, fourcc_format(110)),
+    (Format::R16Float, fourcc_format(111)),
+    (Format::R16G16Float, fourcc_format(112)),
+    (Format::R16G16B16A16Float, fourcc_format(113)),
+    (Format::R32Float, fourcc_format(114)),
+    (Format::R32G32Float, fourcc_format(115)),
+    (Format::R32G32B32A32Float, fourcc_format(116)),
+    (
+        Format::R8G8B8A8Unorm,
+        mask_format(RGBA, 32, [0xff, 0xff00, 0xff_0000, 0xff00_0000]),
+    ),
+    (
+        Format::B8G8R8A8Unorm,
+        mask_format(RGBA, 32, [0xff_0000, 0xff00, 0xff, 0xff00_0000]),
+    ),
+    (
+        Format::B8G8R8X8Unorm,
+        mask_format(RGB, 32, [0xff_0000, 0xff00, 0xff, 0]),
+    ),
+    (
+        Format::R10G10B10A2Unorm,
+        mask_format(RGBA, 32, [0x3ff, 0xf_fc00, 0x3ff0_0000, 0xc000_0000]),
+    ),
+    (
+        Format::R16G16Unorm,
+        mask_format(RGB, 32, [0xffff, 0xffff_0000, 0, 0]),
+    ),
+    (
+        Format::B5G6R5Unorm,
+        mask_format(RGB, 16, [0xf800, 0x7e0, 0x1f, 0]),
+    ),
+    (
+        Format::B5G5R5A1Unorm,
+        mask_format(RGBA, 16, [0x7c00, 0x3e0, 0x1f, 0x8000]),
+    ),
+    (
+        Format::B4G4R4A4Unorm,
+        mask_format(RGBA, 16, [0xf00, 0xf0, 0xf, 0xf000]),
+    ),
+    (Format::R8Unorm, mask_format(LUMINANCE, 8, [0xff, 0, 0, 0])),
+    (
+        Format::R16Unorm,
+        mask_format(LUMINANCE, 16, [0xffff, 0, 0, 0]),
+    ),
+    (
+        Format::R8G8Unorm,
+        mask_format(
+            LUMINANCE | PixelFormat::ALPHA_PIXELS,
+            16,
+            [0xff, 0, 0, 0xff00],
+        ),
+    ),
+    (
+        Format::A8Unorm,
+        mask_format(PixelFormat::ALPHA, 8, [0, 0, 0, 0xff]),
+    ),
+    (
+        Format::R8G8Snorm,
+        mask_format(PixelFormat::BUMP_DU_DV, 16, [0xff, 0xff00, 0, 0]),
+    ),
+    (
+        Format::R8G8B8A8Snorm,
+        mask_format(
+            PixelFormat::BUMP_DU_DV,
+            32,
+            [0xff, 0xff00, 0xff_0000, 0xff00_0000],
+        ),
+    ),
+    (
+        Format::R16G16Snorm,
+        mask_format(PixelFormat::BUMP_DU_DV, 32, [0xffff, 0xffff_0000, 0, 0]),
+    ),
+    // Aliases written by other tools.
+    (Format::Bc2Unorm, fourcc_format(four_cc(b"DXT2"))),
+    (Format::Bc3Unorm, fourcc_format(four_cc(b"DXT4"))),
+    (Format::Bc4Unorm, fourcc_format(four_cc(b"ATI1"))),
+    (Format::Bc5Unorm, fourcc_format(four_cc(b"BC5U"))),
+    (
+        Format::R8G8B8A8Unorm,
+        mask_format(RGB, 32, [0xff, 0xff00, 0xff_0000, 0]),
+    ),
+    // D3DX writes 10:10:10:2 with the red and blue masks swapped.
+    (
+        Format::R10G10B10A2Unorm,
+        mask_format(RGBA, 32, [0x3ff0_0000, 0xf_fc00, 0x3ff, 0xc000_0000]),
+    ),
+    (
+        Format::R32Float,
+        mask_format(RGB, 32, [0xffff_ffff, 0, 0, 0]),
+    ),
+];
+
+#[cfg(test)]
+fn fixture_desc(
+    format: Format,
+    dimension: TextureDimension,
+    size: (u32, u32, u32),
+    mip_levels: u32,
+    array_size: u32,
+    cube: bool,
+) -> DdsDesc {
+    DdsDesc {
+        format,
+        dimension,
+        width: size.0,
+        height: size.1,
+        depth: size.2,
+        mip_levels,
+        array_size,
+        cube,
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn fixtures() {
+    use self::TextureDimension::*;
+
+    let fixtures: &[(&[u8], DdsDesc)] = &[
+        (
+            include_bytes!("dds/fixtures/dxt1_mips.dds"),
+            fixture_desc(Format::Bc1Unorm, Texture2D, (8, 8, 1), 4, 1, false),
+        ),
+        (
+            include_bytes!("dds/fixtures/a8r8g8b8.dds"),
+            fixture_desc(Format::B8G8R8A8Unorm, Texture2D, (3, 2, 1), 1, 1, false),
+        ),
+        (
+            include_bytes!("dds/fixtures/r5g6b5.dds"),
+            fixture_desc(Format::B5G6R5Unorm, Texture2D, (2, 2, 1), 1, 1, false),
+        ),
+        (
+            include_bytes!("dds/fixtures/cube_a8b8g8r8.dds"),
+            fixture_desc(Format::R8G8B8A8Unorm, Texture2D, (2, 2, 1), 2, 1, true),
+        ),
+        (
+            include_bytes!("dds/fixtures/volume_l8.dds"),
+            fixture_desc(Format::R8Unorm, Texture3D, (4, 4, 2), 3, 1, false),
+        ),
+        (
+            include_bytes!("dds/fixtures/fp16_fourcc.dds"),
+            fixture_desc(Format::R16G16B16A16Float, Texture2D, (2, 1, 1), 1, 1, false),
+        ),
+        (
+            include_bytes!("dds/fixtures/bc7_array.dds"),
+            fixture_desc(Format::Bc7Unorm, Texture2D, (8, 4, 1), 2, 3, false),
+        ),
+        (
+            include_bytes!("dds/fixtures/rgba16f_cube_array.dds"),
+            fixture_desc(Format::R16G16B16A16Float, Texture2D, (1, 1, 1), 1, 2, true),
+        ),
+        (
+            include_bytes!("dds/fixtures/r32f_1d.dds"),
+            fixture_desc(Format::R32Float, Texture1D, (5, 1, 1), 3, 1, false),
+        ),
+    ];
+
+    for &(bytes, desc) in fixtures {
+        let dds = Dds::parse(bytes).unwrap();
+        assert_eq!(dds.desc, desc);
+        assert_eq!(dds.data, &bytes[bytes.len() - dds.data.len()..]);
+        assert_eq!(dds.data.len(), desc.layout().unwrap().total_size);
+
+        // The fixtures use the same header fields as DirectXTex, so writing
+        // them back out is lossless.
+        assert_eq!(desc.write(HeaderKind::Auto, dds.data).unwrap(), bytes);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn subresources() {
+    let bytes = include_bytes!("dds/fixtures/cube_a8b8g8r8.dds");
+    let dds = Dds::parse(bytes).unwrap();
+    let face = |mip, slice| dds.subresource(mip, slice).unwrap();
+    assert_eq!(face(0, 0), &dds.data[..16]);
+    assert_eq!(face(1, 0), &dds.data[16..20]);
+    assert_eq!(face(1, 5), &dds.data[116..120]);
+    assert_eq!(dds.subresource(2, 0), None);
+    assert_eq!(dds.subresource(0, 6), None);
+
+    // A `Dds` put together by hand with too little data.
+    let short = Dds {
+        data: &dds.data[..100],
+        ..dds
+    };
+    assert_eq!(short.subresource(0, 5), None);
+    assert!(short.subresource(0, 4).is_some());
+
+    let dds = Dds::parse(include_bytes!("dds/fixtures/volume_l8.dds")).unwrap();
+    assert_eq!(dds.subresource(0, 0).unwrap().len(), 32);
+    assert_eq!(dds.subresource(2, 0).unwrap().len(), 1);
+}
+
+#[cfg(test)]
+#[test]
+fn pixel_formats() {
+    for &(format, pf) in LEGACY_FORMATS {
+        let written = PixelFormat::from_format(format).unwrap();
+        assert_eq!(written.to_format(), Some(format));
+        assert_eq!(pf.to_format(), Some(format));
+    }
+
+    let dxt4 = fourcc_format(four_cc(b"DXT4"));
+    assert_eq!(dxt4.to_format(), Some(Format::Bc3Unorm));
+    assert_eq!(
+        PixelFormat::from_format(Format::Bc3Unorm).unwrap().four_cc,
+        four_cc(b"DXT5")
+    );
+
+    // The alpha flag doesn't change which format the masks describe.
+    let mut bgrx = PixelFormat::from_format(Format::B8G8R8X8Unorm).unwrap();
+    bgrx.flags |= PixelFormat::ALPHA_PIXELS;
+    assert_eq!(bgrx.to_format(), Some(Format::B8G8R8X8Unorm));
+
+    let rgb24 = mask_format(RGB, 24, [0xff_0000, 0xff00, 0xff, 0]);
+    assert_eq!(rgb24.to_format(), None);
+    assert_eq!(PixelFormat::from_format(Format::Bc7Unorm), None);
+    assert_eq!(PixelFormat::from_format(Format::R8G8B8A8UnormSrgb), None);
+}
+
+#[cfg(test)]
+#[test]
+fn write_headers() {
+    let desc = fixture_desc(
+        Format::B8G8R8A8Unorm,
+        TextureDimension::Texture2D,
+        (3, 2, 1),
+        1,
+        1,
+        false,
+    );
+    let data = [0; 24];
+    let legacy = desc.write(HeaderKind::Legacy, &data).unwrap();
+    assert_eq!(legacy.len(), 4 + HEADER_SIZE + 24);
+
+    let dx10 = desc.write(HeaderKind::Dx10, &data).unwrap();
+    assert_eq!(dx10.len(), 4 + HEADER_SIZE + DX10_HEADER_SIZE + 24);
+    assert_eq!(read_u32(&dx10, 84), PixelFormat::DX10);
+    assert_eq!(Dds::parse(&dx10).unwrap().desc, desc);
+
+    let srgb = DdsDesc {
+        format: Format::B8G8R8A8UnormSrgb,
+        ..desc
+    };
+    assert_eq!(
+        srgb.write(HeaderKind::Legacy, &data),
+        Err(DdsError::UnsupportedFormat(Format::B8G8R8A8UnormSrgb))
+    );
+    assert_eq!(
+        read_u32(&srgb.write(HeaderKind::Auto, &data).unwrap(), 84),
+        PixelFormat::DX10
+    );
+
+    let array = DdsDesc {
+        array_size: 2,
+        ..desc
+    };
+    assert!(array.write(HeaderKind::Legacy, &[0; 48]).is_err());
+    assert!(desc.write(HeaderKind::Auto, &[0; 23]).is_err());
+
+    let cube = DdsDesc { cube: true, ..desc };
+    assert!(cube.write(HeaderKind::Auto, &[0; 144]).is_err());
+}
+
+#[cfg(test)]
+fn patch_u32(bytes: &[u8], offset: usize, value: u32) -> Vec<u8> {
+    let mut bytes = bytes.to_vec();
+    bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    bytes
+}
+
+#[cfg(test)]
+#[test]
+fn parse_errors() {
+    let bytes = include_bytes!("dds/fixtures/dxt1_mips.dds");
+    assert_eq!(Dds::parse(b"DDX "), Err(DdsError::NotDds));
+    assert_eq!(Dds::parse(&bytes[..100]), Err(DdsError::Truncated));
+    assert_eq!(
+        Dds::parse(&bytes[..bytes.len() - 1]),
+        Err(DdsError::Truncated)
+    );
+
+    let mut partial_cube = include_bytes!("dds/fixtures/cube_a8b8g8r8.dds").to_vec();
+    partial_cube[112] = 0x02;
+    partial_cube[113] = 0x3e;
+    assert!(matches!(
+        Dds::parse(&partial_cube),
+        Err(DdsError::InvalidHeader(_))
+    ));
+
+    let mut rgb24 = include_bytes!("dds/fixtures/a8r8g8b8.dds").to_vec();
+    rgb24[88] = 24;
+    assert!(matches!(
+        Dds::parse(&rgb24),
+        Err(DdsError::UnsupportedPixelFormat(_))
+    ));
+
+    let mut bad_format = include_bytes!("dds/fixtures/bc7_array.dds").to_vec();
+    bad_format[128] = 200;
+    assert!(matches!(
+        Dds::parse(&bad_format),
+        Err(DdsError::InvalidHeader(_))
+    ));
+
+    let array = include_bytes!("dds/fixtures/bc7_array.dds");
+    let cube_array = include_bytes!("dds/fixtures/rgba16f_cube_array.dds");
+    let invalid = |bytes: &[u8]| matches!(Dds::parse(bytes), Err(DdsError::InvalidHeader(_)));
+
+    // The DX10 array size.
+    assert!(invalid(&patch_u32(array, 140, u32::MAX)));
+    assert!(invalid(&patch_u32(array, 140, MAX_ARRAY_SIZE + 1)));
+    assert!(invalid(&patch_u32(cube_array, 140, u32::MAX / 6 + 1)));
+    assert!(invalid(&patch_u32(cube_array, 140, MAX_ARRAY_SIZE / 6 + 1)));
+
+    // The width, for a block compressed format.
+    assert!(invalid(&patch_u32(array, 16, u32::MAX)));
+    assert!(invalid(&patch_u32(array, 16, MAX_TEXTURE2D_DIMENSION + 1)));
+
+    // Sizes within the limits that the data can't hold.
+    let wide = patch_u32(array, 16, MAX_TEXTURE2D_DIMENSION);
+    assert_eq!(Dds::parse(&wide), Err(DdsError::Truncated));
+    let many = patch_u32(array, 140, MAX_ARRAY_SIZE);
+    assert_eq!(Dds::parse(&many), Err(DdsError::Truncated));
+}
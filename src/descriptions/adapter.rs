@@ -2,16 +2,23 @@ use crate::descriptions::luid::Luid;
 use crate::enums::{AdapterFlags, ComputePreemptionGranularity, GraphicsPreemptionGranularity};
 use crate::helpers::wstrlens;
 use crate::helpers::MemoryDbgHelper;
+use crate::pci::PciId;
 
 use checked_enum::UncheckedEnum;
+#[cfg(windows)]
 use winapi::shared::dxgi::DXGI_ADAPTER_DESC;
+#[cfg(windows)]
 use winapi::shared::dxgi::DXGI_ADAPTER_DESC1;
+#[cfg(windows)]
 use winapi::shared::dxgi1_2::DXGI_ADAPTER_DESC2;
+#[cfg(windows)]
 use winapi::shared::dxgi1_6::DXGI_ADAPTER_DESC3;
 
 #[repr(C)]
 #[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AdapterDesc {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::utf16"))]
     pub description: [u16; 128],
     pub vendor_id: u32,
     pub device_id: u32,
@@ -28,6 +35,16 @@ impl AdapterDesc {
         let len = wstrlens(&self.description);
         String::from_utf16_lossy(&self.description[..len])
     }
+
+    /// The PCI ids, to look up with `dxgi::pci`.
+    pub fn pci_id(&self) -> PciId {
+        PciId {
+            vendor_id: self.vendor_id,
+            device_id: self.device_id,
+            subsys_id: self.subsys_id,
+            revision: self.revision,
+        }
+    }
 }
 
 impl std::fmt::Debug for AdapterDesc {
@@ -58,7 +75,7 @@ impl std::fmt::Debug for AdapterDesc {
 #[cfg(test)]
 member_compat_test! {
     adapter_desc_compat:
-    AdapterDesc <=> DXGI_ADAPTER_DESC {
+    AdapterDesc <=> crate::descriptions::layouts::DXGI_ADAPTER_DESC {
         description <=> Description,
         vendor_id <=> VendorId,
         device_id <=> DeviceId,
@@ -71,12 +88,14 @@ member_compat_test! {
     }
 }
 
+#[cfg(windows)]
 impl From<DXGI_ADAPTER_DESC> for AdapterDesc {
     fn from(desc: DXGI_ADAPTER_DESC) -> AdapterDesc {
         unsafe { std::mem::transmute(desc) }
     }
 }
 
+#[cfg(windows)]
 impl From<AdapterDesc> for DXGI_ADAPTER_DESC {
     fn from(desc: AdapterDesc) -> DXGI_ADAPTER_DESC {
         unsafe { std::mem::transmute(desc) }
@@ -85,7 +104,9 @@ impl From<AdapterDesc> for DXGI_ADAPTER_DESC {
 
 #[repr(C)]
 #[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AdapterDesc1 {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::utf16"))]
     pub description: [u16; 128],
     pub vendor_id: u32,
     pub device_id: u32,
@@ -103,6 +124,16 @@ impl AdapterDesc1 {
         let len = wstrlens(&self.description);
         String::from_utf16_lossy(&self.description[..len])
     }
+
+    /// The PCI ids, to look up with `dxgi::pci`.
+    pub fn pci_id(&self) -> PciId {
+        PciId {
+            vendor_id: self.vendor_id,
+            device_id: self.device_id,
+            subsys_id: self.subsys_id,
+            revision: self.revision,
+        }
+    }
 }
 
 impl std::fmt::Debug for AdapterDesc1 {
@@ -134,7 +165,7 @@ impl std::fmt::Debug for AdapterDesc1 {
 #[cfg(test)]
 member_compat_test! {
     adapter_desc1_compat:
-    AdapterDesc1 <=> DXGI_ADAPTER_DESC1 {
+    AdapterDesc1 <=> crate::descriptions::layouts::DXGI_ADAPTER_DESC1 {
         description <=> Description,
         vendor_id <=> VendorId,
         device_id <=> DeviceId,
@@ -148,12 +179,14 @@ member_compat_test! {
     }
 }
 
+#[cfg(windows)]
 impl From<DXGI_ADAPTER_DESC1> for AdapterDesc1 {
     fn from(desc: DXGI_ADAPTER_DESC1) -> AdapterDesc1 {
         unsafe { std::mem::transmute(desc) }
     }
 }
 
+#[cfg(windows)]
 impl From<AdapterDesc1> for DXGI_ADAPTER_DESC1 {
     fn from(desc: AdapterDesc1) -> DXGI_ADAPTER_DESC1 {
         unsafe { std::mem::transmute(desc) }
@@ -162,7 +195,9 @@ impl From<AdapterDesc1> for DXGI_ADAPTER_DESC1 {
 
 #[repr(C)]
 #[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AdapterDesc2 {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::utf16"))]
     pub description: [u16; 128],
     pub vendor_id: u32,
     pub device_id: u32,
@@ -173,7 +208,9 @@ pub struct AdapterDesc2 {
     pub shared_system_memory: usize,
     pub adapter_luid: Luid,
     pub flags: AdapterFlags,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::unchecked"))]
     pub graphics_preemption_granularity: UncheckedEnum<GraphicsPreemptionGranularity>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::unchecked"))]
     pub compute_preemption_granularity: UncheckedEnum<ComputePreemptionGranularity>,
 }
 
@@ -182,6 +219,16 @@ impl AdapterDesc2 {
         let len = wstrlens(&self.description);
         String::from_utf16_lossy(&self.description[..len])
     }
+
+    /// The PCI ids, to look up with `dxgi::pci`.
+    pub fn pci_id(&self) -> PciId {
+        PciId {
+            vendor_id: self.vendor_id,
+            device_id: self.device_id,
+            subsys_id: self.subsys_id,
+            revision: self.revision,
+        }
+    }
 }
 
 impl std::fmt::Debug for AdapterDesc2 {
@@ -221,7 +268,7 @@ impl std::fmt::Debug for AdapterDesc2 {
 #[cfg(test)]
 member_compat_test! {
     adapter_desc2_compat:
-    AdapterDesc2 <=> DXGI_ADAPTER_DESC2 {
+    AdapterDesc2 <=> crate::descriptions::layouts::DXGI_ADAPTER_DESC2 {
         description <=> Description,
         vendor_id <=> VendorId,
         device_id <=> DeviceId,
@@ -237,12 +284,14 @@ member_compat_test! {
     }
 }
 
+#[cfg(windows)]
 impl From<DXGI_ADAPTER_DESC2> for AdapterDesc2 {
     fn from(desc: DXGI_ADAPTER_DESC2) -> AdapterDesc2 {
         unsafe { std::mem::transmute(desc) }
     }
 }
 
+#[cfg(windows)]
 impl From<AdapterDesc2> for DXGI_ADAPTER_DESC2 {
     fn from(desc: AdapterDesc2) -> DXGI_ADAPTER_DESC2 {
         unsafe { std::mem::transmute(desc) }
@@ -251,7 +300,9 @@ impl From<AdapterDesc2> for DXGI_ADAPTER_DESC2 {
 
 #[repr(C)]
 #[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AdapterDesc3 {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::utf16"))]
     pub description: [u16; 128],
     pub vendor_id: u32,
     pub device_id: u32,
@@ -262,7 +313,9 @@ pub struct AdapterDesc3 {
     pub shared_system_memory: usize,
     pub adapter_luid: Luid,
     pub flags: AdapterFlags,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::unchecked"))]
     pub graphics_preemption_granularity: UncheckedEnum<GraphicsPreemptionGranularity>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::unchecked"))]
     pub compute_preemption_granularity: UncheckedEnum<ComputePreemptionGranularity>,
 }
 
@@ -271,6 +324,16 @@ impl AdapterDesc3 {
         let len = wstrlens(&self.description);
         String::from_utf16_lossy(&self.description[..len])
     }
+
+    /// The PCI ids, to look up with `dxgi::pci`.
+    pub fn pci_id(&self) -> PciId {
+        PciId {
+            vendor_id: self.vendor_id,
+            device_id: self.device_id,
+            subsys_id: self.subsys_id,
+            revision: self.revision,
+        }
+    }
 }
 
 impl std::fmt::Debug for AdapterDesc3 {
@@ -310,7 +373,7 @@ impl std::fmt::Debug for AdapterDesc3 {
 #[cfg(test)]
 member_compat_test! {
     adapter_desc3_compat:
-    AdapterDesc3 <=> DXGI_ADAPTER_DESC3 {
+    AdapterDesc3 <=> crate::descriptions::layouts::DXGI_ADAPTER_DESC3 {
         description <=> Description,
         vendor_id <=> VendorID,
         device_id <=> DeviceID,
@@ -326,12 +389,14 @@ member_compat_test! {
     }
 }
 
+#[cfg(windows)]
 impl From<DXGI_ADAPTER_DESC3> for AdapterDesc3 {
     fn from(desc: DXGI_ADAPTER_DESC3) -> AdapterDesc3 {
         unsafe { std::mem::transmute(desc) }
     }
 }
 
+#[cfg(windows)]
 impl From<AdapterDesc3> for DXGI_ADAPTER_DESC3 {
     fn from(desc: AdapterDesc3) -> DXGI_ADAPTER_DESC3 {
         unsafe { std::mem::transmute(desc) }
@@ -46,11 +46,6 @@ impl PartialEq for DBool {
     fn eq(&self, rhs: &DBool) -> bool {
         bool::from(*self) == bool::from(*rhs)
     }
-
-    #[inline]
-    fn ne(&self, rhs: &DBool) -> bool {
-        bool::from(*self) != bool::from(*rhs)
-    }
 }
 
 impl PartialEq<bool> for DBool {
@@ -58,11 +53,6 @@ impl PartialEq<bool> for DBool {
     fn eq(&self, rhs: &bool) -> bool {
         bool::from(*self) == *rhs
     }
-
-    #[inline]
-    fn ne(&self, rhs: &bool) -> bool {
-        bool::from(*self) != *rhs
-    }
 }
 
 impl PartialEq<DBool> for bool {
@@ -70,9 +60,4 @@ impl PartialEq<DBool> for bool {
     fn eq(&self, rhs: &DBool) -> bool {
         *self == bool::from(*rhs)
     }
-
-    #[inline]
-    fn ne(&self, rhs: &DBool) -> bool {
-        *self != bool::from(*rhs)
-    }
 }
@@ -1,7 +1,13 @@
+#[cfg(windows)]
 use winapi::shared::dxgi::DXGI_FRAME_STATISTICS;
 
+pub use self::pacing::{FramePace, FramePacing, PacingSummary};
+
+mod pacing;
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FrameStatistics {
     pub present_count: u32,
     pub present_refresh_count: u32,
@@ -13,7 +19,7 @@ pub struct FrameStatistics {
 #[cfg(test)]
 member_compat_test! {
     frame_statistics_compat:
-    FrameStatistics <=> DXGI_FRAME_STATISTICS {
+    FrameStatistics <=> crate::descriptions::layouts::DXGI_FRAME_STATISTICS {
         present_count <=> PresentCount,
         present_refresh_count <=> PresentRefreshCount,
         sync_refresh_count <=> SyncRefreshCount,
@@ -22,12 +28,14 @@ member_compat_test! {
     }
 }
 
+#[cfg(windows)]
 impl From<DXGI_FRAME_STATISTICS> for FrameStatistics {
     fn from(desc: DXGI_FRAME_STATISTICS) -> FrameStatistics {
         unsafe { std::mem::transmute(desc) }
     }
 }
 
+#[cfg(windows)]
 impl From<FrameStatistics> for DXGI_FRAME_STATISTICS {
     fn from(desc: FrameStatistics) -> DXGI_FRAME_STATISTICS {
         unsafe { std::mem::transmute(desc) }
@@ -0,0 +1,350 @@
+use crate::descriptions::FrameStatistics;
+
+/// Turns a sequence of frame statistics into per-frame pacing reports.
+///
+/// After each `Present`, feed the analyzer the swap chain's
+/// `last_present_count` and `frame_statistics`. Each sample is compared with
+/// the previous one to find how many new frames reached the screen, how many
+/// refreshes they took, and whether any refresh repeated an old frame or any
+/// frame was replaced before it could be shown.
+///
+/// All counter math is done with wrapping subtraction, so the analyzer keeps
+/// working when the `u32` present and refresh counters wrap around. A
+/// counter that moves backwards by less than half its range is treated as a
+/// discontinuity (e.g. after `DXGI_ERROR_FRAME_STATISTICS_DISJOINT`, a mode
+/// change or a new swap chain) and restarts the analysis, as does
+/// [`reset`](FramePacing::reset).
+#[derive(Clone, Debug)]
+pub struct FramePacing {
+    sync_interval: u32,
+    refresh_period: Option<f64>,
+    measured_period: bool,
+    previous: Option<Previous>,
+    summary: PacingSummary,
+}
+
+/// Pacing of the frames that reached the screen between two samples.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FramePace {
+    /// The present id that was passed in with this sample.
+    pub present_id: u32,
+    /// The present id of the most recently displayed frame.
+    pub displayed_present: u32,
+    /// The refresh (vblank) count at which that frame was first displayed.
+    pub displayed_refresh: u32,
+    /// Presents queued but not yet displayed when the sample was taken.
+    pub queue_depth: u32,
+    /// How many presents reached the screen since the previous sample. 0 if
+    /// the statistics have not changed.
+    pub new_frames: u32,
+    /// How many refreshes passed between the previously displayed frame and
+    /// this one.
+    pub refreshes: u32,
+    /// Refreshes beyond the sync interval that showed an old frame again,
+    /// i.e. missed vblanks.
+    pub repeated: u32,
+    /// Presents that were replaced before they could be shown for a full
+    /// sync interval.
+    pub dropped: u32,
+    /// Estimated performance counter time at which the displayed frame first
+    /// appeared, once the refresh period is known.
+    pub display_qpc: Option<i64>,
+    /// Performance counter ticks between the previously displayed frame and
+    /// this one.
+    pub interval: Option<i64>,
+    /// How much `interval` differs from the previous frame's interval, in
+    /// performance counter ticks.
+    pub jitter: Option<i64>,
+}
+
+impl FramePace {
+    /// Whether the frame was shown late or any frame was lost.
+    pub fn is_glitch(&self) -> bool {
+        self.repeated != 0 || self.dropped != 0
+    }
+}
+
+/// Running totals over all of the frames an analyzer has seen.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct PacingSummary {
+    /// Samples that were compared with a previous sample.
+    pub samples: u64,
+    /// Presents that reached the screen.
+    pub displayed: u64,
+    /// Refreshes that repeated an old frame.
+    pub repeated: u64,
+    /// Presents that were never shown for a full sync interval.
+    pub dropped: u64,
+    /// The deepest present queue that was observed.
+    pub max_queue_depth: u32,
+    /// The largest absolute jitter, in performance counter ticks.
+    pub max_jitter: u64,
+    /// The mean absolute jitter, in performance counter ticks.
+    pub mean_jitter: f64,
+    jitter_samples: u64,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Previous {
+    stats: FrameStatistics,
+    display_qpc: Option<i64>,
+    interval: Option<i64>,
+}
+
+impl FramePacing {
+    /// An analyzer for a swap chain presenting with a sync interval of 1.
+    pub fn new() -> Self {
+        FramePacing {
+            sync_interval: 1,
+            refresh_period: None,
+            measured_period: true,
+            previous: None,
+            summary: PacingSummary::default(),
+        }
+    }
+
+    /// The sync interval passed to `Present`; each frame is expected to stay
+    /// on screen for this many refreshes. 0 (no vsync) is counted as 1.
+    pub fn with_sync_interval(mut self, sync_interval: u32) -> Self {
+        self.sync_interval = sync_interval.max(1);
+        self
+    }
+
+    /// Use a known refresh period instead of measuring it from the sync
+    /// timestamps, e.g. from `Ratio::frame_period_qpc`.
+    pub fn with_refresh_period_qpc(mut self, ticks: u64) -> Self {
+        self.refresh_period = Some(ticks as f64);
+        self.measured_period = false;
+        self
+    }
+
+    /// The refresh period in performance counter ticks, if it is known.
+    pub fn refresh_period_qpc(&self) -> Option<f64> {
+        self.refresh_period
+    }
+
+    /// Totals over every sample pushed so far.
+    pub fn summary(&self) -> &PacingSummary {
+        &self.summary
+    }
+
+    /// Forgets the previous sample, so that the next one starts a new
+    /// sequence. The summary and refresh period are kept.
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+
+    /// Adds a sample and reports the pacing since the previous one. Returns
+    /// `None` for the first sample of a sequence, which has nothing to be
+    /// compared with.
+    pub fn push(&mut self, present_id: u32, stats: &FrameStatistics) -> Option<FramePace> {
+        let previous = match self.previous {
+            Some(previous) if is_forward(&previous.stats, stats) => previous,
+            _ => {
+                self.previous = Some(Previous {
+                    stats: *stats,
+                    display_qpc: self.display_qpc(stats),
+                    interval: None,
+                });
+                return None;
+            }
+        };
+
+        let prev = previous.stats;
+        let new_frames = stats.present_count.wrapping_sub(prev.present_count);
+        if self.measured_period {
+            let refreshes = stats
+                .sync_refresh_count
+                .wrapping_sub(prev.sync_refresh_count);
+            let ticks = stats.sync_qpc_time.wrapping_sub(prev.sync_qpc_time);
+            if refreshes != 0 && ticks > 0 {
+                self.refresh_period = Some(ticks as f64 / refreshes as f64);
+            }
+        }
+
+        let mut pace = FramePace {
+            present_id,
+            displayed_present: stats.present_count,
+            displayed_refresh: stats.present_refresh_count,
+            queue_depth: present_id.wrapping_sub(stats.present_count),
+            new_frames,
+            refreshes: 0,
+            repeated: 0,
+            dropped: 0,
+            display_qpc: previous.display_qpc,
+            interval: None,
+            jitter: None,
+        };
+
+        let mut current = previous;
+        current.stats.sync_refresh_count = stats.sync_refresh_count;
+        current.stats.sync_qpc_time = stats.sync_qpc_time;
+        if new_frames != 0 {
+            let refreshes = stats
+                .present_refresh_count
+                .wrapping_sub(prev.present_refresh_count);
+            let expected = new_frames.saturating_mul(self.sync_interval);
+            pace.refreshes = refreshes;
+            pace.repeated = refreshes.saturating_sub(expected);
+            pace.dropped = new_frames.saturating_sub(refreshes / self.sync_interval);
+
+            pace.display_qpc = self.display_qpc(stats);
+            pace.interval = match (previous.display_qpc, pace.display_qpc) {
+                (Some(a), Some(b)) => Some(b.wrapping_sub(a)),
+                _ => None,
+            };
+            pace.jitter = match (previous.interval, pace.interval) {
+                (Some(a), Some(b)) => Some(b.wrapping_sub(a)),
+                _ => None,
+            };
+
+            current = Previous {
+                stats: *stats,
+                display_qpc: pace.display_qpc,
+                interval: pace.interval,
+            };
+        }
+        self.previous = Some(current);
+        self.summary.add(&pace);
+        Some(pace)
+    }
+
+    fn display_qpc(&self, stats: &FrameStatistics) -> Option<i64> {
+        let period = self.refresh_period?;
+        let behind = stats
+            .sync_refresh_count
+            .wrapping_sub(stats.present_refresh_count);
+        Some(stats.sync_qpc_time - (behind as f64 * period).round() as i64)
+    }
+}
+
+impl Default for FramePacing {
+    fn default() -> Self {
+        FramePacing::new()
+    }
+}
+
+impl PacingSummary {
+    fn add(&mut self, pace: &FramePace) {
+        self.samples += 1;
+        self.displayed += pace.new_frames as u64;
+        self.repeated += pace.repeated as u64;
+        self.dropped += pace.dropped as u64;
+        self.max_queue_depth = self.max_queue_depth.max(pace.queue_depth);
+        if let Some(jitter) = pace.jitter {
+            let jitter = jitter.unsigned_abs();
+            self.jitter_samples += 1;
+            self.max_jitter = self.max_jitter.max(jitter);
+            self.mean_jitter += (jitter as f64 - self.mean_jitter) / self.jitter_samples as f64;
+        }
+    }
+}
+
+/// Whether every counter in `next` is at or past the one in `prev`, allowing
+/// for wraparound.
+fn is_forward(prev: &FrameStatistics, next: &FrameStatistics) -> bool {
+    let forward = |a: u32, b: u32| b.wrapping_sub(a) <= i32::MAX as u32;
+    forward(prev.present_count, next.present_count)
+        && forward(prev.present_refresh_count, next.present_refresh_count)
+        && forward(prev.sync_refresh_count, next.sync_refresh_count)
+}
+
+#[cfg(test)]
+fn stats(present: u32, present_refresh: u32, sync_refresh: u32) -> FrameStatistics {
+    FrameStatistics {
+        present_count: present,
+        present_refresh_count: present_refresh,
+        sync_refresh_count: sync_refresh,
+        sync_qpc_time: sync_refresh.wrapping_sub(u32::MAX - 10) as i64 * 1000,
+        sync_gpu_time: 0,
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn steady_and_glitches() {
+    let mut pacing = FramePacing::new();
+
+    // Two frames queued, each displayed on the next vblank.
+    assert_eq!(pacing.push(3, &stats(1, 100, 100)), None);
+    let pace = pacing.push(4, &stats(2, 101, 101)).unwrap();
+    assert_eq!(
+        (pace.new_frames, pace.refreshes, pace.queue_depth),
+        (1, 1, 2)
+    );
+    assert!(!pace.is_glitch());
+    assert_eq!(pacing.refresh_period_qpc(), Some(1000.0));
+
+    // Frame 3 missed a vblank, so frame 2 was shown twice.
+    let pace = pacing.push(5, &stats(3, 103, 103)).unwrap();
+    assert_eq!((pace.refreshes, pace.repeated, pace.dropped), (2, 1, 0));
+    assert_eq!(pace.interval, Some(2000));
+    assert_eq!(pace.jitter, None);
+
+    // Frames 4 and 5 both became ready for the same vblank and 4 was lost.
+    let pace = pacing.push(6, &stats(5, 104, 104)).unwrap();
+    assert_eq!((pace.new_frames, pace.repeated, pace.dropped), (2, 0, 1));
+    assert_eq!(pace.interval, Some(1000));
+    assert_eq!(pace.jitter, Some(-1000));
+
+    // Nothing new was displayed.
+    let pace = pacing.push(7, &stats(5, 104, 104)).unwrap();
+    assert_eq!((pace.new_frames, pace.queue_depth), (0, 2));
+    assert_eq!(pace.interval, None);
+
+    let summary = pacing.summary();
+    assert_eq!(summary.samples, 4);
+    assert_eq!(summary.displayed, 4);
+    assert_eq!((summary.repeated, summary.dropped), (1, 1));
+    assert_eq!(summary.max_queue_depth, 2);
+    assert_eq!(summary.max_jitter, 1000);
+    assert_eq!(summary.mean_jitter, 1000.0);
+}
+
+#[cfg(test)]
+#[test]
+fn sync_interval_and_known_period() {
+    let mut pacing = FramePacing::new()
+        .with_sync_interval(2)
+        .with_refresh_period_qpc(1000);
+
+    assert_eq!(pacing.push(1, &stats(1, 10, 11)), None);
+    let pace = pacing.push(2, &stats(2, 12, 12)).unwrap();
+    assert!(!pace.is_glitch());
+    assert_eq!(pace.display_qpc, Some(stats(2, 12, 12).sync_qpc_time));
+    assert_eq!(pace.interval, Some(2000));
+    assert_eq!(pacing.refresh_period_qpc(), Some(1000.0));
+
+    let pace = pacing.push(3, &stats(3, 15, 16)).unwrap();
+    assert_eq!((pace.refreshes, pace.repeated, pace.dropped), (3, 1, 0));
+    assert_eq!(
+        pace.display_qpc,
+        Some(stats(3, 15, 16).sync_qpc_time - 1000)
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn wraparound() {
+    let mut pacing = FramePacing::new();
+    let max = u32::MAX;
+
+    assert_eq!(pacing.push(max, &stats(max - 1, max - 1, max - 1)), None);
+    let pace = pacing.push(0, &stats(max, max, max)).unwrap();
+    assert_eq!(
+        (pace.new_frames, pace.refreshes, pace.queue_depth),
+        (1, 1, 1)
+    );
+
+    let pace = pacing.push(1, &stats(1, 1, 1)).unwrap();
+    assert_eq!((pace.new_frames, pace.refreshes), (2, 2));
+    assert_eq!((pace.repeated, pace.dropped), (0, 0));
+    assert_eq!(pace.interval, Some(2000));
+
+    // A counter going backwards starts a new sequence.
+    assert_eq!(pacing.push(2, &stats(0, 1, 1)), None);
+    assert!(pacing.push(3, &stats(1, 2, 2)).is_some());
+    pacing.reset();
+    assert_eq!(pacing.push(4, &stats(2, 3, 3)), None);
+    assert_eq!(pacing.summary().samples, 3);
+}
@@ -4,13 +4,17 @@ use crate::enums::ModeScaling;
 use crate::enums::ModeScanlineOrder;
 
 use checked_enum::UncheckedEnum;
+#[cfg(windows)]
 use winapi::shared::dxgi1_2::DXGI_SWAP_CHAIN_FULLSCREEN_DESC;
 
 #[repr(C)]
 #[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FullscreenDesc {
     pub refresh_rate: Ratio,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::unchecked"))]
     pub scanline_ordering: UncheckedEnum<ModeScanlineOrder>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::unchecked"))]
     pub scaling: UncheckedEnum<ModeScaling>,
     pub windowed: DBool,
 }
@@ -18,7 +22,7 @@ pub struct FullscreenDesc {
 #[cfg(test)]
 member_compat_test! {
     fullscreen_desc_compat:
-    FullscreenDesc <=> DXGI_SWAP_CHAIN_FULLSCREEN_DESC {
+    FullscreenDesc <=> crate::descriptions::layouts::DXGI_SWAP_CHAIN_FULLSCREEN_DESC {
         refresh_rate <=> RefreshRate,
         scanline_ordering <=> ScanlineOrdering,
         scaling <=> Scaling,
@@ -26,6 +30,7 @@ member_compat_test! {
     }
 }
 
+#[cfg(windows)]
 impl From<DXGI_SWAP_CHAIN_FULLSCREEN_DESC> for FullscreenDesc {
     #[inline]
     fn from(desc: DXGI_SWAP_CHAIN_FULLSCREEN_DESC) -> FullscreenDesc {
@@ -33,6 +38,7 @@ impl From<DXGI_SWAP_CHAIN_FULLSCREEN_DESC> for FullscreenDesc {
     }
 }
 
+#[cfg(windows)]
 impl From<FullscreenDesc> for DXGI_SWAP_CHAIN_FULLSCREEN_DESC {
     #[inline]
     fn from(desc: FullscreenDesc) -> DXGI_SWAP_CHAIN_FULLSCREEN_DESC {
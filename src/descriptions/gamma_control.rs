@@ -1,32 +1,39 @@
 use crate::descriptions::DBool;
 use crate::descriptions::Rgb;
 
+#[cfg(windows)]
 use winapi::shared::dxgitype::{DXGI_GAMMA_CONTROL, DXGI_GAMMA_CONTROL_CAPABILITIES};
 
+mod ramp;
+
 #[repr(C)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GammaControl {
     pub scale: Rgb,
     pub offset: Rgb,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::array"))]
     pub gamma_curve: [Rgb; 1025],
 }
 
 #[cfg(test)]
 member_compat_test! {
     gamma_control_compat:
-    GammaControl <=> DXGI_GAMMA_CONTROL {
+    GammaControl <=> crate::descriptions::layouts::DXGI_GAMMA_CONTROL {
         scale <=> Scale,
         offset <=> Offset,
         gamma_curve <=> GammaCurve,
     }
 }
 
+#[cfg(windows)]
 impl From<DXGI_GAMMA_CONTROL> for GammaControl {
     fn from(desc: DXGI_GAMMA_CONTROL) -> GammaControl {
         unsafe { std::mem::transmute(desc) }
     }
 }
 
+#[cfg(windows)]
 impl From<GammaControl> for DXGI_GAMMA_CONTROL {
     fn from(desc: GammaControl) -> DXGI_GAMMA_CONTROL {
         unsafe { std::mem::transmute(desc) }
@@ -34,7 +41,7 @@ impl From<GammaControl> for DXGI_GAMMA_CONTROL {
 }
 
 #[repr(C)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub struct GammaControlCaps {
     pub scale_and_offset_supported: DBool,
     pub max_converted_value: f32,
@@ -46,7 +53,7 @@ pub struct GammaControlCaps {
 #[cfg(test)]
 member_compat_test! {
     gamma_control_caps_compat:
-    GammaControlCaps <=> DXGI_GAMMA_CONTROL_CAPABILITIES {
+    GammaControlCaps <=> crate::descriptions::layouts::DXGI_GAMMA_CONTROL_CAPABILITIES {
         scale_and_offset_supported <=> ScaleAndOffsetSupported,
         max_converted_value <=> MaxConvertedValue,
         min_converted_value <=> MinConvertedValue,
@@ -55,12 +62,14 @@ member_compat_test! {
     }
 }
 
+#[cfg(windows)]
 impl From<DXGI_GAMMA_CONTROL_CAPABILITIES> for GammaControlCaps {
     fn from(desc: DXGI_GAMMA_CONTROL_CAPABILITIES) -> GammaControlCaps {
         unsafe { std::mem::transmute(desc) }
     }
 }
 
+#[cfg(windows)]
 impl From<GammaControlCaps> for DXGI_GAMMA_CONTROL_CAPABILITIES {
     fn from(desc: GammaControlCaps) -> DXGI_GAMMA_CONTROL_CAPABILITIES {
         unsafe { std::mem::transmute(desc) }
@@ -0,0 +1,189 @@
+use crate::codec::linear_to_srgb;
+use crate::descriptions::{DBool, GammaControl, GammaControlCaps, Rgb};
+
+impl GammaControl {
+    /// A ramp that leaves every value unchanged.
+    pub fn identity(caps: &GammaControlCaps) -> GammaControl {
+        GammaControl::from_curve(caps, |x| x)
+    }
+
+    /// A power-law ramp mapping `x` to `x^(1 / gamma)`, so a `gamma` above 1
+    /// brightens the midtones and one below 1 darkens them. A `gamma` that
+    /// isn't positive gives the identity ramp.
+    pub fn gamma(caps: &GammaControlCaps, gamma: f32) -> GammaControl {
+        if gamma > 0.0 {
+            GammaControl::from_curve(caps, |x| x.max(0.0).powf(1.0 / gamma))
+        } else {
+            GammaControl::identity(caps)
+        }
+    }
+
+    /// A ramp that applies the sRGB transfer function, for scanning out
+    /// linear light content on an sRGB display.
+    pub fn srgb(caps: &GammaControlCaps) -> GammaControl {
+        GammaControl::from_curve(caps, linear_to_srgb)
+    }
+
+    /// A ramp mapping `x` to `(x - 0.5) * contrast + 0.5 + brightness`.
+    /// A brightness of 0 and a contrast of 1 give the identity ramp.
+    pub fn brightness_contrast(
+        caps: &GammaControlCaps,
+        brightness: f32,
+        contrast: f32,
+    ) -> GammaControl {
+        GammaControl::from_curve(caps, |x| (x - 0.5) * contrast + 0.5 + brightness)
+    }
+
+    /// A ramp that applies the same curve to all three channels. See
+    /// `from_curves`.
+    pub fn from_curve(caps: &GammaControlCaps, curve: impl Fn(f32) -> f32) -> GammaControl {
+        GammaControl::from_curves(caps, &curve, &curve, &curve)
+    }
+
+    /// A ramp with a separate curve for each channel.
+    ///
+    /// Each curve is sampled at the output's control point positions, and
+    /// the results are clamped to the output's converted value range. Unused
+    /// entries past `num_gamma_control_points` are left at 0. The scale is 1
+    /// and the offset 0.
+    pub fn from_curves(
+        caps: &GammaControlCaps,
+        red: impl Fn(f32) -> f32,
+        green: impl Fn(f32) -> f32,
+        blue: impl Fn(f32) -> f32,
+    ) -> GammaControl {
+        let clamp = |value: f32| {
+            value
+                .max(caps.min_converted_value)
+                .min(caps.max_converted_value)
+        };
+
+        let mut control = GammaControl {
+            scale: Rgb {
+                r: 1.0,
+                g: 1.0,
+                b: 1.0,
+            },
+            offset: Rgb {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+            },
+            gamma_curve: [Rgb {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+            }; 1025],
+        };
+        for (point, &x) in control.gamma_curve.iter_mut().zip(caps.control_points()) {
+            *point = Rgb {
+                r: clamp(red(x)),
+                g: clamp(green(x)),
+                b: clamp(blue(x)),
+            };
+        }
+        control
+    }
+
+    /// The entries of the curve that the output uses.
+    pub fn control_points(&self, caps: &GammaControlCaps) -> &[Rgb] {
+        &self.gamma_curve[..caps.control_points().len()]
+    }
+}
+
+impl GammaControlCaps {
+    /// Capabilities with `num_points` evenly spaced control points from 0 to
+    /// 1 and the given converted value range, for building ramps without a
+    /// fullscreen output.
+    pub fn uniform(num_points: u32, min_converted_value: f32, max_converted_value: f32) -> Self {
+        let num_points = num_points.min(1025);
+        let mut control_point_positions = [0.0; 1025];
+        if num_points == 1 {
+            control_point_positions[0] = 1.0;
+        }
+        for (i, position) in control_point_positions[..num_points as usize]
+            .iter_mut()
+            .enumerate()
+            .skip(1)
+        {
+            *position = i as f32 / (num_points - 1) as f32;
+        }
+
+        GammaControlCaps {
+            scale_and_offset_supported: DBool::FALSE,
+            max_converted_value,
+            min_converted_value,
+            num_gamma_control_points: num_points,
+            control_point_positions,
+        }
+    }
+
+    /// The positions of the control points that the output uses.
+    pub fn control_points(&self) -> &[f32] {
+        let len = (self.num_gamma_control_points as usize).min(1025);
+        &self.control_point_positions[..len]
+    }
+}
+
+#[cfg(test)]
+fn assert_close(actual: &[Rgb], expected: &[f32]) {
+    assert_eq!(actual.len(), expected.len());
+    for (a, &e) in actual.iter().zip(expected) {
+        for &channel in &[a.r, a.g, a.b] {
+            assert!((channel - e).abs() < 1e-5, "{:?} != {:?}", actual, expected);
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn standard_ramps() {
+    let caps = GammaControlCaps::uniform(5, 0.0, 1.0);
+    assert_eq!(caps.control_points(), &[0.0, 0.25, 0.5, 0.75, 1.0]);
+
+    let identity = GammaControl::identity(&caps);
+    assert_close(identity.control_points(&caps), &[0.0, 0.25, 0.5, 0.75, 1.0]);
+    assert_eq!(identity.scale.g, 1.0);
+    assert_eq!(identity.gamma_curve[5].r, 0.0);
+
+    let gamma = GammaControl::gamma(&caps, 2.0);
+    assert_close(
+        gamma.control_points(&caps),
+        &[0.0, 0.5, std::f32::consts::FRAC_1_SQRT_2, 0.866_025_4, 1.0],
+    );
+    let invalid = GammaControl::gamma(&caps, 0.0);
+    assert_close(invalid.control_points(&caps), &[0.0, 0.25, 0.5, 0.75, 1.0]);
+
+    let srgb = GammaControl::srgb(&caps);
+    assert_close(
+        srgb.control_points(&caps),
+        &[0.0, 0.537_099_5, 0.735_356_7, 0.880_824_3, 1.0],
+    );
+
+    let adjusted = GammaControl::brightness_contrast(&caps, 0.1, 2.0);
+    assert_close(adjusted.control_points(&caps), &[0.0, 0.1, 0.6, 1.0, 1.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn resampling_and_clamping() {
+    let mut caps = GammaControlCaps::uniform(3, 0.25, 0.75);
+    caps.control_point_positions[1] = 0.1;
+    let ramp = GammaControl::from_curves(&caps, |x| x, |x| 1.0 - x, |_| f32::NAN);
+    let points = ramp.control_points(&caps);
+    assert_eq!(points.len(), 3);
+    assert_eq!([points[0].r, points[1].r, points[2].r], [0.25, 0.25, 0.75]);
+    assert_eq!([points[0].g, points[1].g, points[2].g], [0.75, 0.75, 0.25]);
+    assert_eq!([points[0].b, points[1].b, points[2].b], [0.25, 0.25, 0.25]);
+
+    let full = GammaControlCaps::uniform(2000, 0.0, 1.0);
+    assert_eq!(full.control_points().len(), 1025);
+    assert_eq!(full.control_points()[512], 0.5);
+    assert_eq!(
+        GammaControlCaps::uniform(1, 0.0, 1.0).control_points(),
+        &[1.0]
+    );
+    assert!(GammaControlCaps::uniform(0, 0.0, 1.0)
+        .control_points()
+        .is_empty());
+}
@@ -0,0 +1,232 @@
+//! The DXGI structs the descriptions are layout compatible with, for the
+//! `member_compat_test!` checks. On Windows these are the winapi definitions;
+//! everywhere else they are transcribed from the Windows SDK headers so the
+//! layout checks still run.
+
+#![allow(non_camel_case_types, non_snake_case, clippy::upper_case_acronyms)]
+
+#[cfg(windows)]
+pub use winapi::shared::dxgi::{
+    DXGI_ADAPTER_DESC, DXGI_ADAPTER_DESC1, DXGI_FRAME_STATISTICS, DXGI_OUTPUT_DESC,
+    DXGI_SURFACE_DESC, DXGI_SWAP_CHAIN_DESC,
+};
+#[cfg(windows)]
+pub use winapi::shared::dxgi1_2::{
+    DXGI_ADAPTER_DESC2, DXGI_SWAP_CHAIN_DESC1, DXGI_SWAP_CHAIN_FULLSCREEN_DESC,
+};
+#[cfg(windows)]
+pub use winapi::shared::dxgi1_4::DXGI_QUERY_VIDEO_MEMORY_INFO;
+#[cfg(windows)]
+pub use winapi::shared::dxgi1_6::DXGI_ADAPTER_DESC3;
+#[cfg(windows)]
+pub use winapi::shared::dxgitype::{
+    DXGI_GAMMA_CONTROL, DXGI_GAMMA_CONTROL_CAPABILITIES, DXGI_MODE_DESC, DXGI_RATIONAL, DXGI_RGB,
+    DXGI_RGBA, DXGI_SAMPLE_DESC,
+};
+
+#[cfg(not(windows))]
+pub use self::mirror::*;
+
+#[cfg(not(windows))]
+mod mirror {
+    type BOOL = i32;
+    type WCHAR = u16;
+    type SIZE_T = usize;
+    type LARGE_INTEGER = i64;
+    type HANDLE = *mut std::ffi::c_void;
+
+    #[repr(C)]
+    pub struct LUID {
+        pub LowPart: u32,
+        pub HighPart: i32,
+    }
+
+    #[repr(C)]
+    pub struct RECT {
+        pub left: i32,
+        pub top: i32,
+        pub right: i32,
+        pub bottom: i32,
+    }
+
+    #[repr(C)]
+    pub struct DXGI_RATIONAL {
+        pub Numerator: u32,
+        pub Denominator: u32,
+    }
+
+    #[repr(C)]
+    pub struct DXGI_SAMPLE_DESC {
+        pub Count: u32,
+        pub Quality: u32,
+    }
+
+    #[repr(C)]
+    pub struct DXGI_RGB {
+        pub Red: f32,
+        pub Green: f32,
+        pub Blue: f32,
+    }
+
+    #[repr(C)]
+    pub struct DXGI_RGBA {
+        pub r: f32,
+        pub g: f32,
+        pub b: f32,
+        pub a: f32,
+    }
+
+    #[repr(C)]
+    pub struct DXGI_GAMMA_CONTROL {
+        pub Scale: DXGI_RGB,
+        pub Offset: DXGI_RGB,
+        pub GammaCurve: [DXGI_RGB; 1025],
+    }
+
+    #[repr(C)]
+    pub struct DXGI_GAMMA_CONTROL_CAPABILITIES {
+        pub ScaleAndOffsetSupported: BOOL,
+        pub MaxConvertedValue: f32,
+        pub MinConvertedValue: f32,
+        pub NumGammaControlPoints: u32,
+        pub ControlPointPositions: [f32; 1025],
+    }
+
+    #[repr(C)]
+    pub struct DXGI_MODE_DESC {
+        pub Width: u32,
+        pub Height: u32,
+        pub RefreshRate: DXGI_RATIONAL,
+        pub Format: u32,
+        pub ScanlineOrdering: u32,
+        pub Scaling: u32,
+    }
+
+    #[repr(C)]
+    pub struct DXGI_ADAPTER_DESC {
+        pub Description: [WCHAR; 128],
+        pub VendorId: u32,
+        pub DeviceId: u32,
+        pub SubSysId: u32,
+        pub Revision: u32,
+        pub DedicatedVideoMemory: SIZE_T,
+        pub DedicatedSystemMemory: SIZE_T,
+        pub SharedSystemMemory: SIZE_T,
+        pub AdapterLuid: LUID,
+    }
+
+    #[repr(C)]
+    pub struct DXGI_ADAPTER_DESC1 {
+        pub Description: [WCHAR; 128],
+        pub VendorId: u32,
+        pub DeviceId: u32,
+        pub SubSysId: u32,
+        pub Revision: u32,
+        pub DedicatedVideoMemory: SIZE_T,
+        pub DedicatedSystemMemory: SIZE_T,
+        pub SharedSystemMemory: SIZE_T,
+        pub AdapterLuid: LUID,
+        pub Flags: u32,
+    }
+
+    #[repr(C)]
+    pub struct DXGI_ADAPTER_DESC2 {
+        pub Description: [WCHAR; 128],
+        pub VendorId: u32,
+        pub DeviceId: u32,
+        pub SubSysId: u32,
+        pub Revision: u32,
+        pub DedicatedVideoMemory: SIZE_T,
+        pub DedicatedSystemMemory: SIZE_T,
+        pub SharedSystemMemory: SIZE_T,
+        pub AdapterLuid: LUID,
+        pub Flags: u32,
+        pub GraphicsPreemptionGranularity: u32,
+        pub ComputePreemptionGranularity: u32,
+    }
+
+    #[repr(C)]
+    pub struct DXGI_ADAPTER_DESC3 {
+        pub Description: [WCHAR; 128],
+        pub VendorID: u32,
+        pub DeviceID: u32,
+        pub SubSysID: u32,
+        pub Revision: u32,
+        pub DedicatedVideoMemory: SIZE_T,
+        pub DedicatedSystemMemory: SIZE_T,
+        pub SharedSystemMemory: SIZE_T,
+        pub AdapterLuid: LUID,
+        pub Flags: u32,
+        pub GraphicsPreemptionGranularity: u32,
+        pub ComputePreemptionGranularity: u32,
+    }
+
+    #[repr(C)]
+    pub struct DXGI_OUTPUT_DESC {
+        pub DeviceName: [WCHAR; 32],
+        pub DesktopCoordinates: RECT,
+        pub AttachedToDesktop: BOOL,
+        pub Rotation: u32,
+        pub Monitor: HANDLE,
+    }
+
+    #[repr(C)]
+    pub struct DXGI_FRAME_STATISTICS {
+        pub PresentCount: u32,
+        pub PresentRefreshCount: u32,
+        pub SyncRefreshCount: u32,
+        pub SyncQPCTime: LARGE_INTEGER,
+        pub SyncGPUTime: LARGE_INTEGER,
+    }
+
+    #[repr(C)]
+    pub struct DXGI_SURFACE_DESC {
+        pub Width: u32,
+        pub Height: u32,
+        pub Format: u32,
+        pub SampleDesc: DXGI_SAMPLE_DESC,
+    }
+
+    #[repr(C)]
+    pub struct DXGI_SWAP_CHAIN_DESC {
+        pub BufferDesc: DXGI_MODE_DESC,
+        pub SampleDesc: DXGI_SAMPLE_DESC,
+        pub BufferUsage: u32,
+        pub BufferCount: u32,
+        pub OutputWindow: HANDLE,
+        pub Windowed: BOOL,
+        pub SwapEffect: u32,
+        pub Flags: u32,
+    }
+
+    #[repr(C)]
+    pub struct DXGI_SWAP_CHAIN_DESC1 {
+        pub Width: u32,
+        pub Height: u32,
+        pub Format: u32,
+        pub Stereo: BOOL,
+        pub SampleDesc: DXGI_SAMPLE_DESC,
+        pub BufferUsage: u32,
+        pub BufferCount: u32,
+        pub Scaling: u32,
+        pub SwapEffect: u32,
+        pub AlphaMode: u32,
+        pub Flags: u32,
+    }
+
+    #[repr(C)]
+    pub struct DXGI_SWAP_CHAIN_FULLSCREEN_DESC {
+        pub RefreshRate: DXGI_RATIONAL,
+        pub ScanlineOrdering: u32,
+        pub Scaling: u32,
+        pub Windowed: BOOL,
+    }
+
+    #[repr(C)]
+    pub struct DXGI_QUERY_VIDEO_MEMORY_INFO {
+        pub Budget: u64,
+        pub CurrentUsage: u64,
+        pub AvailableForReservation: u64,
+        pub CurrentReservation: u64,
+    }
+}
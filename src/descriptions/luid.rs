@@ -32,6 +32,7 @@ impl From<Luid> for i64 {
     }
 }
 
+#[cfg(windows)]
 impl From<winapi::shared::ntdef::LUID> for Luid {
     fn from(l: winapi::shared::ntdef::LUID) -> Luid {
         Luid {
@@ -41,6 +42,7 @@ impl From<winapi::shared::ntdef::LUID> for Luid {
     }
 }
 
+#[cfg(windows)]
 impl From<Luid> for winapi::shared::ntdef::LUID {
     fn from(l: Luid) -> Self {
         Self {
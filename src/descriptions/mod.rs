@@ -29,11 +29,24 @@ pub use self::surface::*;
 #[doc(inline)]
 pub use self::swap_chain::*;
 
+#[cfg(windows)]
+#[doc(no_inline)]
+pub use winapi::shared::windef::{HMONITOR, HWND};
+
+/// Stand-in for the Win32 `HWND` handle type on targets other than Windows.
+#[cfg(not(windows))]
+pub type HWND = *mut std::ffi::c_void;
+/// Stand-in for the Win32 `HMONITOR` handle type on targets other than Windows.
+#[cfg(not(windows))]
+pub type HMONITOR = *mut std::ffi::c_void;
+
 mod adapter;
 mod dbool;
 mod frame_statistics;
 mod fullscreen;
 mod gamma_control;
+#[cfg(test)]
+mod layouts;
 mod luid;
 mod mode;
 mod output;
@@ -2,16 +2,25 @@ use crate::descriptions::Ratio;
 use crate::enums::{Format, ModeScaling, ModeScanlineOrder};
 
 use checked_enum::UncheckedEnum;
+#[cfg(windows)]
 use winapi::shared::dxgitype::DXGI_MODE_DESC;
 
+pub use self::matching::{find_closest_mode, rank_modes, ModeMatch, ModeScore};
+
+mod matching;
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mode {
     pub width: u32,
     pub height: u32,
     pub refresh_rate: Ratio,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::unchecked"))]
     pub format: UncheckedEnum<Format>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::unchecked"))]
     pub scanline_ordering: UncheckedEnum<ModeScanlineOrder>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::unchecked"))]
     pub scaling: UncheckedEnum<ModeScaling>,
 }
 
@@ -31,7 +40,7 @@ impl Default for Mode {
 #[cfg(test)]
 member_compat_test! {
     mode_compat:
-    Mode <=> DXGI_MODE_DESC {
+    Mode <=> crate::descriptions::layouts::DXGI_MODE_DESC {
         width <=> Width,
         height <=> Height,
         refresh_rate <=> RefreshRate,
@@ -41,12 +50,14 @@ member_compat_test! {
     }
 }
 
+#[cfg(windows)]
 impl From<DXGI_MODE_DESC> for Mode {
     fn from(desc: DXGI_MODE_DESC) -> Mode {
         unsafe { std::mem::transmute(desc) }
     }
 }
 
+#[cfg(windows)]
 impl From<Mode> for DXGI_MODE_DESC {
     fn from(desc: Mode) -> DXGI_MODE_DESC {
         unsafe { std::mem::transmute(desc) }
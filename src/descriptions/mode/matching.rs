@@ -0,0 +1,287 @@
+use crate::descriptions::Mode;
+use crate::enums::{Format, ModeScaling, ModeScanlineOrder};
+
+use std::fmt;
+
+#[cfg(test)]
+use crate::helpers::fixtures::mode;
+
+/// How far a display mode is from a partially specified target mode.
+///
+/// Each field is a penalty for one property of the mode, and 0 means the
+/// property matched (or was unspecified in the target). The fields are
+/// declared in the order `FindClosestMatchingMode` gives the properties
+/// priority, so comparing two scores compares the penalties one after
+/// another and a lower score is a better match.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ModeScore {
+    /// 1 if the target specifies a scanline ordering and the mode's differs.
+    pub scanline_ordering: u32,
+    /// 1 if the target specifies a scaling and the mode's differs.
+    pub scaling: u32,
+    /// 0 for the same format, 1 for a format in the same cast family (e.g.
+    /// the sRGB variant), 2 for a format with the same bits per pixel and 3
+    /// for anything else.
+    pub format: u32,
+    /// The sum of the width and height differences, in pixels.
+    pub resolution: u64,
+    /// The refresh rate difference, in millihertz. `u64::MAX` if the target
+    /// specifies a refresh rate and the mode doesn't have one.
+    pub refresh_rate: u64,
+}
+
+impl ModeScore {
+    /// Whether every specified property of the target matched exactly.
+    pub fn is_exact(&self) -> bool {
+        *self == ModeScore::default()
+    }
+}
+
+impl fmt::Display for ModeScore {
+    /// Explains the score, e.g. `"format differs, resolution off by 80px"`.
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_exact() {
+            return fmt.write_str("exact match");
+        }
+
+        let mut reasons = Vec::new();
+        if self.scanline_ordering != 0 {
+            reasons.push("scanline ordering differs".to_string());
+        }
+        if self.scaling != 0 {
+            reasons.push("scaling differs".to_string());
+        }
+        match self.format {
+            0 => (),
+            1 => reasons.push("format is cast compatible".to_string()),
+            2 => reasons.push("format has the same pixel size".to_string()),
+            _ => reasons.push("format differs".to_string()),
+        }
+        if self.resolution != 0 {
+            reasons.push(format!("resolution off by {}px", self.resolution));
+        }
+        if self.refresh_rate == u64::MAX {
+            reasons.push("refresh rate is unspecified".to_string());
+        } else if self.refresh_rate != 0 {
+            let hz = self.refresh_rate as f64 / 1000.0;
+            reasons.push(format!("refresh rate off by {:.3}Hz", hz));
+        }
+        fmt.write_str(&reasons.join(", "))
+    }
+}
+
+/// A candidate mode found by [`find_closest_mode`] or [`rank_modes`].
+#[derive(Copy, Clone, Debug)]
+pub struct ModeMatch {
+    /// Index of the mode in the searched list.
+    pub index: usize,
+    pub mode: Mode,
+    pub score: ModeScore,
+}
+
+impl Mode {
+    /// Scores how well this mode matches a partially specified `target`.
+    ///
+    /// Unspecified properties of the target act as wildcards and never add a
+    /// penalty: a width or height of 0, a refresh rate with a 0 numerator or
+    /// denominator, `Format::Unknown`, `ModeScanlineOrder::Unspecified` and
+    /// `ModeScaling::Unspecified`.
+    pub fn match_score(&self, target: &Mode) -> ModeScore {
+        let scanline_ordering = target.scanline_ordering.value
+            != ModeScanlineOrder::Unspecified as u32
+            && self.scanline_ordering.value != target.scanline_ordering.value;
+        let scaling = target.scaling.value != ModeScaling::Unspecified as u32
+            && self.scaling.value != target.scaling.value;
+
+        let format = if target.format.value == Format::Unknown as u32
+            || self.format.value == target.format.value
+        {
+            0
+        } else {
+            match (self.format.as_enum(), target.format.as_enum()) {
+                (Some(a), Some(b)) if a.is_cast_compatible(b) => 1,
+                (Some(a), Some(b)) if a.info().bits_per_element == b.info().bits_per_element => 2,
+                _ => 3,
+            }
+        };
+
+        let distance = |a: u32, b: u32| {
+            if b == 0 {
+                0
+            } else {
+                (a as i64 - b as i64).abs()
+            }
+        };
+        let resolution = distance(self.width, target.width) + distance(self.height, target.height);
+
+        let refresh = target.refresh_rate;
+        let refresh_rate = if refresh.numerator == 0 || refresh.denominator == 0 {
+            0
+        } else if self.refresh_rate.is_unspecified() {
+            u64::MAX
+        } else {
+            ((self.refresh_rate.to_f64() - refresh.to_f64()).abs() * 1000.0).round() as u64
+        };
+
+        ModeScore {
+            scanline_ordering: scanline_ordering as u32,
+            scaling: scaling as u32,
+            format,
+            resolution: resolution as u64,
+            refresh_rate,
+        }
+    }
+}
+
+/// Finds the mode in `modes` that most closely matches a partially specified
+/// `target`, or `None` if `modes` is empty.
+///
+/// Modes are compared by [`ModeScore`]. Between modes with the same score
+/// the larger resolution wins, then the higher refresh rate, then the mode
+/// that comes first in the list, so the result does not depend on how the
+/// list was sorted unless it contains duplicates.
+pub fn find_closest_mode(modes: &[Mode], target: &Mode) -> Option<ModeMatch> {
+    scored(modes, target).min_by(compare)
+}
+
+/// Scores every mode in `modes` against `target` and sorts them from the
+/// best match to the worst, using the same order as [`find_closest_mode`].
+pub fn rank_modes(modes: &[Mode], target: &Mode) -> Vec<ModeMatch> {
+    let mut matches: Vec<ModeMatch> = scored(modes, target).collect();
+    matches.sort_by(compare);
+    matches
+}
+
+fn scored<'a>(modes: &'a [Mode], target: &'a Mode) -> impl Iterator<Item = ModeMatch> + 'a {
+    modes
+        .iter()
+        .enumerate()
+        .map(move |(index, mode)| ModeMatch {
+            index,
+            mode: *mode,
+            score: mode.match_score(target),
+        })
+}
+
+fn compare(a: &ModeMatch, b: &ModeMatch) -> std::cmp::Ordering {
+    let area = |m: &ModeMatch| m.mode.width as u64 * m.mode.height as u64;
+    a.score
+        .cmp(&b.score)
+        .then_with(|| area(b).cmp(&area(a)))
+        .then_with(|| b.mode.refresh_rate.cmp(&a.mode.refresh_rate))
+        .then_with(|| a.index.cmp(&b.index))
+}
+
+#[cfg(test)]
+fn monitor_modes() -> Vec<Mode> {
+    vec![
+        mode(1280, 720, (60, 1), Format::B8G8R8A8Unorm),
+        mode(1920, 1080, (60000, 1001), Format::B8G8R8A8Unorm),
+        mode(1920, 1080, (60, 1), Format::B8G8R8A8Unorm),
+        mode(1920, 1080, (144, 1), Format::B8G8R8A8Unorm),
+        mode(1920, 1080, (60, 1), Format::R10G10B10A2Unorm),
+        Mode {
+            scaling: ModeScaling::Stretched.into(),
+            ..mode(1280, 720, (60, 1), Format::B8G8R8A8Unorm)
+        },
+    ]
+}
+
+#[cfg(test)]
+#[test]
+fn exact_and_nearest() {
+    let modes = monitor_modes();
+
+    let found = find_closest_mode(&modes, &mode(1920, 1080, (60, 1), Format::B8G8R8A8Unorm));
+    let found = found.unwrap();
+    assert_eq!(found.index, 2);
+    assert!(found.score.is_exact());
+    assert_eq!(found.score.to_string(), "exact match");
+
+    let found = find_closest_mode(&modes, &mode(1900, 1000, (59, 1), Format::B8G8R8A8Unorm));
+    let found = found.unwrap();
+    assert_eq!(found.index, 1);
+    assert_eq!(found.score.resolution, 100);
+    assert_eq!(found.score.refresh_rate, 940);
+    assert_eq!(
+        found.score.to_string(),
+        "resolution off by 100px, refresh rate off by 0.940Hz"
+    );
+
+    assert!(find_closest_mode(&[], &Mode::default()).is_none());
+}
+
+#[cfg(test)]
+#[test]
+fn wildcards() {
+    let modes = monitor_modes();
+
+    // Nothing specified: the largest, fastest mode wins.
+    let target = mode(0, 0, (0, 0), Format::Unknown);
+    let found = find_closest_mode(&modes, &target).unwrap();
+    assert_eq!(found.index, 3);
+    assert!(found.score.is_exact());
+
+    // Only the height is specified.
+    let target = mode(0, 720, (0, 0), Format::B8G8R8A8Unorm);
+    assert_eq!(find_closest_mode(&modes, &target).unwrap().index, 0);
+
+    // Only the scaling is specified.
+    let target = Mode {
+        scaling: ModeScaling::Stretched.into(),
+        ..mode(0, 0, (0, 0), Format::Unknown)
+    };
+    assert_eq!(find_closest_mode(&modes, &target).unwrap().index, 5);
+}
+
+#[cfg(test)]
+#[test]
+fn unspecified_refresh_rates() {
+    let broken = mode(1920, 1080, (60, 0), Format::B8G8R8A8Unorm);
+    let modes = [broken, mode(1920, 1080, (30, 1), Format::B8G8R8A8Unorm)];
+
+    let target = mode(1920, 1080, (60, 1), Format::B8G8R8A8Unorm);
+    let score = broken.match_score(&target);
+    assert_eq!(score.refresh_rate, u64::MAX);
+    assert_eq!(score.to_string(), "refresh rate is unspecified");
+    assert_eq!(find_closest_mode(&modes, &target).unwrap().index, 1);
+
+    // Without a target refresh rate the mode is as good as any other.
+    let target = mode(1920, 1080, (0, 0), Format::B8G8R8A8Unorm);
+    assert!(broken.match_score(&target).is_exact());
+}
+
+#[cfg(test)]
+#[test]
+fn priorities() {
+    let modes = monitor_modes();
+
+    // Format outranks resolution and refresh rate.
+    let target = mode(1280, 720, (144, 1), Format::R10G10B10A2Unorm);
+    let found = find_closest_mode(&modes, &target).unwrap();
+    assert_eq!(found.index, 4);
+    assert_eq!(found.score.format, 0);
+    assert_eq!(
+        found.score.to_string(),
+        "resolution off by 1000px, refresh rate off by 84.000Hz"
+    );
+
+    // A cast compatible format beats one that only shares the pixel size.
+    let target = mode(1920, 1080, (60, 1), Format::B8G8R8A8UnormSrgb);
+    let ranked = rank_modes(&modes, &target);
+    assert_eq!(ranked[0].index, 2);
+    assert_eq!(ranked[0].score.format, 1);
+    assert_eq!(ranked.last().unwrap().index, 4);
+    assert_eq!(ranked.last().unwrap().score.format, 2);
+
+    // Scaling outranks everything but scanline ordering.
+    let target = Mode {
+        scaling: ModeScaling::Stretched.into(),
+        ..mode(1920, 1080, (144, 1), Format::B8G8R8A8Unorm)
+    };
+    let ranked = rank_modes(&modes, &target);
+    assert_eq!(ranked[0].index, 5);
+    assert_eq!(ranked[1].index, 3);
+    assert_eq!(ranked[1].score.to_string(), "scaling differs");
+    assert!(ranked.windows(2).all(|w| w[0].score <= w[1].score));
+}
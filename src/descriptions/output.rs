@@ -1,19 +1,28 @@
-use crate::descriptions::DBool;
+use crate::descriptions::{DBool, HMONITOR};
 use crate::enums::ModeRotation;
 use crate::helpers::wstrlens;
 
 use checked_enum::UncheckedEnum;
 use math2d::Recti;
+#[cfg(windows)]
 use winapi::shared::dxgi::DXGI_OUTPUT_DESC;
-use winapi::shared::windef::HMONITOR;
+
+pub use self::topology::{DesktopOutput, DesktopTopology, Side};
+
+mod topology;
 
 #[repr(C)]
 #[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OutputDesc {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::utf16"))]
     pub device_name: [u16; 32],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::RectiDef"))]
     pub desktop_coordinates: Recti,
     pub attached_to_desktop: DBool,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::unchecked"))]
     pub rotation: UncheckedEnum<ModeRotation>,
+    #[cfg_attr(feature = "serde", serde(skip, default = "std::ptr::null_mut"))]
     pub monitor: HMONITOR,
 }
 
@@ -27,7 +36,7 @@ impl OutputDesc {
 #[cfg(test)]
 member_compat_test! {
     output_desc_compat:
-    OutputDesc <=> DXGI_OUTPUT_DESC {
+    OutputDesc <=> crate::descriptions::layouts::DXGI_OUTPUT_DESC {
         device_name <=> DeviceName,
         desktop_coordinates <=> DesktopCoordinates,
         attached_to_desktop <=> AttachedToDesktop,
@@ -36,12 +45,14 @@ member_compat_test! {
     }
 }
 
+#[cfg(windows)]
 impl From<DXGI_OUTPUT_DESC> for OutputDesc {
     fn from(desc: DXGI_OUTPUT_DESC) -> OutputDesc {
         unsafe { std::mem::transmute(desc) }
     }
 }
 
+#[cfg(windows)]
 impl From<OutputDesc> for DXGI_OUTPUT_DESC {
     fn from(desc: OutputDesc) -> DXGI_OUTPUT_DESC {
         unsafe { std::mem::transmute(desc) }
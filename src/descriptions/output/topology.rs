@@ -0,0 +1,345 @@
+use crate::descriptions::OutputDesc;
+use crate::enums::ModeRotation;
+
+use math2d::{Point2i, Recti};
+
+#[cfg(test)]
+use crate::helpers::fixtures::output_desc;
+
+/// An output's place on the virtual desktop.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DesktopOutput {
+    pub device_name: String,
+    /// The output's bounds in desktop coordinates, after rotation.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::RectiDef"))]
+    pub bounds: Recti,
+    pub rotation: ModeRotation,
+}
+
+impl DesktopOutput {
+    /// The output's width and height in desktop coordinates.
+    pub fn size(&self) -> (u32, u32) {
+        (
+            (self.bounds.right as i64 - self.bounds.left as i64).max(0) as u32,
+            (self.bounds.bottom as i64 - self.bounds.top as i64).max(0) as u32,
+        )
+    }
+
+    /// The output's width and height before rotation, i.e. of the display
+    /// mode. A monitor turned to portrait has a wide native size.
+    pub fn native_size(&self) -> (u32, u32) {
+        let (width, height) = self.size();
+        match self.rotation {
+            ModeRotation::Rotate90 | ModeRotation::Rotate270 => (height, width),
+            _ => (width, height),
+        }
+    }
+}
+
+impl<'a> From<&'a OutputDesc> for DesktopOutput {
+    fn from(desc: &'a OutputDesc) -> DesktopOutput {
+        DesktopOutput {
+            device_name: desc.device_name(),
+            bounds: desc.desktop_coordinates,
+            rotation: desc.rotation.as_enum().unwrap_or(ModeRotation::Unspecified),
+        }
+    }
+}
+
+/// Which side of an output a neighbouring output is on.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Side {
+    Left,
+    Top,
+    Right,
+    Bottom,
+}
+
+/// The arrangement of outputs on the virtual desktop.
+///
+/// Outputs are referred to by their index in [`outputs`](Self::outputs),
+/// which is the order they were added in. Bounds are half-open, as with
+/// `RECT`: a point on an output's right or bottom edge belongs to the output
+/// next to it.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DesktopTopology {
+    outputs: Vec<DesktopOutput>,
+}
+
+impl DesktopTopology {
+    /// An empty desktop.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The desktop formed by the outputs that are attached to it. Detached
+    /// outputs are skipped.
+    pub fn from_descs<'a, I>(descs: I) -> Self
+    where
+        I: IntoIterator<Item = &'a OutputDesc>,
+    {
+        DesktopTopology {
+            outputs: descs
+                .into_iter()
+                .filter(|desc| bool::from(desc.attached_to_desktop))
+                .map(DesktopOutput::from)
+                .collect(),
+        }
+    }
+
+    /// Adds an output, e.g. one from a recorded snapshot.
+    pub fn push(&mut self, output: DesktopOutput) {
+        self.outputs.push(output);
+    }
+
+    pub fn outputs(&self) -> &[DesktopOutput] {
+        &self.outputs
+    }
+
+    /// The primary output, whose top left corner is the desktop origin.
+    pub fn primary(&self) -> Option<usize> {
+        self.outputs
+            .iter()
+            .position(|o| o.bounds.left == 0 && o.bounds.top == 0 && !is_empty(&o.bounds))
+    }
+
+    /// The output containing `point`.
+    pub fn output_at(&self, point: Point2i) -> Option<usize> {
+        self.outputs.iter().position(|o| {
+            let b = &o.bounds;
+            point.x >= b.left && point.x < b.right && point.y >= b.top && point.y < b.bottom
+        })
+    }
+
+    /// The output that `rect` overlaps the most, which is how
+    /// `GetContainingOutput` picks the output for a window. Ties go to the
+    /// output added first. `None` if `rect` is off every output.
+    pub fn output_for_rect(&self, rect: Recti) -> Option<usize> {
+        let mut best = None;
+        let mut best_area = 0;
+        for (index, output) in self.outputs.iter().enumerate() {
+            let area = intersection(&output.bounds, &rect).map_or(0, |r| area(&r));
+            if area > best_area {
+                best = Some(index);
+                best_area = area;
+            }
+        }
+        best
+    }
+
+    /// The output closest to `point`, measured to the nearest edge of each
+    /// output. An output containing the point is always closest.
+    pub fn nearest_output(&self, point: Point2i) -> Option<usize> {
+        let distance = |b: &Recti| {
+            let dx = (b.left as i64 - point.x as i64)
+                .max(point.x as i64 - (b.right as i64 - 1))
+                .max(0);
+            let dy = (b.top as i64 - point.y as i64)
+                .max(point.y as i64 - (b.bottom as i64 - 1))
+                .max(0);
+            dx * dx + dy * dy
+        };
+        self.outputs
+            .iter()
+            .enumerate()
+            .filter(|(_, o)| !is_empty(&o.bounds))
+            .min_by_key(|(index, o)| (distance(&o.bounds), *index))
+            .map(|(index, _)| index)
+    }
+
+    /// The smallest rect containing every output. `None` for an empty
+    /// desktop.
+    pub fn bounding_box(&self) -> Option<Recti> {
+        let mut outputs = self.outputs.iter().map(|o| o.bounds);
+        let first = outputs.next()?;
+        Some(outputs.fold(first, |a, b| {
+            Recti::new(
+                a.left.min(b.left),
+                a.top.min(b.top),
+                a.right.max(b.right),
+                a.bottom.max(b.bottom),
+            )
+        }))
+    }
+
+    /// The outputs that share part of an edge with output `index`, and the
+    /// side of it they are on. Outputs that only meet at a corner are not
+    /// adjacent.
+    pub fn neighbors(&self, index: usize) -> Vec<(usize, Side)> {
+        let a = match self.outputs.get(index) {
+            Some(output) => output.bounds,
+            None => return Vec::new(),
+        };
+
+        let mut neighbors = Vec::new();
+        for (other, output) in self.outputs.iter().enumerate() {
+            if other == index {
+                continue;
+            }
+            let b = output.bounds;
+            let overlap_x = a.left < b.right && b.left < a.right;
+            let overlap_y = a.top < b.bottom && b.top < a.bottom;
+            let side = if overlap_y && b.right == a.left {
+                Side::Left
+            } else if overlap_y && b.left == a.right {
+                Side::Right
+            } else if overlap_x && b.bottom == a.top {
+                Side::Top
+            } else if overlap_x && b.top == a.bottom {
+                Side::Bottom
+            } else {
+                continue;
+            };
+            neighbors.push((other, side));
+        }
+        neighbors
+    }
+
+    /// Every pair of adjacent outputs, as `(a, b, side of a that b is on)`
+    /// with `a < b`.
+    pub fn adjacency(&self) -> Vec<(usize, usize, Side)> {
+        (0..self.outputs.len())
+            .flat_map(|a| {
+                self.neighbors(a)
+                    .into_iter()
+                    .filter(move |&(b, _)| b > a)
+                    .map(move |(b, side)| (a, b, side))
+            })
+            .collect()
+    }
+}
+
+fn is_empty(rect: &Recti) -> bool {
+    rect.left >= rect.right || rect.top >= rect.bottom
+}
+
+fn intersection(a: &Recti, b: &Recti) -> Option<Recti> {
+    let r = Recti::new(
+        a.left.max(b.left),
+        a.top.max(b.top),
+        a.right.min(b.right),
+        a.bottom.min(b.bottom),
+    );
+    if is_empty(&r) {
+        None
+    } else {
+        Some(r)
+    }
+}
+
+fn area(rect: &Recti) -> u64 {
+    (rect.right as i64 - rect.left as i64) as u64 * (rect.bottom as i64 - rect.top as i64) as u64
+}
+
+#[cfg(test)]
+fn output(name: &str, bounds: (i32, i32, i32, i32), rotation: ModeRotation) -> DesktopOutput {
+    DesktopOutput::from(&output_desc(name, bounds, rotation))
+}
+
+/// A 1440p primary, a portrait 1080p monitor to its left, and a 1080p
+/// monitor above the primary's right half.
+#[cfg(test)]
+fn desktop() -> DesktopTopology {
+    let mut desktop = DesktopTopology::new();
+    desktop.push(output(
+        "left",
+        (-1080, -200, 0, 1720),
+        ModeRotation::Rotate90,
+    ));
+    desktop.push(output("main", (0, 0, 2560, 1440), ModeRotation::Identity));
+    desktop.push(output(
+        "top",
+        (1280, -1080, 3200, 0),
+        ModeRotation::Identity,
+    ));
+    desktop
+}
+
+#[cfg(test)]
+#[test]
+fn point_and_rect_queries() {
+    let desktop = desktop();
+    assert_eq!(desktop.primary(), Some(1));
+    assert_eq!(desktop.output_at(Point2i::new(-1, -1)), Some(0));
+    assert_eq!(desktop.output_at(Point2i::new(0, 0)), Some(1));
+    assert_eq!(desktop.output_at(Point2i::new(3000, -1)), Some(2));
+    assert_eq!(desktop.output_at(Point2i::new(-500, -500)), None);
+    assert_eq!(desktop.output_at(Point2i::new(2560, 100)), None);
+
+    // A window straddling the left and main outputs, mostly on the left.
+    assert_eq!(
+        desktop.output_for_rect(Recti::new(-600, 100, 200, 700)),
+        Some(0)
+    );
+    assert_eq!(
+        desktop.output_for_rect(Recti::new(-200, 100, 600, 700)),
+        Some(1)
+    );
+    assert_eq!(
+        desktop.output_for_rect(Recti::new(-2000, -2000, -1500, -1500)),
+        None
+    );
+
+    assert_eq!(desktop.nearest_output(Point2i::new(-500, -500)), Some(0));
+    assert_eq!(desktop.nearest_output(Point2i::new(100, -10)), Some(1));
+    assert_eq!(desktop.nearest_output(Point2i::new(5000, -500)), Some(2));
+    assert_eq!(
+        DesktopTopology::new().nearest_output(Point2i::new(0, 0)),
+        None
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn bounds_and_adjacency() {
+    let desktop = desktop();
+    assert_eq!(
+        desktop.bounding_box(),
+        Some(Recti::new(-1080, -1080, 3200, 1720))
+    );
+    assert_eq!(DesktopTopology::new().bounding_box(), None);
+
+    assert_eq!(desktop.neighbors(1), vec![(0, Side::Left), (2, Side::Top)]);
+    assert_eq!(desktop.neighbors(2), vec![(1, Side::Bottom)]);
+    assert_eq!(desktop.neighbors(3), vec![]);
+    assert_eq!(
+        desktop.adjacency(),
+        vec![(0, 1, Side::Right), (1, 2, Side::Top)]
+    );
+
+    // Outputs meeting only at a corner aren't adjacent.
+    let mut corner = DesktopTopology::new();
+    corner.push(output("a", (0, 0, 100, 100), ModeRotation::Identity));
+    corner.push(output("b", (100, 100, 200, 200), ModeRotation::Identity));
+    assert!(corner.adjacency().is_empty());
+}
+
+#[cfg(test)]
+#[test]
+fn rotation_and_descs() {
+    let desktop = desktop();
+    let left = &desktop.outputs()[0];
+    assert_eq!(left.size(), (1080, 1920));
+    assert_eq!(left.native_size(), (1920, 1080));
+    assert_eq!(desktop.outputs()[1].native_size(), (2560, 1440));
+
+    let attached = output_desc(
+        "\\\\.\\DISPLAY2",
+        (-1080, -200, 0, 1720),
+        ModeRotation::Rotate270,
+    );
+    let detached = OutputDesc {
+        attached_to_desktop: false.into(),
+        ..attached
+    };
+
+    let topology = DesktopTopology::from_descs(&[attached, detached]);
+    assert_eq!(topology.outputs().len(), 1);
+    assert_eq!(topology.outputs()[0].device_name, "\\\\.\\DISPLAY2");
+    assert_eq!(topology.outputs()[0].rotation, ModeRotation::Rotate270);
+    assert_eq!(topology.outputs()[0].native_size(), (1920, 1080));
+    assert_eq!(topology.output_at(Point2i::new(-1080, -200)), Some(0));
+}
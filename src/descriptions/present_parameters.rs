@@ -1,13 +1,19 @@
 use math2d::Point2i;
 use math2d::Recti;
+#[cfg(windows)]
 use winapi::shared::dxgi1_2::DXGI_PRESENT_PARAMETERS;
 
+pub use self::builder::{DirtyRects, PresentParametersBuilder, PresentParametersError};
+
+mod builder;
+
 pub struct PresentParameters<'a> {
     pub dirty_rects: Option<&'a [Recti]>,
     pub scroll_rect: Option<&'a Recti>,
     pub scroll_offset: Option<&'a Point2i>,
 }
 
+#[cfg(windows)]
 impl<'a> From<&'a PresentParameters<'a>> for DXGI_PRESENT_PARAMETERS {
     fn from(params: &'a PresentParameters<'a>) -> DXGI_PRESENT_PARAMETERS {
         DXGI_PRESENT_PARAMETERS {
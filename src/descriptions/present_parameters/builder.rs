@@ -0,0 +1,487 @@
+use crate::descriptions::PresentParameters;
+use crate::enums::SwapEffect;
+
+use math2d::{Point2i, Recti};
+use std::fmt;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+/// Reasons `PresentParametersBuilder` rejects a partial present.
+pub enum PresentParametersError {
+    /// `SwapEffect::FlipDiscard` swap chains can't present dirty rects or
+    /// scroll.
+    FlipDiscard,
+    /// The scroll rect is empty or not inside the back buffer.
+    ScrollRectOutOfBounds(Recti),
+    /// Moving the scroll rect by the offset takes it outside the back buffer.
+    ScrollOffsetOutOfBounds(Point2i),
+}
+
+impl fmt::Display for PresentParametersError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PresentParametersError::FlipDiscard => {
+                fmt.write_str("FlipDiscard swap chains can't present dirty rects or scroll")
+            }
+            PresentParametersError::ScrollRectOutOfBounds(r) => write!(
+                fmt,
+                "the scroll rect ({}, {})-({}, {}) is not inside the back buffer",
+                r.left, r.top, r.right, r.bottom
+            ),
+            PresentParametersError::ScrollOffsetOutOfBounds(p) => write!(
+                fmt,
+                "scrolling by ({}, {}) moves the scroll rect outside the back buffer",
+                p.x, p.y
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PresentParametersError {}
+
+/// Accumulates the regions of the back buffer changed during a frame.
+///
+/// Rects are clipped to the buffer, and overlapping or adjacent rects are
+/// merged into their bounding box. Once there are more than `max_rects`
+/// rects, or they cover at least `max_coverage` of the buffer, the whole
+/// frame is considered dirty, since presenting it in full is then cheaper
+/// than tracking the pieces.
+#[derive(Clone, Debug)]
+pub struct DirtyRects {
+    width: u32,
+    height: u32,
+    rects: Vec<Recti>,
+    full: bool,
+    max_rects: usize,
+    max_coverage: f32,
+}
+
+impl DirtyRects {
+    /// An empty accumulator for a `width` x `height` buffer, which becomes a
+    /// full frame at more than 16 rects or 50% coverage.
+    pub fn new(width: u32, height: u32) -> Self {
+        DirtyRects {
+            width,
+            height,
+            rects: Vec::new(),
+            full: false,
+            max_rects: 16,
+            max_coverage: 0.5,
+        }
+    }
+
+    /// The number of rects above which the whole frame is dirty.
+    pub fn with_max_rects(mut self, max_rects: usize) -> Self {
+        self.max_rects = max_rects;
+        self.check_thresholds();
+        self
+    }
+
+    /// The fraction of the buffer's area, from 0 to 1, at which the whole
+    /// frame is dirty.
+    pub fn with_max_coverage(mut self, max_coverage: f32) -> Self {
+        self.max_coverage = max_coverage;
+        self.check_thresholds();
+        self
+    }
+
+    /// Marks a region as dirty. Parts outside the buffer are ignored.
+    pub fn add(&mut self, rect: Recti) {
+        if self.full {
+            return;
+        }
+
+        let mut rect = match clip(rect, self.bounds()) {
+            Some(rect) => rect,
+            None => return,
+        };
+
+        // Absorb every rect the new one touches, repeating because the grown
+        // rect can reach rects the original one didn't.
+        loop {
+            let before = self.rects.len();
+            self.rects.retain(|other| {
+                if touches(&rect, other) {
+                    rect = union(&rect, other);
+                    false
+                } else {
+                    true
+                }
+            });
+            if self.rects.len() == before {
+                break;
+            }
+        }
+        self.rects.push(rect);
+        self.check_thresholds();
+    }
+
+    /// Marks the whole frame as dirty.
+    pub fn add_full_frame(&mut self) {
+        self.full = true;
+        self.rects.clear();
+    }
+
+    /// Whether the whole frame is dirty.
+    pub fn is_full_frame(&self) -> bool {
+        self.full
+    }
+
+    /// Whether nothing has been marked dirty.
+    pub fn is_empty(&self) -> bool {
+        !self.full && self.rects.is_empty()
+    }
+
+    /// The merged dirty rects. Empty if the whole frame is dirty.
+    pub fn rects(&self) -> &[Recti] {
+        &self.rects
+    }
+
+    /// The number of pixels covered by the dirty rects.
+    pub fn area(&self) -> u64 {
+        if self.full {
+            self.width as u64 * self.height as u64
+        } else {
+            self.rects.iter().map(area).sum()
+        }
+    }
+
+    /// Forgets everything, ready for the next frame.
+    pub fn clear(&mut self) {
+        self.rects.clear();
+        self.full = false;
+    }
+
+    /// Changes the buffer size, e.g. after `ResizeBuffers`. The next frame
+    /// has to be presented in full, so the whole frame is marked dirty.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+        self.add_full_frame();
+    }
+
+    fn bounds(&self) -> Recti {
+        Recti::new(
+            0,
+            0,
+            self.width.min(i32::MAX as u32) as i32,
+            self.height.min(i32::MAX as u32) as i32,
+        )
+    }
+
+    fn check_thresholds(&mut self) {
+        if self.full || self.rects.is_empty() {
+            return;
+        }
+
+        let total = self.width as f64 * self.height as f64;
+        if self.rects.len() > self.max_rects
+            || self.area() as f64 >= total * self.max_coverage as f64
+        {
+            self.add_full_frame();
+        }
+    }
+}
+
+/// Builds the parameters for `present1` from the changes made during a
+/// frame.
+///
+/// The builder owns the rects, so it can be kept across frames and cleared
+/// after each present. Scrolling adds the region it exposes to the dirty
+/// rects, as `present1` requires.
+#[derive(Clone, Debug)]
+pub struct PresentParametersBuilder {
+    dirty: DirtyRects,
+    scroll: Option<(Recti, Point2i)>,
+}
+
+impl PresentParametersBuilder {
+    /// A builder for a `width` x `height` back buffer.
+    pub fn new(width: u32, height: u32) -> Self {
+        PresentParametersBuilder::with_dirty_rects(DirtyRects::new(width, height))
+    }
+
+    /// A builder that accumulates into a configured `DirtyRects`.
+    pub fn with_dirty_rects(dirty: DirtyRects) -> Self {
+        PresentParametersBuilder {
+            dirty,
+            scroll: None,
+        }
+    }
+
+    /// Marks a region as dirty.
+    pub fn add_dirty_rect(&mut self, rect: Recti) -> &mut Self {
+        self.dirty.add(rect);
+        self
+    }
+
+    /// Marks the whole frame as dirty.
+    pub fn add_full_frame(&mut self) -> &mut Self {
+        self.dirty.add_full_frame();
+        self
+    }
+
+    /// Scrolls the contents of `rect` by `offset`. Both the rect and the
+    /// rect moved by the offset have to be inside the back buffer. Replaces
+    /// any earlier scroll in the frame.
+    pub fn scroll(
+        &mut self,
+        rect: Recti,
+        offset: Point2i,
+    ) -> Result<&mut Self, PresentParametersError> {
+        let bounds = self.dirty.bounds();
+        if rect.left >= rect.right || rect.top >= rect.bottom || !contains(&bounds, &rect) {
+            return Err(PresentParametersError::ScrollRectOutOfBounds(rect));
+        }
+
+        let moved = offset_rect(&rect, offset)
+            .filter(|moved| contains(&bounds, moved))
+            .ok_or(PresentParametersError::ScrollOffsetOutOfBounds(offset))?;
+
+        for exposed in subtract(&rect, &moved) {
+            self.dirty.add(exposed);
+        }
+        self.scroll = Some((rect, offset));
+        Ok(self)
+    }
+
+    /// The dirty rects collected so far.
+    pub fn dirty_rects(&self) -> &DirtyRects {
+        &self.dirty
+    }
+
+    /// Forgets the frame's changes, ready for the next frame.
+    pub fn clear(&mut self) -> &mut Self {
+        self.dirty.clear();
+        self.scroll = None;
+        self
+    }
+
+    /// Changes the buffer size, e.g. after `ResizeBuffers`. The next frame
+    /// is presented in full.
+    pub fn resize(&mut self, width: u32, height: u32) -> &mut Self {
+        self.dirty.resize(width, height);
+        self.scroll = None;
+        self
+    }
+
+    /// The parameters for presenting the frame to a swap chain with the
+    /// given swap effect.
+    ///
+    /// A full frame is presented without dirty rects or scroll, which is
+    /// valid for every swap effect. A partial present fails with
+    /// `FlipDiscard`.
+    pub fn build(
+        &self,
+        swap_effect: SwapEffect,
+    ) -> Result<PresentParameters<'_>, PresentParametersError> {
+        if self.dirty.is_full_frame() {
+            return Ok(PresentParameters {
+                dirty_rects: None,
+                scroll_rect: None,
+                scroll_offset: None,
+            });
+        }
+        if swap_effect == SwapEffect::FlipDiscard {
+            return Err(PresentParametersError::FlipDiscard);
+        }
+
+        let rects = self.dirty.rects();
+        Ok(PresentParameters {
+            dirty_rects: if rects.is_empty() { None } else { Some(rects) },
+            scroll_rect: self.scroll.as_ref().map(|scroll| &scroll.0),
+            scroll_offset: self.scroll.as_ref().map(|scroll| &scroll.1),
+        })
+    }
+}
+
+fn clip(rect: Recti, bounds: Recti) -> Option<Recti> {
+    let clipped = Recti::new(
+        rect.left.max(bounds.left),
+        rect.top.max(bounds.top),
+        rect.right.min(bounds.right),
+        rect.bottom.min(bounds.bottom),
+    );
+    if clipped.left < clipped.right && clipped.top < clipped.bottom {
+        Some(clipped)
+    } else {
+        None
+    }
+}
+
+/// Whether two rects overlap or share part of an edge.
+fn touches(a: &Recti, b: &Recti) -> bool {
+    let x = a.left <= b.right && b.left <= a.right;
+    let y = a.top <= b.bottom && b.top <= a.bottom;
+    let overlap_x = a.left < b.right && b.left < a.right;
+    let overlap_y = a.top < b.bottom && b.top < a.bottom;
+    x && y && (overlap_x || overlap_y)
+}
+
+fn union(a: &Recti, b: &Recti) -> Recti {
+    Recti::new(
+        a.left.min(b.left),
+        a.top.min(b.top),
+        a.right.max(b.right),
+        a.bottom.max(b.bottom),
+    )
+}
+
+fn contains(outer: &Recti, inner: &Recti) -> bool {
+    inner.left >= outer.left
+        && inner.top >= outer.top
+        && inner.right <= outer.right
+        && inner.bottom <= outer.bottom
+}
+
+fn area(rect: &Recti) -> u64 {
+    (rect.right - rect.left) as u64 * (rect.bottom - rect.top) as u64
+}
+
+fn offset_rect(rect: &Recti, offset: Point2i) -> Option<Recti> {
+    Some(Recti::new(
+        rect.left.checked_add(offset.x)?,
+        rect.top.checked_add(offset.y)?,
+        rect.right.checked_add(offset.x)?,
+        rect.bottom.checked_add(offset.y)?,
+    ))
+}
+
+/// The parts of `a` not covered by `b`, as up to four rects.
+fn subtract(a: &Recti, b: &Recti) -> Vec<Recti> {
+    let inner = match clip(*b, *a) {
+        Some(inner) => inner,
+        None => return vec![*a],
+    };
+
+    let pieces = [
+        Recti::new(a.left, a.top, a.right, inner.top),
+        Recti::new(a.left, inner.bottom, a.right, a.bottom),
+        Recti::new(a.left, inner.top, inner.left, inner.bottom),
+        Recti::new(inner.right, inner.top, a.right, inner.bottom),
+    ];
+    pieces
+        .iter()
+        .cloned()
+        .filter(|r| r.left < r.right && r.top < r.bottom)
+        .collect()
+}
+
+#[cfg(test)]
+#[test]
+fn clip_and_merge() {
+    let mut dirty = DirtyRects::new(100, 100);
+    dirty.add(Recti::new(-10, -10, 10, 10));
+    dirty.add(Recti::new(200, 0, 300, 10));
+    dirty.add(Recti::new(50, 50, 50, 60));
+    assert_eq!(dirty.rects(), &[Recti::new(0, 0, 10, 10)]);
+
+    // Adjacent along an edge: merged.
+    dirty.add(Recti::new(10, 0, 20, 10));
+    assert_eq!(dirty.rects(), &[Recti::new(0, 0, 20, 10)]);
+
+    // Only touching at a corner: kept apart.
+    dirty.add(Recti::new(20, 10, 30, 20));
+    assert_eq!(dirty.rects().len(), 2);
+
+    // Bridging both merges all three.
+    dirty.add(Recti::new(15, 5, 25, 15));
+    assert_eq!(dirty.rects(), &[Recti::new(0, 0, 30, 20)]);
+    assert_eq!(dirty.area(), 600);
+    assert!(!dirty.is_full_frame());
+
+    dirty.clear();
+    assert!(dirty.is_empty());
+}
+
+#[cfg(test)]
+#[test]
+fn collapse_to_full_frame() {
+    let mut dirty = DirtyRects::new(100, 100).with_max_rects(3);
+    for i in 0..3 {
+        dirty.add(Recti::new(i * 20, 0, i * 20 + 10, 10));
+    }
+    assert_eq!(dirty.rects().len(), 3);
+    dirty.add(Recti::new(90, 90, 95, 95));
+    assert!(dirty.is_full_frame());
+    assert!(dirty.rects().is_empty());
+    assert_eq!(dirty.area(), 10_000);
+
+    let mut dirty = DirtyRects::new(100, 100).with_max_coverage(0.25);
+    dirty.add(Recti::new(0, 0, 100, 24));
+    assert!(!dirty.is_full_frame());
+    dirty.add(Recti::new(0, 50, 10, 60));
+    assert!(dirty.is_full_frame());
+
+    dirty.resize(200, 50);
+    assert!(dirty.is_full_frame());
+}
+
+#[cfg(test)]
+#[test]
+fn build_parameters() {
+    let mut builder = PresentParametersBuilder::new(100, 100);
+    builder.add_dirty_rect(Recti::new(10, 10, 20, 20));
+    let params = builder.build(SwapEffect::FlipSequential).unwrap();
+    assert_eq!(params.dirty_rects, Some(&[Recti::new(10, 10, 20, 20)][..]));
+    assert_eq!(params.scroll_rect, None);
+    assert_eq!(
+        builder.build(SwapEffect::FlipDiscard).err(),
+        Some(PresentParametersError::FlipDiscard)
+    );
+
+    builder.clear().add_full_frame();
+    let params = builder.build(SwapEffect::FlipDiscard).unwrap();
+    assert_eq!(params.dirty_rects, None);
+
+    builder.clear();
+    let params = builder.build(SwapEffect::FlipSequential).unwrap();
+    assert_eq!(params.dirty_rects, None);
+}
+
+#[cfg(test)]
+#[test]
+fn scrolling() {
+    let mut builder = PresentParametersBuilder::with_dirty_rects(
+        DirtyRects::new(100, 100).with_max_coverage(1.0),
+    );
+
+    // Scrolling a list up by 10 exposes its bottom 10 rows.
+    let list = Recti::new(0, 20, 100, 100);
+    builder.scroll(list, Point2i::new(0, -10)).unwrap();
+    assert_eq!(
+        builder.dirty_rects().rects(),
+        &[Recti::new(0, 90, 100, 100)]
+    );
+    let params = builder.build(SwapEffect::FlipSequential).unwrap();
+    assert_eq!(params.scroll_rect, Some(&list));
+    assert_eq!(params.scroll_offset, Some(&Point2i::new(0, -10)));
+
+    assert_eq!(
+        builder
+            .scroll(Recti::new(0, 0, 101, 10), Point2i::new(0, 0))
+            .err(),
+        Some(PresentParametersError::ScrollRectOutOfBounds(Recti::new(
+            0, 0, 101, 10
+        )))
+    );
+    assert_eq!(
+        builder
+            .scroll(Recti::new(0, 0, 10, 0), Point2i::new(0, 0))
+            .err(),
+        Some(PresentParametersError::ScrollRectOutOfBounds(Recti::new(
+            0, 0, 10, 0
+        )))
+    );
+    assert_eq!(
+        builder.scroll(list, Point2i::new(0, 1)).err(),
+        Some(PresentParametersError::ScrollOffsetOutOfBounds(
+            Point2i::new(0, 1)
+        ))
+    );
+    assert_eq!(
+        builder.scroll(list, Point2i::new(i32::MAX, 0)).err(),
+        Some(PresentParametersError::ScrollOffsetOutOfBounds(
+            Point2i::new(i32::MAX, 0)
+        ))
+    );
+}
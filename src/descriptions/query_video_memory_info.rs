@@ -1,7 +1,9 @@
+#[cfg(windows)]
 use winapi::shared::dxgi1_4::DXGI_QUERY_VIDEO_MEMORY_INFO;
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct QueryVideoMemoryInfo {
     pub budget: u64,
     pub current_usage: u64,
@@ -12,7 +14,7 @@ pub struct QueryVideoMemoryInfo {
 #[cfg(test)]
 member_compat_test! {
     swap_chain_desc_compat:
-    QueryVideoMemoryInfo <=> DXGI_QUERY_VIDEO_MEMORY_INFO {
+    QueryVideoMemoryInfo <=> crate::descriptions::layouts::DXGI_QUERY_VIDEO_MEMORY_INFO {
         budget <=> Budget,
         current_usage <=> CurrentUsage,
         available_for_reservation <=> AvailableForReservation,
@@ -20,12 +22,14 @@ member_compat_test! {
     }
 }
 
+#[cfg(windows)]
 impl From<DXGI_QUERY_VIDEO_MEMORY_INFO> for QueryVideoMemoryInfo {
     fn from(desc: DXGI_QUERY_VIDEO_MEMORY_INFO) -> QueryVideoMemoryInfo {
         unsafe { std::mem::transmute(desc) }
     }
 }
 
+#[cfg(windows)]
 impl From<QueryVideoMemoryInfo> for DXGI_QUERY_VIDEO_MEMORY_INFO {
     fn from(desc: QueryVideoMemoryInfo) -> DXGI_QUERY_VIDEO_MEMORY_INFO {
         unsafe { std::mem::transmute(desc) }
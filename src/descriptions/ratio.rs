@@ -2,10 +2,21 @@ use std::cmp::Ordering;
 use std::fmt;
 use std::mem::swap;
 
+mod arith;
+
+#[cfg(windows)]
 use winapi::shared::dxgitype::DXGI_RATIONAL;
 
+/// A rational number, used for refresh rates.
+///
+/// A denominator of 0 means the value is unspecified. The arithmetic
+/// operators never overflow: results that don't fit in `u32` parts are
+/// rounded to the nearest ratio that does, subtraction saturates at zero,
+/// and an unspecified operand or a division by zero gives the unspecified
+/// ratio `0/0`. The `checked_*` methods return `None` instead.
 #[repr(C)]
 #[derive(Copy, Clone, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ratio {
     pub numerator: u32,
     pub denominator: u32,
@@ -30,29 +41,159 @@ impl Ratio {
         self.numerator as f64 / self.denominator as f64
     }
 
+    /// Whether this is the unspecified ratio DXGI uses for a refresh rate
+    /// it should choose itself, i.e. the denominator is 0.
+    #[inline]
+    pub fn is_unspecified(&self) -> bool {
+        self.denominator == 0
+    }
+
+    /// The simplest ratio that rounds to `f`, e.g. `2997/50` for `59.94`.
+    /// Negative and NaN values give 0, and values too large for a `u32`
+    /// numerator saturate.
     #[inline]
     pub fn approximate(f: f32) -> Self {
-        Ratio::new((f * 14400.0) as u32, 14400).simplify()
+        approximate(f as f64, f.abs() as f64 * f32::EPSILON as f64)
     }
 
+    /// The simplest ratio that rounds to `f`, e.g. `2997/50` for `59.94`.
+    /// Negative and NaN values give 0, and values too large for a `u32`
+    /// numerator saturate.
     #[inline]
     pub fn approximate_64(f: f64) -> Self {
-        Ratio::new((f * 14400.0) as u32, 14400).simplify()
+        approximate(f, f.abs() * f64::EPSILON)
+    }
+
+    /// The ratio closest to `f` whose denominator is at most
+    /// `max_denominator`, found from the continued fraction expansion of `f`.
+    pub fn best_approximation(f: f64, max_denominator: u32) -> Self {
+        match exact_parts(f) {
+            Ok((n, d)) => arith::nearest(n, d, max_denominator.max(1) as u128),
+            Err(ratio) => ratio,
+        }
     }
 
+    /// Brings both ratios to their least common denominator. Unspecified
+    /// ratios are returned unchanged.
+    ///
+    /// Panics if the common denominator or the scaled numerators do not fit
+    /// in a `u32`; see `checked_common_denominator`.
     #[inline]
     pub fn common_denominator(&self, other: &Ratio) -> (Ratio, Ratio) {
+        self.checked_common_denominator(other)
+            .expect("common denominator does not fit in a u32")
+    }
+
+    /// Brings both ratios to their least common denominator, or returns
+    /// `None` if the result does not fit in `u32` parts. Unspecified ratios
+    /// are returned unchanged.
+    pub fn checked_common_denominator(&self, other: &Ratio) -> Option<(Ratio, Ratio)> {
         if self.denominator == 0 || other.denominator == 0 {
-            return (*self, *other);
+            return Some((*self, *other));
+        }
+
+        let gcd = gcd(self.denominator, other.denominator) as u64;
+        let den = self.denominator as u64 * (other.denominator as u64 / gcd);
+        let lhs = self.numerator as u64 * (other.denominator as u64 / gcd);
+        let rhs = other.numerator as u64 * (self.denominator as u64 / gcd);
+
+        let max = u32::MAX as u64;
+        if den > max || lhs > max || rhs > max {
+            return None;
+        }
+        Some((
+            Ratio::new(lhs as u32, den as u32),
+            Ratio::new(rhs as u32, den as u32),
+        ))
+    }
+
+    /// The length of one frame at this refresh rate, in seconds. `None` for
+    /// a zero or unspecified rate.
+    #[inline]
+    pub fn frame_period_secs(&self) -> Option<f64> {
+        self.frame_period(1).map(|(n, d)| n as f64 / d as f64)
+    }
+
+    /// The length of one frame at this refresh rate in 100ns units, the unit
+    /// of composition and media timestamps, rounded to the nearest unit.
+    #[inline]
+    pub fn frame_period_100ns(&self) -> Option<u64> {
+        self.frame_period_qpc(10_000_000)
+    }
+
+    /// The length of one frame at this refresh rate in performance counter
+    /// ticks, for comparing against `FrameStatistics::sync_qpc_time`.
+    /// `frequency` is the `QueryPerformanceFrequency` in Hz. Rounded to the
+    /// nearest tick.
+    #[inline]
+    pub fn frame_period_qpc(&self, frequency: u64) -> Option<u64> {
+        let (n, d) = self.frame_period(frequency)?;
+        let ticks = (n + d / 2) / d;
+        Some(ticks.min(u64::MAX as u128) as u64)
+    }
+
+    fn frame_period(&self, frequency: u64) -> Option<(u128, u128)> {
+        if self.numerator == 0 || self.denominator == 0 {
+            return None;
+        }
+        Some((
+            self.denominator as u128 * frequency as u128,
+            self.numerator as u128,
+        ))
+    }
+
+    /// The NTSC variant of an integer rate, `nominal * 1000/1001`, e.g.
+    /// `60000/1001` (59.94 Hz) for 60.
+    #[inline]
+    pub fn ntsc(nominal: u32) -> Self {
+        Ratio::new(nominal, 1) * Ratio::new(1000, 1001)
+    }
+
+    /// If this is exactly an NTSC rate `nominal * 1000/1001`, the nominal
+    /// integer rate, e.g. `Some(24)` for `24000/1001` (23.976 Hz).
+    pub fn ntsc_nominal(&self) -> Option<u32> {
+        if self.is_unspecified() || self.numerator == 0 {
+            return None;
         }
 
-        let gcd = gcd(self.denominator, other.denominator);
-        let den = self.denominator * (other.denominator / gcd);
+        let nominal = (self.to_f64() * 1.001).round();
+        if nominal < 1.0 || nominal > u32::MAX as f64 {
+            return None;
+        }
+        let nominal = nominal as u32;
+        if nominal % 1001 != 0 && *self == Ratio::ntsc(nominal) {
+            Some(nominal)
+        } else {
+            None
+        }
+    }
 
-        let lhs = Ratio::new(self.numerator * other.denominator / gcd, den);
-        let rhs = Ratio::new(other.numerator * self.denominator / gcd, den);
+    /// Snaps a measured or rounded rate to the nearest standard broadcast
+    /// rate, either an integer rate or its NTSC variant, if one is within
+    /// `tolerance` Hz. For example `2997/50` (59.94) snaps to `60000/1001`
+    /// with a tolerance of 0.001.
+    pub fn snap_broadcast(&self, tolerance: f64) -> Option<Ratio> {
+        if self.is_unspecified() {
+            return None;
+        }
+
+        let hz = self.to_f64();
+        let integer = hz.round();
+        let ntsc = (hz * 1.001).round();
+        let mut candidates = Vec::with_capacity(2);
+        if integer >= 1.0 && integer <= u32::MAX as f64 {
+            candidates.push(Ratio::new(integer as u32, 1));
+        }
+        if ntsc >= 1.0 && ntsc <= u32::MAX as f64 {
+            candidates.push(Ratio::ntsc(ntsc as u32));
+        }
 
-        (lhs, rhs)
+        candidates
+            .into_iter()
+            .map(|c| (c, (c.to_f64() - hz).abs()))
+            .filter(|&(_, error)| error <= tolerance)
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .map(|(c, _)| c)
     }
 
     #[inline]
@@ -72,13 +213,14 @@ impl Ratio {
 #[cfg(test)]
 member_compat_test! {
     ratio_compat:
-    Ratio <=> DXGI_RATIONAL {
+    Ratio <=> crate::descriptions::layouts::DXGI_RATIONAL {
         numerator <=> Numerator,
         denominator <=> Denominator,
     }
 }
 
 // This is safe because of the test above
+#[cfg(windows)]
 impl From<DXGI_RATIONAL> for Ratio {
     #[inline]
     fn from(ratio: DXGI_RATIONAL) -> Ratio {
@@ -87,6 +229,7 @@ impl From<DXGI_RATIONAL> for Ratio {
 }
 
 // This is safe because of the test above
+#[cfg(windows)]
 impl From<Ratio> for DXGI_RATIONAL {
     #[inline]
     fn from(ratio: Ratio) -> DXGI_RATIONAL {
@@ -120,12 +263,13 @@ impl fmt::Display for Ratio {
     }
 }
 
+/// Ratios are compared by value, so `30/1` equals `60/2`. Unspecified
+/// ratios (any ratio with a 0 denominator) are all equal to each other, and
+/// less than every specified ratio.
 impl PartialEq for Ratio {
     #[inline]
     fn eq(&self, other: &Ratio) -> bool {
-        let a = self.simplify();
-        let b = other.simplify();
-        a.numerator == b.numerator && a.denominator == b.denominator
+        self.cmp(other) == Ordering::Equal
     }
 }
 
@@ -139,8 +283,56 @@ impl PartialOrd for Ratio {
 impl Ord for Ratio {
     #[inline]
     fn cmp(&self, other: &Ratio) -> Ordering {
-        let (lhs, rhs) = self.common_denominator(other);
-        lhs.numerator.cmp(&rhs.numerator)
+        match (self.is_unspecified(), other.is_unspecified()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (false, false) => {
+                let lhs = self.numerator as u64 * other.denominator as u64;
+                let rhs = other.numerator as u64 * self.denominator as u64;
+                lhs.cmp(&rhs)
+            }
+        }
+    }
+}
+
+/// The simplest ratio within `tolerance` of `f`.
+fn approximate(f: f64, tolerance: f64) -> Ratio {
+    match exact_parts(f) {
+        Ok((n, d)) => arith::continued_fraction(n, d, u32::MAX as u128, tolerance),
+        Err(ratio) => ratio,
+    }
+}
+
+/// Splits a finite positive float into the exact fraction `n/d` it stores,
+/// or gives the ratio to use directly for values out of range.
+fn exact_parts(f: f64) -> Result<(u128, u128), Ratio> {
+    if f.is_nan() || f <= 0.0 {
+        return Err(Ratio::new(0, 1));
+    }
+    if f >= u32::MAX as f64 {
+        return Err(Ratio::new(u32::MAX, 1));
+    }
+
+    let bits = f.to_bits();
+    let exponent = ((bits >> 52) & 0x7FF) as i32;
+    let mantissa = bits & ((1 << 52) - 1);
+    let (mut mantissa, mut shift) = if exponent == 0 {
+        (mantissa, 1074)
+    } else {
+        (mantissa | (1 << 52), 1075 - exponent)
+    };
+
+    // Anything smaller than 2^-64 rounds to 0 at any u32 denominator, so
+    // the low bits can be dropped to keep the denominator in range.
+    if shift > 116 {
+        mantissa >>= (shift - 116).min(63);
+        shift = 116;
+    }
+    if shift <= 0 {
+        Ok(((mantissa as u128) << -shift, 1))
+    } else {
+        Ok((mantissa as u128, 1 << shift))
     }
 }
 
@@ -195,3 +387,126 @@ fn test_ord() {
     assert!(Ratio::new(2, 72) > Ratio::new(1, 72));
     assert!(Ratio::new(1, 2) < Ratio::new(2, 3));
 }
+
+#[cfg(test)]
+#[test]
+fn test_unspecified_ord() {
+    assert_eq!(Ratio::new(0, 0), Ratio::new(60, 0));
+    assert_eq!(Ratio::new(30, 1), Ratio::new(60, 2));
+    assert!(Ratio::new(0, 0) < Ratio::new(0, 1));
+    assert!(Ratio::new(5, 0) < Ratio::new(1, 1000));
+    assert!(Ratio::new(u32::MAX, 1) > Ratio::new(u32::MAX - 1, 1));
+    assert!(Ratio::new(u32::MAX, u32::MAX - 1) < Ratio::new(u32::MAX - 1, u32::MAX - 2));
+
+    let (a, b) = Ratio::new(0, 0).common_denominator(&Ratio::new(1, 2));
+    assert_eq!((a.denominator, b.denominator), (0, 2));
+    assert_eq!(
+        Ratio::new(1, u32::MAX).checked_common_denominator(&Ratio::new(1, u32::MAX - 1)),
+        None
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_arithmetic() {
+    let ntsc = Ratio::new(60000, 1001);
+    assert_eq!(Ratio::new(1, 2) + Ratio::new(1, 3), Ratio::new(5, 6));
+    assert_eq!(Ratio::new(1, 2) - Ratio::new(1, 3), Ratio::new(1, 6));
+    assert_eq!(Ratio::new(1, 3) - Ratio::new(1, 2), Ratio::new(0, 1));
+    assert_eq!(ntsc * Ratio::new(1001, 1000), Ratio::new(60, 1));
+    assert_eq!(ntsc / Ratio::new(2, 1), Ratio::new(30000, 1001));
+    assert!((ntsc / Ratio::new(0, 1)).is_unspecified());
+    assert!((ntsc + Ratio::new(0, 0)).is_unspecified());
+
+    // Results that don't fit are rounded rather than overflowing.
+    let big = Ratio::new(u32::MAX, 1);
+    assert_eq!(big.checked_add(big), None);
+    assert_eq!(big + big, Ratio::new(u32::MAX, 1));
+    let fine = Ratio::new(1, u32::MAX) + Ratio::new(1, u32::MAX - 1);
+    assert!((fine.to_f64() - 2.0 / u32::MAX as f64).abs() < 1e-18);
+    assert_eq!(Ratio::new(1, 3).checked_sub(Ratio::new(1, 2)), None);
+    assert_eq!(
+        Ratio::new(2, 3).checked_mul(Ratio::new(3, 4)),
+        Some(Ratio::new(1, 2))
+    );
+    assert_eq!(ntsc.checked_div(Ratio::new(0, 1)), None);
+
+    assert_eq!(
+        Ratio::new(314159, 100000).limit_denominator(100),
+        Ratio::new(311, 99)
+    );
+    assert_eq!(
+        Ratio::new(314159, 100000).limit_denominator(10),
+        Ratio::new(22, 7)
+    );
+    assert_eq!(Ratio::new(15, 4).limit_denominator(0), Ratio::new(4, 1));
+}
+
+#[cfg(test)]
+#[test]
+fn test_best_approximation() {
+    assert_eq!(Ratio::approximate(59.94), Ratio::new(2997, 50));
+    assert_eq!(Ratio::approximate(60.0), Ratio::new(60, 1));
+    assert_eq!(Ratio::approximate_64(23.976), Ratio::new(2997, 125));
+    assert_eq!(Ratio::approximate(-1.0), Ratio::new(0, 1));
+    assert_eq!(Ratio::approximate(f32::NAN), Ratio::new(0, 1));
+    assert_eq!(Ratio::approximate(1e20), Ratio::new(u32::MAX, 1));
+
+    let ntsc = 60000.0 / 1001.0;
+    assert_eq!(
+        Ratio::best_approximation(ntsc, 1001),
+        Ratio::new(60000, 1001)
+    );
+    assert_eq!(Ratio::best_approximation(ntsc, 100), Ratio::new(2997, 50));
+    assert_eq!(
+        Ratio::best_approximation(24000.0 / 1001.0, 1001),
+        Ratio::new(24000, 1001)
+    );
+    assert_eq!(
+        Ratio::best_approximation(std::f64::consts::PI, 1000),
+        Ratio::new(355, 113)
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_frame_period() {
+    let hz60 = Ratio::new(60, 1);
+    assert_eq!(hz60.frame_period_secs(), Some(1.0 / 60.0));
+    assert_eq!(hz60.frame_period_100ns(), Some(166_667));
+    assert_eq!(Ratio::new(60000, 1001).frame_period_100ns(), Some(166_833));
+    assert_eq!(
+        Ratio::new(144, 1).frame_period_qpc(10_000_000),
+        Some(69_444)
+    );
+    assert_eq!(Ratio::new(1, 1).frame_period_qpc(u64::MAX), Some(u64::MAX));
+    assert_eq!(Ratio::new(0, 1).frame_period_100ns(), None);
+    assert_eq!(Ratio::new(60, 0).frame_period_secs(), None);
+}
+
+#[cfg(test)]
+#[test]
+fn test_ntsc() {
+    assert_eq!(Ratio::ntsc(60), Ratio::new(60000, 1001));
+    assert_eq!(Ratio::ntsc(24), Ratio::new(24000, 1001));
+    assert_eq!(Ratio::new(120000, 2002).ntsc_nominal(), Some(60));
+    assert_eq!(Ratio::new(30000, 1001).ntsc_nominal(), Some(30));
+    assert_eq!(Ratio::new(60, 1).ntsc_nominal(), None);
+    assert_eq!(Ratio::new(2997, 50).ntsc_nominal(), None);
+    assert_eq!(Ratio::new(1000, 1).ntsc_nominal(), None);
+
+    assert_eq!(
+        Ratio::new(2997, 50).snap_broadcast(0.001),
+        Some(Ratio::new(60000, 1001))
+    );
+    assert_eq!(
+        Ratio::new(59_999, 1000).snap_broadcast(0.01),
+        Some(Ratio::new(60, 1))
+    );
+    assert_eq!(
+        Ratio::new(2397, 100).snap_broadcast(0.01),
+        Some(Ratio::new(24000, 1001))
+    );
+    assert_eq!(Ratio::new(75, 2).snap_broadcast(0.1), None);
+    assert_eq!(Ratio::new(0, 0).snap_broadcast(1.0), None);
+}
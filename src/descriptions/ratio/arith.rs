@@ -0,0 +1,181 @@
+use crate::descriptions::Ratio;
+
+use std::ops::{Add, Div, Mul, Sub};
+
+const MAX: u128 = u32::MAX as u128;
+
+impl Ratio {
+    /// Adds two ratios, returning `None` if either is unspecified or the
+    /// reduced sum does not fit in `u32` parts.
+    pub fn checked_add(self, rhs: Ratio) -> Option<Ratio> {
+        let (a, b) = specified(self, rhs)?;
+        let (n, d) = (a.0 * b.1 + b.0 * a.1, a.1 * b.1);
+        exact(n, d)
+    }
+
+    /// Subtracts two ratios, returning `None` if either is unspecified, the
+    /// difference is negative or it does not fit in `u32` parts.
+    pub fn checked_sub(self, rhs: Ratio) -> Option<Ratio> {
+        let (a, b) = specified(self, rhs)?;
+        let n = (a.0 * b.1).checked_sub(b.0 * a.1)?;
+        exact(n, a.1 * b.1)
+    }
+
+    /// Multiplies two ratios, returning `None` if either is unspecified or
+    /// the reduced product does not fit in `u32` parts.
+    pub fn checked_mul(self, rhs: Ratio) -> Option<Ratio> {
+        let (a, b) = specified(self, rhs)?;
+        exact(a.0 * b.0, a.1 * b.1)
+    }
+
+    /// Divides two ratios, returning `None` if either is unspecified, `rhs`
+    /// is zero or the reduced quotient does not fit in `u32` parts.
+    pub fn checked_div(self, rhs: Ratio) -> Option<Ratio> {
+        let (a, b) = specified(self, rhs)?;
+        if b.0 == 0 {
+            return None;
+        }
+        exact(a.0 * b.1, a.1 * b.0)
+    }
+
+    /// The ratio closest to `self` whose denominator is at most
+    /// `max_denominator`.
+    pub fn limit_denominator(&self, max_denominator: u32) -> Ratio {
+        if self.denominator == 0 {
+            return *self;
+        }
+        nearest(
+            self.numerator as u128,
+            self.denominator as u128,
+            max_denominator.max(1) as u128,
+        )
+    }
+}
+
+impl Add for Ratio {
+    type Output = Ratio;
+
+    fn add(self, rhs: Ratio) -> Ratio {
+        match specified(self, rhs) {
+            Some((a, b)) => nearest(a.0 * b.1 + b.0 * a.1, a.1 * b.1, MAX),
+            None => Ratio::new(0, 0),
+        }
+    }
+}
+
+impl Sub for Ratio {
+    type Output = Ratio;
+
+    fn sub(self, rhs: Ratio) -> Ratio {
+        match specified(self, rhs) {
+            Some((a, b)) => {
+                let n = (a.0 * b.1).saturating_sub(b.0 * a.1);
+                nearest(n, a.1 * b.1, MAX)
+            }
+            None => Ratio::new(0, 0),
+        }
+    }
+}
+
+impl Mul for Ratio {
+    type Output = Ratio;
+
+    fn mul(self, rhs: Ratio) -> Ratio {
+        match specified(self, rhs) {
+            Some((a, b)) => nearest(a.0 * b.0, a.1 * b.1, MAX),
+            None => Ratio::new(0, 0),
+        }
+    }
+}
+
+impl Div for Ratio {
+    type Output = Ratio;
+
+    fn div(self, rhs: Ratio) -> Ratio {
+        match specified(self, rhs) {
+            Some((a, b)) if b.0 != 0 => nearest(a.0 * b.1, a.1 * b.0, MAX),
+            _ => Ratio::new(0, 0),
+        }
+    }
+}
+
+type Parts = (u128, u128);
+
+fn specified(a: Ratio, b: Ratio) -> Option<(Parts, Parts)> {
+    if a.denominator == 0 || b.denominator == 0 {
+        return None;
+    }
+    Some((
+        (a.numerator as u128, a.denominator as u128),
+        (b.numerator as u128, b.denominator as u128),
+    ))
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+fn exact(n: u128, d: u128) -> Option<Ratio> {
+    let g = gcd(n, d).max(1);
+    let (n, d) = (n / g, d / g);
+    if n <= MAX && d <= MAX {
+        Some(Ratio::new(n as u32, d as u32))
+    } else {
+        None
+    }
+}
+
+/// The ratio closest to `n/d` with a numerator that fits in `u32` and a
+/// denominator of at most `max_den`.
+pub(super) fn nearest(n: u128, d: u128, max_den: u128) -> Ratio {
+    match exact(n, d) {
+        Some(ratio) if ratio.denominator as u128 <= max_den => ratio,
+        _ => continued_fraction(n, d, max_den, 0.0),
+    }
+}
+
+/// Walks the continued fraction expansion of `n/d`, stopping at the first
+/// convergent within `tolerance` of it. When a convergent would exceed the
+/// bounds, the best semiconvergent within them is used instead.
+pub(super) fn continued_fraction(mut n: u128, mut d: u128, max_den: u128, tolerance: f64) -> Ratio {
+    debug_assert!(d != 0);
+    let value = n as f64 / d as f64;
+    let error = |p: u128, q: u128| (value - p as f64 / q as f64).abs();
+
+    let (mut p0, mut q0, mut p1, mut q1) = (0u128, 1u128, 1u128, 0u128);
+    while d != 0 {
+        let a = n / d;
+        let p2 = a.saturating_mul(p1).saturating_add(p0);
+        let q2 = a.saturating_mul(q1).saturating_add(q0);
+
+        if p2 > MAX || q2 > max_den {
+            let k_num = (MAX - p0).checked_div(p1).unwrap_or(a);
+            let k_den = (max_den - q0).checked_div(q1).unwrap_or(a);
+            let k = k_num.min(k_den);
+            let (ps, qs) = (k * p1 + p0, k * q1 + q0);
+            if q1 == 0 || (qs != 0 && error(ps, qs) < error(p1, q1)) {
+                return Ratio::new(ps as u32, qs as u32);
+            }
+            break;
+        }
+
+        p0 = p1;
+        q0 = q1;
+        p1 = p2;
+        q1 = q2;
+        if tolerance > 0.0 && error(p1, q1) <= tolerance {
+            break;
+        }
+
+        let r = n - a * d;
+        n = d;
+        d = r;
+    }
+
+    Ratio::new(p1 as u32, q1 as u32)
+}
@@ -1,8 +1,11 @@
+#[cfg(windows)]
 use winapi::shared::dxgitype::DXGI_RGB;
+#[cfg(windows)]
 use winapi::shared::dxgitype::DXGI_RGBA;
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rgb {
     pub r: f32,
     pub g: f32,
@@ -12,19 +15,21 @@ pub struct Rgb {
 #[cfg(test)]
 member_compat_test! {
     rgb_compat:
-    Rgb <=> DXGI_RGB {
+    Rgb <=> crate::descriptions::layouts::DXGI_RGB {
         r <=> Red,
         g <=> Green,
         b <=> Blue,
     }
 }
 
+#[cfg(windows)]
 impl From<DXGI_RGB> for Rgb {
     fn from(desc: DXGI_RGB) -> Rgb {
         unsafe { std::mem::transmute(desc) }
     }
 }
 
+#[cfg(windows)]
 impl From<Rgb> for DXGI_RGB {
     fn from(desc: Rgb) -> DXGI_RGB {
         unsafe { std::mem::transmute(desc) }
@@ -43,7 +48,7 @@ pub struct Rgba {
 #[cfg(test)]
 member_compat_test! {
     rgba_compat:
-    Rgba <=> DXGI_RGBA {
+    Rgba <=> crate::descriptions::layouts::DXGI_RGBA {
         r <=> r,
         g <=> g,
         b <=> b,
@@ -51,12 +56,14 @@ member_compat_test! {
     }
 }
 
+#[cfg(windows)]
 impl From<DXGI_RGBA> for Rgba {
     fn from(desc: DXGI_RGBA) -> Rgba {
         unsafe { std::mem::transmute(desc) }
     }
 }
 
+#[cfg(windows)]
 impl From<Rgba> for DXGI_RGBA {
     fn from(desc: Rgba) -> DXGI_RGBA {
         unsafe { std::mem::transmute(desc) }
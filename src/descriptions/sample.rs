@@ -1,7 +1,9 @@
+#[cfg(windows)]
 use winapi::shared::dxgitype::DXGI_SAMPLE_DESC;
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SampleDesc {
     pub count: u32,
     pub quality: u32,
@@ -19,18 +21,20 @@ impl Default for SampleDesc {
 #[cfg(test)]
 member_compat_test! {
     sample_desc_compat:
-    SampleDesc <=> DXGI_SAMPLE_DESC {
+    SampleDesc <=> crate::descriptions::layouts::DXGI_SAMPLE_DESC {
         count <=> Count,
         quality <=> Quality,
     }
 }
 
+#[cfg(windows)]
 impl From<DXGI_SAMPLE_DESC> for SampleDesc {
     fn from(desc: DXGI_SAMPLE_DESC) -> SampleDesc {
         unsafe { std::mem::transmute(desc) }
     }
 }
 
+#[cfg(windows)]
 impl From<SampleDesc> for DXGI_SAMPLE_DESC {
     fn from(desc: SampleDesc) -> DXGI_SAMPLE_DESC {
         unsafe { std::mem::transmute(desc) }
@@ -2,6 +2,7 @@ use crate::descriptions::SampleDesc;
 use crate::enums::Format;
 
 use checked_enum::UncheckedEnum;
+#[cfg(windows)]
 use winapi::shared::dxgi::DXGI_SURFACE_DESC;
 
 #[repr(C)]
@@ -27,7 +28,7 @@ impl Default for SurfaceDesc {
 #[cfg(test)]
 member_compat_test! {
     surface_desc_compat:
-    SurfaceDesc <=> DXGI_SURFACE_DESC {
+    SurfaceDesc <=> crate::descriptions::layouts::DXGI_SURFACE_DESC {
         width <=> Width,
         height <=> Height,
         format <=> Format,
@@ -35,12 +36,14 @@ member_compat_test! {
     }
 }
 
+#[cfg(windows)]
 impl From<DXGI_SURFACE_DESC> for SurfaceDesc {
     fn from(desc: DXGI_SURFACE_DESC) -> SurfaceDesc {
         unsafe { std::mem::transmute(desc) }
     }
 }
 
+#[cfg(windows)]
 impl From<SurfaceDesc> for DXGI_SURFACE_DESC {
     fn from(desc: SurfaceDesc) -> DXGI_SURFACE_DESC {
         unsafe { std::mem::transmute(desc) }
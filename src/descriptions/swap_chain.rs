@@ -1,21 +1,31 @@
-use crate::descriptions::{DBool, Mode, SampleDesc};
+use crate::descriptions::{DBool, Mode, SampleDesc, HWND};
 use crate::enums::Format;
 use crate::enums::{AlphaMode, Scaling, SwapChainFlags, SwapEffect, UsageFlags};
 
 use checked_enum::UncheckedEnum;
+#[cfg(windows)]
 use winapi::shared::dxgi::DXGI_SWAP_CHAIN_DESC;
+#[cfg(windows)]
 use winapi::shared::dxgi1_2::DXGI_SWAP_CHAIN_DESC1;
-use winapi::shared::windef::HWND;
+
+#[cfg(windows)]
+pub(crate) use self::validate::{check_swap_chain, SwapChainSettings};
+pub use self::validate::{SwapChainError, SwapChainIssue};
+
+mod validate;
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SwapChainDesc {
     pub buffer_desc: Mode,
     pub sample_desc: SampleDesc,
     pub buffer_usage: UsageFlags,
     pub buffer_count: u32,
+    #[cfg_attr(feature = "serde", serde(skip, default = "std::ptr::null_mut"))]
     pub output_window: HWND,
     pub windowed: DBool,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::unchecked"))]
     pub swap_effect: UncheckedEnum<SwapEffect>,
     pub flags: SwapChainFlags,
 }
@@ -23,7 +33,7 @@ pub struct SwapChainDesc {
 #[cfg(test)]
 member_compat_test! {
     swap_chain_desc_compat:
-    SwapChainDesc <=> DXGI_SWAP_CHAIN_DESC {
+    SwapChainDesc <=> crate::descriptions::layouts::DXGI_SWAP_CHAIN_DESC {
         buffer_desc <=> BufferDesc,
         sample_desc <=> SampleDesc,
         buffer_usage <=> BufferUsage,
@@ -35,12 +45,14 @@ member_compat_test! {
     }
 }
 
+#[cfg(windows)]
 impl From<DXGI_SWAP_CHAIN_DESC> for SwapChainDesc {
     fn from(desc: DXGI_SWAP_CHAIN_DESC) -> SwapChainDesc {
         unsafe { std::mem::transmute(desc) }
     }
 }
 
+#[cfg(windows)]
 impl From<SwapChainDesc> for DXGI_SWAP_CHAIN_DESC {
     fn from(desc: SwapChainDesc) -> DXGI_SWAP_CHAIN_DESC {
         unsafe { std::mem::transmute(desc) }
@@ -64,16 +76,21 @@ impl Default for SwapChainDesc {
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SwapChainDesc1 {
     pub width: u32,
     pub height: u32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::unchecked"))]
     pub format: UncheckedEnum<Format>,
     pub stereo: DBool,
     pub sample_desc: SampleDesc,
     pub buffer_usage: UsageFlags,
     pub buffer_count: u32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::unchecked"))]
     pub scaling: UncheckedEnum<Scaling>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::unchecked"))]
     pub swap_effect: UncheckedEnum<SwapEffect>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::unchecked"))]
     pub alpha_mode: UncheckedEnum<AlphaMode>,
     pub flags: SwapChainFlags,
 }
@@ -81,7 +98,7 @@ pub struct SwapChainDesc1 {
 #[cfg(test)]
 member_compat_test! {
     swap_chain_desc1_compat:
-    SwapChainDesc1 <=> DXGI_SWAP_CHAIN_DESC1 {
+    SwapChainDesc1 <=> crate::descriptions::layouts::DXGI_SWAP_CHAIN_DESC1 {
         width <=> Width,
         height <=> Height,
         format <=> Format,
@@ -96,12 +113,14 @@ member_compat_test! {
     }
 }
 
+#[cfg(windows)]
 impl From<DXGI_SWAP_CHAIN_DESC1> for SwapChainDesc1 {
     fn from(desc: DXGI_SWAP_CHAIN_DESC1) -> SwapChainDesc1 {
         unsafe { std::mem::transmute(desc) }
     }
 }
 
+#[cfg(windows)]
 impl From<SwapChainDesc1> for DXGI_SWAP_CHAIN_DESC1 {
     fn from(desc: SwapChainDesc1) -> DXGI_SWAP_CHAIN_DESC1 {
         unsafe { std::mem::transmute(desc) }
@@ -0,0 +1,416 @@
+use crate::descriptions::{SwapChainDesc, SwapChainDesc1};
+use crate::enums::{AlphaMode, Format, NumericType, SwapChainFlags, SwapEffect};
+use crate::error::DxgiError;
+
+use checked_enum::{CheckedEnum, UncheckedEnum};
+use std::fmt;
+
+/// A combination of swap chain settings that DXGI rejects, usually with
+/// nothing more specific than `DXGI_ERROR_INVALID_CALL`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SwapChainIssue {
+    /// Flip model swap chains can't be multisampled.
+    FlipWithMsaa { sample_count: u32 },
+    /// `SwapEffect::Sequential` swap chains can't be multisampled.
+    SequentialWithMsaa { sample_count: u32 },
+    /// Flip model swap chains need at least 2 buffers.
+    TooFewFlipBuffers { buffer_count: u32 },
+    /// `SwapChainFlags::ALLOW_TEARING` requires a flip model swap effect.
+    TearingWithoutFlip {
+        swap_effect: UncheckedEnum<SwapEffect>,
+    },
+    /// Flip model swap chains only accept a few buffer formats.
+    FlipFormat {
+        format: UncheckedEnum<Format>,
+        suggested: Format,
+    },
+    /// Swap chains for a window can't use `AlphaMode::Straight`.
+    StraightAlphaForHwnd,
+}
+
+impl SwapChainIssue {
+    /// How to change the description to fix the issue.
+    pub fn suggestion(&self) -> String {
+        match *self {
+            SwapChainIssue::FlipWithMsaa { .. } => {
+                "use a sample count of 1 and resolve an MSAA texture into the buffers".to_string()
+            }
+            SwapChainIssue::SequentialWithMsaa { .. } => {
+                "use a sample count of 1, or SwapEffect::Discard".to_string()
+            }
+            SwapChainIssue::TooFewFlipBuffers { .. } => {
+                "use a buffer count of 2 or more".to_string()
+            }
+            SwapChainIssue::TearingWithoutFlip { .. } => {
+                "use SwapEffect::FlipDiscard or SwapEffect::FlipSequential".to_string()
+            }
+            SwapChainIssue::FlipFormat { suggested, .. } => format!(
+                "use {:?} and create the render target view with the format you need",
+                suggested
+            ),
+            SwapChainIssue::StraightAlphaForHwnd => {
+                "use AlphaMode::Ignore or AlphaMode::Unspecified".to_string()
+            }
+        }
+    }
+}
+
+impl fmt::Display for SwapChainIssue {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SwapChainIssue::FlipWithMsaa { sample_count } => write!(
+                fmt,
+                "flip model swap chains can't have {} samples",
+                sample_count
+            )?,
+            SwapChainIssue::SequentialWithMsaa { sample_count } => write!(
+                fmt,
+                "sequential swap chains can't have {} samples",
+                sample_count
+            )?,
+            SwapChainIssue::TooFewFlipBuffers { buffer_count } => write!(
+                fmt,
+                "flip model swap chains can't have {} buffer(s)",
+                buffer_count
+            )?,
+            SwapChainIssue::TearingWithoutFlip { swap_effect } => {
+                fmt.write_str("ALLOW_TEARING requires a flip model swap effect, not ")?;
+                write_enum(fmt, swap_effect)?;
+            }
+            SwapChainIssue::FlipFormat { format, .. } => {
+                fmt.write_str("flip model swap chains can't use ")?;
+                write_enum(fmt, format)?;
+            }
+            SwapChainIssue::StraightAlphaForHwnd => {
+                fmt.write_str("swap chains for a window can't use straight alpha")?
+            }
+        }
+        write!(fmt, "; {}", self.suggestion())
+    }
+}
+
+/// Why a swap chain couldn't be created or its buffers resized.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SwapChainError {
+    /// The settings have issues DXGI is known to reject, so DXGI wasn't
+    /// called.
+    Invalid(Vec<SwapChainIssue>),
+    /// DXGI failed the call.
+    Dxgi(DxgiError),
+}
+
+impl SwapChainError {
+    /// Fails with `Invalid` if there are any issues.
+    pub fn check(issues: Vec<SwapChainIssue>) -> Result<(), SwapChainError> {
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(SwapChainError::Invalid(issues))
+        }
+    }
+
+    /// The issues that stopped the call, if any.
+    pub fn issues(&self) -> &[SwapChainIssue] {
+        match *self {
+            SwapChainError::Invalid(ref issues) => issues,
+            SwapChainError::Dxgi(_) => &[],
+        }
+    }
+}
+
+impl fmt::Display for SwapChainError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SwapChainError::Invalid(ref issues) => {
+                fmt.write_str("invalid swap chain settings")?;
+                for issue in issues {
+                    write!(fmt, "\n  {}", issue)?;
+                }
+                Ok(())
+            }
+            SwapChainError::Dxgi(error) => error.fmt(fmt),
+        }
+    }
+}
+
+impl std::error::Error for SwapChainError {}
+
+impl From<DxgiError> for SwapChainError {
+    fn from(error: DxgiError) -> SwapChainError {
+        SwapChainError::Dxgi(error)
+    }
+}
+
+#[cfg(windows)]
+impl From<dcommon::error::Error> for SwapChainError {
+    fn from(error: dcommon::error::Error) -> SwapChainError {
+        SwapChainError::Dxgi(error.into())
+    }
+}
+
+impl SwapChainDesc {
+    /// Checks the description for combinations that `CreateSwapChain` is
+    /// known to reject. Returns an empty list if none were found.
+    pub fn validate(&self) -> Vec<SwapChainIssue> {
+        check_swap_chain(&SwapChainSettings {
+            swap_effect: self.swap_effect,
+            sample_count: self.sample_desc.count,
+            buffer_count: Some(self.buffer_count),
+            format: self.buffer_desc.format,
+            flags: self.flags,
+        })
+    }
+}
+
+impl SwapChainDesc1 {
+    /// Checks the description for combinations that the swap chain creation
+    /// functions are known to reject. Returns an empty list if none were
+    /// found.
+    ///
+    /// This doesn't include the rules that only apply to swap chains for a
+    /// window; see `validate_hwnd`.
+    pub fn validate(&self) -> Vec<SwapChainIssue> {
+        check_swap_chain(&SwapChainSettings {
+            swap_effect: self.swap_effect,
+            sample_count: self.sample_desc.count,
+            buffer_count: Some(self.buffer_count),
+            format: self.format,
+            flags: self.flags,
+        })
+    }
+
+    /// Checks the description for combinations that `CreateSwapChainForHwnd`
+    /// is known to reject. Returns an empty list if none were found.
+    pub fn validate_hwnd(&self) -> Vec<SwapChainIssue> {
+        let mut issues = self.validate();
+        if self.alpha_mode.value == AlphaMode::Straight as u32 {
+            issues.push(SwapChainIssue::StraightAlphaForHwnd);
+        }
+        issues
+    }
+}
+
+/// The settings shared by every kind of swap chain description.
+pub(crate) struct SwapChainSettings {
+    pub swap_effect: UncheckedEnum<SwapEffect>,
+    pub sample_count: u32,
+    /// `None` when the buffer count is being left as it is.
+    pub buffer_count: Option<u32>,
+    /// `Format::Unknown` when the format is being left as it is.
+    pub format: UncheckedEnum<Format>,
+    pub flags: SwapChainFlags,
+}
+
+pub(crate) fn check_swap_chain(settings: &SwapChainSettings) -> Vec<SwapChainIssue> {
+    let mut issues = Vec::new();
+    let effect = settings.swap_effect.value;
+    let is_flip =
+        effect == SwapEffect::FlipSequential as u32 || effect == SwapEffect::FlipDiscard as u32;
+
+    if settings.sample_count > 1 {
+        let sample_count = settings.sample_count;
+        if is_flip {
+            issues.push(SwapChainIssue::FlipWithMsaa { sample_count });
+        } else if effect == SwapEffect::Sequential as u32 {
+            issues.push(SwapChainIssue::SequentialWithMsaa { sample_count });
+        }
+    }
+
+    if let Some(buffer_count) = settings.buffer_count {
+        if is_flip && buffer_count < 2 {
+            issues.push(SwapChainIssue::TooFewFlipBuffers { buffer_count });
+        }
+    }
+
+    if settings.flags.0 & SwapChainFlags::ALLOW_TEARING.0 != 0 && !is_flip {
+        issues.push(SwapChainIssue::TearingWithoutFlip {
+            swap_effect: settings.swap_effect,
+        });
+    }
+
+    let format = settings.format;
+    if is_flip && format.value != Format::Unknown as u32 && !is_flip_format(format) {
+        issues.push(SwapChainIssue::FlipFormat {
+            format,
+            suggested: suggest_flip_format(format),
+        });
+    }
+
+    issues
+}
+
+const FLIP_FORMATS: [Format; 4] = [
+    Format::R16G16B16A16Float,
+    Format::B8G8R8A8Unorm,
+    Format::R8G8B8A8Unorm,
+    Format::R10G10B10A2Unorm,
+];
+
+fn is_flip_format(format: UncheckedEnum<Format>) -> bool {
+    FLIP_FORMATS.iter().any(|&f| f as u32 == format.value)
+}
+
+fn suggest_flip_format(format: UncheckedEnum<Format>) -> Format {
+    let format = match format.as_enum() {
+        Some(format) => format,
+        None => return Format::B8G8R8A8Unorm,
+    };
+
+    let linear = format.to_linear();
+    if FLIP_FORMATS.contains(&linear) {
+        linear
+    } else if format.info().numeric == NumericType::Float {
+        Format::R16G16B16A16Float
+    } else {
+        Format::B8G8R8A8Unorm
+    }
+}
+
+fn write_enum<T>(fmt: &mut fmt::Formatter, value: UncheckedEnum<T>) -> fmt::Result
+where
+    T: fmt::Debug + CheckedEnum<Storage = u32>,
+{
+    match value.as_enum() {
+        Some(value) => write!(fmt, "{:?}", value),
+        None => write!(fmt, "{}", value.value),
+    }
+}
+
+#[cfg(test)]
+fn flip_desc() -> SwapChainDesc1 {
+    SwapChainDesc1 {
+        swap_effect: SwapEffect::FlipDiscard.into(),
+        format: Format::B8G8R8A8Unorm.into(),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn valid_descriptions() {
+    assert_eq!(SwapChainDesc1::default().validate_hwnd(), vec![]);
+    assert_eq!(SwapChainDesc::default().validate(), vec![]);
+    assert_eq!(flip_desc().validate_hwnd(), vec![]);
+
+    let tearing = SwapChainDesc1 {
+        flags: SwapChainFlags::ALLOW_TEARING,
+        buffer_count: 3,
+        format: Format::R10G10B10A2Unorm.into(),
+        ..flip_desc()
+    };
+    assert_eq!(tearing.validate_hwnd(), vec![]);
+
+    // Straight alpha is fine for composition swap chains.
+    let straight = SwapChainDesc1 {
+        alpha_mode: AlphaMode::Straight.into(),
+        ..flip_desc()
+    };
+    assert_eq!(straight.validate(), vec![]);
+    assert_eq!(
+        straight.validate_hwnd(),
+        vec![SwapChainIssue::StraightAlphaForHwnd]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn flip_rules() {
+    let mut desc = flip_desc();
+    desc.sample_desc.count = 4;
+    desc.buffer_count = 1;
+    desc.format = Format::B8G8R8A8UnormSrgb.into();
+
+    let issues = desc.validate();
+    assert_eq!(
+        issues,
+        vec![
+            SwapChainIssue::FlipWithMsaa { sample_count: 4 },
+            SwapChainIssue::TooFewFlipBuffers { buffer_count: 1 },
+            SwapChainIssue::FlipFormat {
+                format: Format::B8G8R8A8UnormSrgb.into(),
+                suggested: Format::B8G8R8A8Unorm,
+            },
+        ]
+    );
+    assert_eq!(
+        issues[2].to_string(),
+        "flip model swap chains can't use B8G8R8A8UnormSrgb; use B8G8R8A8Unorm and \
+         create the render target view with the format you need"
+    );
+
+    desc.format = Format::R32G32B32A32Float.into();
+    desc.sample_desc.count = 1;
+    desc.buffer_count = 2;
+    assert_eq!(
+        desc.validate(),
+        vec![SwapChainIssue::FlipFormat {
+            format: Format::R32G32B32A32Float.into(),
+            suggested: Format::R16G16B16A16Float,
+        }]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn bitblt_rules() {
+    let mut desc = SwapChainDesc {
+        swap_effect: SwapEffect::Sequential.into(),
+        flags: SwapChainFlags::ALLOW_TEARING,
+        ..Default::default()
+    };
+    desc.sample_desc.count = 8;
+    desc.buffer_count = 1;
+
+    let issues = desc.validate();
+    assert_eq!(
+        issues,
+        vec![
+            SwapChainIssue::SequentialWithMsaa { sample_count: 8 },
+            SwapChainIssue::TearingWithoutFlip {
+                swap_effect: SwapEffect::Sequential.into(),
+            },
+        ]
+    );
+    assert_eq!(
+        issues[1].to_string(),
+        "ALLOW_TEARING requires a flip model swap effect, not Sequential; \
+         use SwapEffect::FlipDiscard or SwapEffect::FlipSequential"
+    );
+
+    // Discard allows MSAA and any format.
+    desc.swap_effect = SwapEffect::Discard.into();
+    desc.flags = SwapChainFlags::NONE;
+    desc.buffer_desc.format = Format::B8G8R8A8UnormSrgb.into();
+    assert_eq!(desc.validate(), vec![]);
+}
+
+#[cfg(test)]
+#[test]
+fn errors_carry_issues() {
+    let mut desc = flip_desc();
+    desc.alpha_mode = AlphaMode::Straight.into();
+    desc.sample_desc.count = 4;
+
+    let error = SwapChainError::check(desc.validate_hwnd()).unwrap_err();
+    assert_eq!(
+        error,
+        SwapChainError::Invalid(vec![
+            SwapChainIssue::FlipWithMsaa { sample_count: 4 },
+            SwapChainIssue::StraightAlphaForHwnd,
+        ])
+    );
+    assert_eq!(error.issues(), &desc.validate_hwnd()[..]);
+    assert_eq!(
+        error.to_string(),
+        "invalid swap chain settings\n  \
+         flip model swap chains can't have 4 samples; use a sample count of 1 and resolve \
+         an MSAA texture into the buffers\n  \
+         swap chains for a window can't use straight alpha; use AlphaMode::Ignore or \
+         AlphaMode::Unspecified"
+    );
+
+    assert_eq!(SwapChainError::check(flip_desc().validate_hwnd()), Ok(()));
+    let error = SwapChainError::from(DxgiError::InvalidCall);
+    assert_eq!(error.issues(), &[]);
+    assert_eq!(error.to_string(), DxgiError::InvalidCall.to_string());
+}
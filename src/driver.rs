@@ -0,0 +1,392 @@
+//! User-mode driver versions, as returned by
+//! `IAdapter::check_interface_support`, and blocklists of known bad ones.
+
+use crate::pci::{vendor, PciId};
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A user-mode driver version, `product.version.subversion.build`, e.g.
+/// `31.0.15.3623`. Versions order by their parts from left to right.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DriverVersion {
+    pub product: u16,
+    pub version: u16,
+    pub subversion: u16,
+    pub build: u16,
+}
+
+impl DriverVersion {
+    pub fn new(product: u16, version: u16, subversion: u16, build: u16) -> DriverVersion {
+        DriverVersion {
+            product,
+            version,
+            subversion,
+            build,
+        }
+    }
+
+    /// Splits the `LARGE_INTEGER` returned by `CheckInterfaceSupport`, which
+    /// holds one part in each 16 bits, `product` highest.
+    pub fn from_raw(raw: i64) -> DriverVersion {
+        let raw = raw as u64;
+        DriverVersion {
+            product: (raw >> 48) as u16,
+            version: (raw >> 32) as u16,
+            subversion: (raw >> 16) as u16,
+            build: raw as u16,
+        }
+    }
+
+    pub fn to_raw(self) -> i64 {
+        ((self.product as u64) << 48
+            | (self.version as u64) << 32
+            | (self.subversion as u64) << 16
+            | self.build as u64) as i64
+    }
+
+    /// The version as the vendor's release notes and control panel write it,
+    /// falling back to the full version.
+    ///
+    /// - NVIDIA: the last five digits of `subversion` and `build`, so
+    ///   `31.0.15.3623` is `536.23`.
+    /// - Intel: `subversion.build` for the driver numbering used since 2018,
+    ///   so `31.0.101.4502` is `101.4502`.
+    pub fn vendor_string(&self, vendor_id: u32) -> String {
+        match vendor_id {
+            vendor::NVIDIA => {
+                let number = (self.subversion as u32 % 10) * 10000 + self.build as u32;
+                format!("{}.{:02}", number / 100, number % 100)
+            }
+            vendor::INTEL if self.subversion >= 100 => {
+                format!("{}.{}", self.subversion, self.build)
+            }
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl From<i64> for DriverVersion {
+    fn from(raw: i64) -> DriverVersion {
+        DriverVersion::from_raw(raw)
+    }
+}
+
+impl From<DriverVersion> for i64 {
+    fn from(version: DriverVersion) -> i64 {
+        version.to_raw()
+    }
+}
+
+impl fmt::Display for DriverVersion {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "{}.{}.{}.{}",
+            self.product, self.version, self.subversion, self.build
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+/// An error parsing a `DriverVersion`.
+pub enum DriverVersionError {
+    /// The string isn't one to four numbers separated by dots.
+    InvalidFormat,
+    /// A part is larger than 65535.
+    OutOfRange,
+}
+
+impl fmt::Display for DriverVersionError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DriverVersionError::InvalidFormat => {
+                fmt.write_str("driver versions are up to four numbers separated by dots")
+            }
+            DriverVersionError::OutOfRange => {
+                fmt.write_str("driver version parts must be at most 65535")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DriverVersionError {}
+
+impl FromStr for DriverVersion {
+    type Err = DriverVersionError;
+
+    /// Parses `product.version.subversion.build`. Missing trailing parts
+    /// are 0, so `31.0` is `31.0.0.0`.
+    fn from_str(s: &str) -> Result<DriverVersion, DriverVersionError> {
+        let mut parts = [0u16; 4];
+        for (index, part) in s.trim().split('.').enumerate() {
+            let valid =
+                index < parts.len() && !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+            if !valid {
+                return Err(DriverVersionError::InvalidFormat);
+            }
+            parts[index] = part.parse().map_err(|_| DriverVersionError::OutOfRange)?;
+        }
+        Ok(DriverVersion::new(parts[0], parts[1], parts[2], parts[3]))
+    }
+}
+
+/// A range of drivers to avoid, e.g. "NVIDIA drivers older than 536.23".
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DriverRule {
+    pub vendor_id: u32,
+    /// The devices the rule applies to. Empty for every device of the
+    /// vendor.
+    pub device_ids: Vec<u32>,
+    /// The first affected version, if there is a lower bound.
+    pub from: Option<DriverVersion>,
+    /// The first fixed version, if there is one.
+    pub fixed_in: Option<DriverVersion>,
+    /// Why the drivers are avoided, for logs and crash reports.
+    pub reason: String,
+}
+
+impl DriverRule {
+    /// Matches every driver from `vendor_id` older than `fixed_in`.
+    pub fn older_than(vendor_id: u32, fixed_in: DriverVersion) -> DriverRule {
+        DriverRule {
+            vendor_id,
+            device_ids: Vec::new(),
+            from: None,
+            fixed_in: Some(fixed_in),
+            reason: String::new(),
+        }
+    }
+
+    /// Matches drivers from `vendor_id` from `from` up to but not including
+    /// `fixed_in`.
+    pub fn between(vendor_id: u32, from: DriverVersion, fixed_in: DriverVersion) -> DriverRule {
+        DriverRule {
+            from: Some(from),
+            ..DriverRule::older_than(vendor_id, fixed_in)
+        }
+    }
+
+    /// Matches every driver from `vendor_id` from `from` on, for problems
+    /// that haven't been fixed yet.
+    pub fn since(vendor_id: u32, from: DriverVersion) -> DriverRule {
+        DriverRule {
+            vendor_id,
+            device_ids: Vec::new(),
+            from: Some(from),
+            fixed_in: None,
+            reason: String::new(),
+        }
+    }
+
+    /// Restricts the rule to the given devices.
+    pub fn with_devices(mut self, device_ids: &[u32]) -> Self {
+        self.device_ids = device_ids.to_vec();
+        self
+    }
+
+    pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = reason.into();
+        self
+    }
+
+    /// Whether the adapter with `id` running driver `version` is affected.
+    pub fn matches(&self, id: &PciId, version: DriverVersion) -> bool {
+        id.vendor_id == self.vendor_id
+            && (self.device_ids.is_empty() || self.device_ids.contains(&id.device_id))
+            && self.from.map_or(true, |from| version >= from)
+            && self.fixed_in.map_or(true, |fixed_in| version < fixed_in)
+    }
+}
+
+/// A list of driver rules, checked in order.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DriverBlocklist {
+    pub rules: Vec<DriverRule>,
+}
+
+impl DriverBlocklist {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn with_rule(mut self, rule: DriverRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// The first rule affecting the adapter with `id` running driver
+    /// `version`, or `None` if the driver is fine.
+    pub fn check(&self, id: &PciId, version: DriverVersion) -> Option<&DriverRule> {
+        self.rules.iter().find(|rule| rule.matches(id, version))
+    }
+
+    /// Every rule affecting the adapter with `id` running driver `version`.
+    pub fn matching<'a>(
+        &'a self,
+        id: &'a PciId,
+        version: DriverVersion,
+    ) -> impl Iterator<Item = &'a DriverRule> + 'a {
+        self.rules
+            .iter()
+            .filter(move |rule| rule.matches(id, version))
+    }
+}
+
+#[cfg(test)]
+fn version(s: &str) -> DriverVersion {
+    s.parse().unwrap()
+}
+
+#[cfg(test)]
+#[test]
+fn raw_and_strings() {
+    let v = DriverVersion::from_raw(0x001F_0000_000F_0E27);
+    assert_eq!(v, DriverVersion::new(31, 0, 15, 3623));
+    assert_eq!(v.to_raw(), 0x001F_0000_000F_0E27);
+    assert_eq!(v.to_string(), "31.0.15.3623");
+    assert_eq!(version("31.0.15.3623"), v);
+
+    let high = DriverVersion::new(0xFFFF, 1, 2, 0xFFFF);
+    assert!(high.to_raw() < 0);
+    assert_eq!(DriverVersion::from(i64::from(high)), high);
+
+    assert_eq!(version("31"), DriverVersion::new(31, 0, 0, 0));
+    assert_eq!(
+        version(" 27.21.14.5671 "),
+        DriverVersion::new(27, 21, 14, 5671)
+    );
+    assert_eq!(
+        "31.0.15.70000".parse::<DriverVersion>(),
+        Err(DriverVersionError::OutOfRange)
+    );
+    for bad in &["", "31..15.3623", "31.0.15.3623.1", "31.0.-1.0", "v31"] {
+        assert_eq!(
+            bad.parse::<DriverVersion>(),
+            Err(DriverVersionError::InvalidFormat),
+            "{:?}",
+            bad
+        );
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn ordering() {
+    let mut versions = [
+        version("31.0.15.3623"),
+        version("30.0.15.1179"),
+        version("31.0.15.2698"),
+        version("31.0.101.4502"),
+        version("27.21.14.5671"),
+    ];
+    versions.sort();
+    let sorted: Vec<String> = versions.iter().map(|v| v.to_string()).collect();
+    assert_eq!(
+        sorted,
+        vec![
+            "27.21.14.5671",
+            "30.0.15.1179",
+            "31.0.15.2698",
+            "31.0.15.3623",
+            "31.0.101.4502",
+        ]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn vendor_strings() {
+    assert_eq!(
+        version("31.0.15.3623").vendor_string(vendor::NVIDIA),
+        "536.23"
+    );
+    assert_eq!(
+        version("27.21.14.5671").vendor_string(vendor::NVIDIA),
+        "456.71"
+    );
+    assert_eq!(
+        version("30.0.15.1179").vendor_string(vendor::NVIDIA),
+        "511.79"
+    );
+    assert_eq!(
+        version("31.0.15.3600").vendor_string(vendor::NVIDIA),
+        "536.00"
+    );
+    assert_eq!(
+        version("31.0.101.4502").vendor_string(vendor::INTEL),
+        "101.4502"
+    );
+    assert_eq!(
+        version("26.20.100.7262").vendor_string(vendor::INTEL),
+        "100.7262"
+    );
+    assert_eq!(
+        version("20.19.15.4835").vendor_string(vendor::INTEL),
+        "20.19.15.4835"
+    );
+    assert_eq!(
+        version("31.0.21001.45002").vendor_string(vendor::AMD),
+        "31.0.21001.45002"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn blocklist() {
+    let blocklist = DriverBlocklist::new()
+        .with_rule(
+            DriverRule::older_than(vendor::NVIDIA, version("31.0.15.3623"))
+                .with_reason("flip model stutter"),
+        )
+        .with_rule(
+            DriverRule::between(
+                vendor::INTEL,
+                version("31.0.101.4000"),
+                version("31.0.101.4500"),
+            )
+            .with_devices(&[0x56a0, 0x56a1])
+            .with_reason("HDR corruption"),
+        )
+        .with_rule(DriverRule::since(vendor::AMD, version("31.0.21000.0")).with_reason("hang"));
+
+    let nvidia = PciId {
+        vendor_id: vendor::NVIDIA,
+        device_id: 0x2484,
+        ..Default::default()
+    };
+    let reason = |id: &PciId, v: &str| blocklist.check(id, version(v)).map(|r| r.reason.as_str());
+    assert_eq!(reason(&nvidia, "31.0.15.2698"), Some("flip model stutter"));
+    assert_eq!(reason(&nvidia, "31.0.15.3623"), None);
+    assert_eq!(reason(&nvidia, "31.0.15.5222"), None);
+
+    let arc = PciId {
+        vendor_id: vendor::INTEL,
+        device_id: 0x56a0,
+        ..Default::default()
+    };
+    let iris = PciId {
+        device_id: 0x9a49,
+        ..arc
+    };
+    assert_eq!(reason(&arc, "31.0.101.3999"), None);
+    assert_eq!(reason(&arc, "31.0.101.4000"), Some("HDR corruption"));
+    assert_eq!(reason(&arc, "31.0.101.4500"), None);
+    assert_eq!(reason(&iris, "31.0.101.4000"), None);
+
+    let amd = PciId {
+        vendor_id: vendor::AMD,
+        ..Default::default()
+    };
+    assert_eq!(reason(&amd, "31.0.21001.45002"), Some("hang"));
+    assert_eq!(reason(&amd, "30.0.13025.5005"), None);
+    assert_eq!(
+        blocklist
+            .matching(&amd, version("31.0.21001.45002"))
+            .count(),
+        1
+    );
+}
@@ -1,4 +1,5 @@
 #[auto_enum::auto_enum(u32, checked)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Identifies the alpha transparency behavior of a surface
 pub enum AlphaMode {
     /// Unspecified, the meaning of this is contextual to the API
@@ -1,4 +1,13 @@
+pub use self::family::CastFamily;
+pub use self::info::{FormatChannel, FormatComponent, FormatInfo, NumericType};
+pub use self::layout::{MipChainLayout, PlaneLayout, SubresourceLayout, SurfaceLayout};
+
+mod family;
+mod info;
+mod layout;
+
 #[auto_enum::auto_enum(u32, checked)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Resource data formats, including fully-typed and typeless formats. A
 /// list of modifiers at the bottom of the page more fully describes each
 /// format type.
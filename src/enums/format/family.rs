@@ -0,0 +1,347 @@
+use crate::enums::{Format, NumericType};
+
+use std::slice;
+
+impl Format {
+    /// Get the typeless format of the cast family this format belongs to.
+    /// Typeless formats return themselves. Returns `None` for formats which
+    /// can't be created as a typeless resource (e.g. `R11G11B10Float`, the
+    /// video formats).
+    pub fn typeless(self) -> Option<Format> {
+        self.family().map(|family| family[0])
+    }
+
+    /// Get the sRGB member of this format's family. Only `Unorm`, `Typeless`
+    /// and sRGB formats have an sRGB counterpart, and only when the family
+    /// contains one (e.g. `R8G8B8A8Unorm` => `R8G8B8A8UnormSrgb`).
+    pub fn to_srgb(self) -> Option<Format> {
+        match self.info().numeric {
+            NumericType::Unorm | NumericType::Typeless | NumericType::Srgb => self
+                .family()?
+                .iter()
+                .cloned()
+                .find(|f| f.info().numeric == NumericType::Srgb),
+            _ => None,
+        }
+    }
+
+    /// Get the linear (non-sRGB) `Unorm` member of this format's family if
+    /// this is an sRGB format. All other formats are returned unchanged.
+    ///
+    /// Flip model swap chains do not accept sRGB buffer formats, so this can
+    /// be used to find the buffer format to create the swap chain with before
+    /// creating an sRGB render target view onto it.
+    pub fn to_linear(self) -> Format {
+        if self.info().numeric != NumericType::Srgb {
+            return self;
+        }
+
+        self.family()
+            .and_then(|family| {
+                family
+                    .iter()
+                    .cloned()
+                    .find(|f| f.info().numeric == NumericType::Unorm)
+            })
+            .unwrap_or(self)
+    }
+
+    /// Whether this is an sRGB format.
+    pub fn is_srgb(self) -> bool {
+        self.info().numeric == NumericType::Srgb
+    }
+
+    /// Whether this is a typeless format.
+    pub fn is_typeless(self) -> bool {
+        self.info().numeric == NumericType::Typeless && self != Format::Unknown
+    }
+
+    /// Get the format used to create a depth-stencil view of a resource in
+    /// this format's family, e.g. `R24G8Typeless` => `D24UnormS8Uint`.
+    pub fn depth_view_format(self) -> Option<Format> {
+        match self.typeless()? {
+            Format::R32G8X24Typeless => Some(Format::D32FloatS8X24Uint),
+            Format::R32Typeless => Some(Format::D32Float),
+            Format::R24G8Typeless => Some(Format::D24UnormS8Uint),
+            Format::R16Typeless => Some(Format::D16Unorm),
+            _ => None,
+        }
+    }
+
+    /// Get the format used to read the depth component of a resource in
+    /// this format's family from a shader, e.g. `R24G8Typeless` =>
+    /// `R24UnormX8Typeless`.
+    pub fn depth_srv_format(self) -> Option<Format> {
+        match self.typeless()? {
+            Format::R32G8X24Typeless => Some(Format::R32FloatX8X24Typeless),
+            Format::R32Typeless => Some(Format::R32Float),
+            Format::R24G8Typeless => Some(Format::R24UnormX8Typeless),
+            Format::R16Typeless => Some(Format::R16Unorm),
+            _ => None,
+        }
+    }
+
+    /// Get the format used to read the stencil component of a resource in
+    /// this format's family from a shader, e.g. `R24G8Typeless` =>
+    /// `X24TypelessG8Uint`.
+    pub fn stencil_view_format(self) -> Option<Format> {
+        match self.typeless()? {
+            Format::R32G8X24Typeless => Some(Format::X32TypelessG8X24Uint),
+            Format::R24G8Typeless => Some(Format::X24TypelessG8Uint),
+            _ => None,
+        }
+    }
+
+    /// Iterate over every format which can be used to view a resource of
+    /// this format, starting with the typeless format. Formats without a
+    /// family only yield themselves.
+    pub fn cast_family(self) -> CastFamily {
+        match self.family() {
+            Some(family) => CastFamily {
+                single: None,
+                members: family.iter(),
+            },
+            None => CastFamily {
+                single: Some(self),
+                members: [].iter(),
+            },
+        }
+    }
+
+    /// Whether a resource created with this format can be viewed as `other`
+    /// (both formats are in the same cast family).
+    pub fn is_cast_compatible(self, other: Format) -> bool {
+        self == other || (self.typeless().is_some() && self.typeless() == other.typeless())
+    }
+
+    fn family(self) -> Option<&'static [Format]> {
+        use self::Format::*;
+        static R32G32B32A32: &[Format] = &[
+            R32G32B32A32Typeless,
+            R32G32B32A32Float,
+            R32G32B32A32Uint,
+            R32G32B32A32Sint,
+        ];
+        static R32G32B32: &[Format] = &[
+            R32G32B32Typeless,
+            R32G32B32Float,
+            R32G32B32Uint,
+            R32G32B32Sint,
+        ];
+        static R16G16B16A16: &[Format] = &[
+            R16G16B16A16Typeless,
+            R16G16B16A16Float,
+            R16G16B16A16Unorm,
+            R16G16B16A16Uint,
+            R16G16B16A16Snorm,
+            R16G16B16A16Sint,
+        ];
+        static R32G32: &[Format] = &[R32G32Typeless, R32G32Float, R32G32Uint, R32G32Sint];
+        static R32G8X24: &[Format] = &[
+            R32G8X24Typeless,
+            D32FloatS8X24Uint,
+            R32FloatX8X24Typeless,
+            X32TypelessG8X24Uint,
+        ];
+        static R10G10B10A2: &[Format] = &[R10G10B10A2Typeless, R10G10B10A2Unorm, R10G10B10A2Uint];
+        static R8G8B8A8: &[Format] = &[
+            R8G8B8A8Typeless,
+            R8G8B8A8Unorm,
+            R8G8B8A8UnormSrgb,
+            R8G8B8A8Uint,
+            R8G8B8A8Snorm,
+            R8G8B8A8Sint,
+        ];
+        static R16G16: &[Format] = &[
+            R16G16Typeless,
+            R16G16Float,
+            R16G16Unorm,
+            R16G16Uint,
+            R16G16Snorm,
+            R16G16Sint,
+        ];
+        static R32: &[Format] = &[R32Typeless, D32Float, R32Float, R32Uint, R32Sint];
+        static R24G8: &[Format] = &[
+            R24G8Typeless,
+            D24UnormS8Uint,
+            R24UnormX8Typeless,
+            X24TypelessG8Uint,
+        ];
+        static R8G8: &[Format] = &[R8G8Typeless, R8G8Unorm, R8G8Uint, R8G8Snorm, R8G8Sint];
+        static R16: &[Format] = &[
+            R16Typeless,
+            R16Float,
+            D16Unorm,
+            R16Unorm,
+            R16Uint,
+            R16Snorm,
+            R16Sint,
+        ];
+        static R8: &[Format] = &[R8Typeless, R8Unorm, R8Uint, R8Snorm, R8Sint];
+        static BC1: &[Format] = &[Bc1Typeless, Bc1Unorm, Bc1UnormSrgb];
+        static BC2: &[Format] = &[Bc2Typeless, Bc2Unorm, Bc2UnormSrgb];
+        static BC3: &[Format] = &[Bc3Typeless, Bc3Unorm, Bc3UnormSrgb];
+        static BC4: &[Format] = &[Bc4Typeless, Bc4Unorm, Bc4Snorm];
+        static BC5: &[Format] = &[Bc5Typeless, Bc5Unorm, Bc5Snorm];
+        static B8G8R8A8: &[Format] = &[B8G8R8A8Typeless, B8G8R8A8Unorm, B8G8R8A8UnormSrgb];
+        static B8G8R8X8: &[Format] = &[B8G8R8X8Typeless, B8G8R8X8Unorm, B8G8R8X8UnormSrgb];
+        static BC6H: &[Format] = &[Bc6hTypeless, Bc6hUf16, Bc6hSf16];
+        static BC7: &[Format] = &[Bc7Typeless, Bc7Unorm, Bc7UnormSrgb];
+
+        let family = match self {
+            R32G32B32A32Typeless | R32G32B32A32Float | R32G32B32A32Uint | R32G32B32A32Sint => {
+                R32G32B32A32
+            }
+            R32G32B32Typeless | R32G32B32Float | R32G32B32Uint | R32G32B32Sint => R32G32B32,
+            R16G16B16A16Typeless | R16G16B16A16Float | R16G16B16A16Unorm | R16G16B16A16Uint
+            | R16G16B16A16Snorm | R16G16B16A16Sint => R16G16B16A16,
+            R32G32Typeless | R32G32Float | R32G32Uint | R32G32Sint => R32G32,
+            R32G8X24Typeless | D32FloatS8X24Uint | R32FloatX8X24Typeless | X32TypelessG8X24Uint => {
+                R32G8X24
+            }
+            R10G10B10A2Typeless | R10G10B10A2Unorm | R10G10B10A2Uint => R10G10B10A2,
+            R8G8B8A8Typeless | R8G8B8A8Unorm | R8G8B8A8UnormSrgb | R8G8B8A8Uint | R8G8B8A8Snorm
+            | R8G8B8A8Sint => R8G8B8A8,
+            R16G16Typeless | R16G16Float | R16G16Unorm | R16G16Uint | R16G16Snorm | R16G16Sint => {
+                R16G16
+            }
+            R32Typeless | D32Float | R32Float | R32Uint | R32Sint => R32,
+            R24G8Typeless | D24UnormS8Uint | R24UnormX8Typeless | X24TypelessG8Uint => R24G8,
+            R8G8Typeless | R8G8Unorm | R8G8Uint | R8G8Snorm | R8G8Sint => R8G8,
+            R16Typeless | R16Float | D16Unorm | R16Unorm | R16Uint | R16Snorm | R16Sint => R16,
+            R8Typeless | R8Unorm | R8Uint | R8Snorm | R8Sint => R8,
+            Bc1Typeless | Bc1Unorm | Bc1UnormSrgb => BC1,
+            Bc2Typeless | Bc2Unorm | Bc2UnormSrgb => BC2,
+            Bc3Typeless | Bc3Unorm | Bc3UnormSrgb => BC3,
+            Bc4Typeless | Bc4Unorm | Bc4Snorm => BC4,
+            Bc5Typeless | Bc5Unorm | Bc5Snorm => BC5,
+            B8G8R8A8Typeless | B8G8R8A8Unorm | B8G8R8A8UnormSrgb => B8G8R8A8,
+            B8G8R8X8Typeless | B8G8R8X8Unorm | B8G8R8X8UnormSrgb => B8G8R8X8,
+            Bc6hTypeless | Bc6hUf16 | Bc6hSf16 => BC6H,
+            Bc7Typeless | Bc7Unorm | Bc7UnormSrgb => BC7,
+            _ => return None,
+        };
+
+        Some(family)
+    }
+}
+
+#[derive(Clone)]
+/// Iterator over the members of a format's cast family.
+///
+/// See [`Format::cast_family`][1]
+///
+/// [1]: enum.Format.html#method.cast_family
+pub struct CastFamily {
+    single: Option<Format>,
+    members: slice::Iter<'static, Format>,
+}
+
+impl Iterator for CastFamily {
+    type Item = Format;
+
+    #[inline]
+    fn next(&mut self) -> Option<Format> {
+        self.single.take().or_else(|| self.members.next().cloned())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for CastFamily {
+    #[inline]
+    fn len(&self) -> usize {
+        self.single.is_some() as usize + self.members.len()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn family_members_agree() {
+    for format in (0..=132).filter_map(Format::from_u32) {
+        let bits = format.info().bits_per_element;
+        for member in format.cast_family() {
+            assert_eq!(member.typeless(), format.typeless(), "{:?}", member);
+            assert_eq!(member.info().bits_per_element, bits, "{:?}", member);
+            assert!(format.is_cast_compatible(member));
+        }
+
+        if let Some(typeless) = format.typeless() {
+            assert!(typeless.is_typeless(), "{:?}", format);
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn srgb_conversions() {
+    assert_eq!(
+        Format::R8G8B8A8Typeless.to_srgb(),
+        Some(Format::R8G8B8A8UnormSrgb)
+    );
+    assert_eq!(
+        Format::R8G8B8A8Unorm.to_srgb(),
+        Some(Format::R8G8B8A8UnormSrgb)
+    );
+    assert_eq!(
+        Format::B8G8R8A8Unorm.to_srgb(),
+        Some(Format::B8G8R8A8UnormSrgb)
+    );
+    assert_eq!(Format::Bc7Unorm.to_srgb(), Some(Format::Bc7UnormSrgb));
+    assert_eq!(Format::R8G8B8A8Uint.to_srgb(), None);
+    assert_eq!(Format::R16G16B16A16Float.to_srgb(), None);
+    assert_eq!(Format::Bc4Unorm.to_srgb(), None);
+
+    assert_eq!(Format::B8G8R8A8UnormSrgb.to_linear(), Format::B8G8R8A8Unorm);
+    assert_eq!(Format::Bc1UnormSrgb.to_linear(), Format::Bc1Unorm);
+    assert_eq!(Format::R8G8B8A8Uint.to_linear(), Format::R8G8B8A8Uint);
+    assert_eq!(
+        Format::R10G10B10A2Unorm.to_linear(),
+        Format::R10G10B10A2Unorm
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn depth_stencil_views() {
+    assert_eq!(
+        Format::R24G8Typeless.depth_view_format(),
+        Some(Format::D24UnormS8Uint)
+    );
+    assert_eq!(
+        Format::R24G8Typeless.depth_srv_format(),
+        Some(Format::R24UnormX8Typeless)
+    );
+    assert_eq!(
+        Format::D24UnormS8Uint.stencil_view_format(),
+        Some(Format::X24TypelessG8Uint)
+    );
+    assert_eq!(
+        Format::R32G8X24Typeless.stencil_view_format(),
+        Some(Format::X32TypelessG8X24Uint)
+    );
+    assert_eq!(Format::R32Float.depth_view_format(), Some(Format::D32Float));
+    assert_eq!(Format::D32Float.stencil_view_format(), None);
+    assert_eq!(Format::R8G8B8A8Unorm.depth_view_format(), None);
+}
+
+#[cfg(test)]
+#[test]
+fn cast_family_iter() {
+    let family: Vec<_> = Format::Bc1UnormSrgb.cast_family().collect();
+    assert_eq!(
+        family,
+        [Format::Bc1Typeless, Format::Bc1Unorm, Format::Bc1UnormSrgb]
+    );
+
+    let family: Vec<_> = Format::Nv12.cast_family().collect();
+    assert_eq!(family, [Format::Nv12]);
+    assert_eq!(Format::R16Uint.cast_family().len(), 7);
+
+    assert!(!Format::R8G8B8A8Unorm.is_cast_compatible(Format::B8G8R8A8Unorm));
+    assert!(Format::Nv12.is_cast_compatible(Format::Nv12));
+}
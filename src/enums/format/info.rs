@@ -0,0 +1,553 @@
+use crate::enums::Format;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+/// Describes the memory layout of a [`Format`][1] in terms of elements,
+/// blocks and channels.
+///
+/// An *element* is the smallest addressable unit of a format. For most
+/// formats this is a single pixel, for block-compressed formats it is a
+/// 4x4 block of pixels, and for packed sub-sampled formats (such as
+/// [`Yuy2`][2]) it is a 2x1 pair of pixels sharing chroma samples. Planar
+/// formats report the average number of bits per pixel across all of
+/// their planes.
+///
+/// [1]: enum.Format.html
+/// [2]: enum.Format.html#variant.Yuy2
+pub struct FormatInfo {
+    /// Number of bits occupied by one element (see above).
+    pub bits_per_element: u32,
+    /// Width in pixels of one element.
+    pub block_width: u32,
+    /// Height in pixels of one element.
+    pub block_height: u32,
+    /// The predominant numeric interpretation of the format. Individual
+    /// channels may differ, e.g. the stencil channel of
+    /// `D24UnormS8Uint` is `Uint`.
+    pub numeric: NumericType,
+    /// The channels of one element, in memory order (least significant bit
+    /// first). Sub-sampled formats list a component once per sample, e.g.
+    /// `Yuy2` is `Y, U, Y, V`. Block-compressed formats list their
+    /// components with a bit width of 0, since the channels are not stored
+    /// as discrete bit fields.
+    pub channels: &'static [FormatChannel],
+    /// The format contains a depth component.
+    pub depth: bool,
+    /// The format contains a stencil component.
+    pub stencil: bool,
+    /// The format stores its components in more than one plane.
+    pub planar: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+/// A single channel (bit field) inside of a format element.
+pub struct FormatChannel {
+    /// Which component this channel holds.
+    pub component: FormatComponent,
+    /// How the bits of this channel are interpreted.
+    pub numeric: NumericType,
+    /// Width of the channel in bits.
+    pub bits: u8,
+    /// Offset of the channel in bits from the start of the element, or
+    /// from the start of the plane's element for planar formats.
+    pub offset: u8,
+    /// The plane this channel is stored in. Always 0 for non-planar
+    /// formats.
+    pub plane: u8,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The component stored in a [`FormatChannel`][1].
+///
+/// [1]: struct.FormatChannel.html
+pub enum FormatComponent {
+    R,
+    G,
+    B,
+    A,
+    /// Depth
+    D,
+    /// Stencil
+    S,
+    /// Unused bits
+    X,
+    /// Shared exponent
+    E,
+    /// Luma
+    Y,
+    /// Blue-difference chroma (Cb)
+    U,
+    /// Red-difference chroma (Cr)
+    V,
+    /// Palette index
+    P,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The format modifier which describes how the bits of a channel are
+/// interpreted. See the table at the bottom of the [`Format`][1]
+/// documentation for more details.
+///
+/// [1]: enum.Format.html
+pub enum NumericType {
+    Unorm,
+    Snorm,
+    Uint,
+    Sint,
+    Float,
+    Srgb,
+    Typeless,
+    SharedExp,
+}
+
+impl FormatInfo {
+    /// The number of distinct components stored by the format, not counting
+    /// unused (`X`) bits. Repeated samples such as the two `Y` values in
+    /// `Yuy2` are only counted once.
+    pub fn component_count(&self) -> u32 {
+        let mut seen = 0u32;
+        for channel in self.channels {
+            if channel.component != FormatComponent::X {
+                seen |= 1 << channel.component as u32;
+            }
+        }
+        seen.count_ones()
+    }
+
+    /// Find the first channel holding the given component.
+    pub fn channel(&self, component: FormatComponent) -> Option<&'static FormatChannel> {
+        self.channels.iter().find(|c| c.component == component)
+    }
+
+    /// Whether the format is one of the BC1-BC7 block-compressed formats.
+    pub fn is_block_compressed(&self) -> bool {
+        self.block_width == 4 && self.block_height == 4
+    }
+
+    /// Whether the format packs pairs of horizontally adjacent pixels into a
+    /// single element which shares some of its samples (e.g. `Yuy2`,
+    /// `R8G8_B8G8Unorm`).
+    pub fn is_packed(&self) -> bool {
+        self.block_width == 2 && self.block_height == 1
+    }
+
+    /// Number of bytes occupied by one element. Returns 0 for planar formats
+    /// and for formats with less than one byte per element (`R1Unorm`).
+    pub fn bytes_per_element(&self) -> u32 {
+        if self.planar || self.bits_per_element & 7 != 0 {
+            0
+        } else {
+            self.bits_per_element / 8
+        }
+    }
+}
+
+macro_rules! info {
+    (
+        $bits:literal, block: $bw:literal, $bh:literal, $numeric:ident,
+        [$($comp:ident : $ty:ident $cbits:literal @ $off:literal $(in $plane:literal)?),*]
+        $(, $flag:ident)*
+    ) => {
+        FormatInfo {
+            bits_per_element: $bits,
+            block_width: $bw,
+            block_height: $bh,
+            numeric: NumericType::$numeric,
+            channels: &[$(FormatChannel {
+                component: FormatComponent::$comp,
+                numeric: NumericType::$ty,
+                bits: $cbits,
+                offset: $off,
+                plane: 0 $(+ $plane)?,
+            }),*],
+            depth: has_flag!(depth; $($flag)*),
+            stencil: has_flag!(stencil; $($flag)*),
+            planar: has_flag!(planar; $($flag)*),
+        }
+    };
+    ($bits:literal, $numeric:ident, [$($chan:tt)*] $(, $flag:ident)*) => {
+        info!($bits, block: 1, 1, $numeric, [$($chan)*] $(, $flag)*)
+    };
+}
+
+macro_rules! has_flag {
+    ($want:ident;) => { false };
+    (depth; depth $($rest:ident)*) => { true };
+    (stencil; stencil $($rest:ident)*) => { true };
+    (planar; planar $($rest:ident)*) => { true };
+    ($want:ident; $other:ident $($rest:ident)*) => { has_flag!($want; $($rest)*) };
+}
+
+impl Format {
+    /// Get the full layout description of this format. Every format,
+    /// including block-compressed, video and palettized formats, has a
+    /// description. `Unknown` describes an empty 0-bit element.
+    pub fn info(self) -> FormatInfo {
+        use self::Format::*;
+        match self {
+            Unknown => info!(0, Typeless, []),
+
+            R32G32B32A32Typeless => info!(128, Typeless, [
+                R: Typeless 32 @ 0, G: Typeless 32 @ 32, B: Typeless 32 @ 64, A: Typeless 32 @ 96
+            ]),
+            R32G32B32A32Float => info!(128, Float, [
+                R: Float 32 @ 0, G: Float 32 @ 32, B: Float 32 @ 64, A: Float 32 @ 96
+            ]),
+            R32G32B32A32Uint => info!(128, Uint, [
+                R: Uint 32 @ 0, G: Uint 32 @ 32, B: Uint 32 @ 64, A: Uint 32 @ 96
+            ]),
+            R32G32B32A32Sint => info!(128, Sint, [
+                R: Sint 32 @ 0, G: Sint 32 @ 32, B: Sint 32 @ 64, A: Sint 32 @ 96
+            ]),
+
+            R32G32B32Typeless => info!(96, Typeless, [
+                R: Typeless 32 @ 0, G: Typeless 32 @ 32, B: Typeless 32 @ 64
+            ]),
+            R32G32B32Float => {
+                info!(96, Float, [R: Float 32 @ 0, G: Float 32 @ 32, B: Float 32 @ 64])
+            }
+            R32G32B32Uint => info!(96, Uint, [R: Uint 32 @ 0, G: Uint 32 @ 32, B: Uint 32 @ 64]),
+            R32G32B32Sint => info!(96, Sint, [R: Sint 32 @ 0, G: Sint 32 @ 32, B: Sint 32 @ 64]),
+
+            R16G16B16A16Typeless => info!(64, Typeless, [
+                R: Typeless 16 @ 0, G: Typeless 16 @ 16, B: Typeless 16 @ 32, A: Typeless 16 @ 48
+            ]),
+            R16G16B16A16Float => info!(64, Float, [
+                R: Float 16 @ 0, G: Float 16 @ 16, B: Float 16 @ 32, A: Float 16 @ 48
+            ]),
+            R16G16B16A16Unorm => info!(64, Unorm, [
+                R: Unorm 16 @ 0, G: Unorm 16 @ 16, B: Unorm 16 @ 32, A: Unorm 16 @ 48
+            ]),
+            R16G16B16A16Uint => info!(64, Uint, [
+                R: Uint 16 @ 0, G: Uint 16 @ 16, B: Uint 16 @ 32, A: Uint 16 @ 48
+            ]),
+            R16G16B16A16Snorm => info!(64, Snorm, [
+                R: Snorm 16 @ 0, G: Snorm 16 @ 16, B: Snorm 16 @ 32, A: Snorm 16 @ 48
+            ]),
+            R16G16B16A16Sint => info!(64, Sint, [
+                R: Sint 16 @ 0, G: Sint 16 @ 16, B: Sint 16 @ 32, A: Sint 16 @ 48
+            ]),
+
+            R32G32Typeless => info!(64, Typeless, [R: Typeless 32 @ 0, G: Typeless 32 @ 32]),
+            R32G32Float => info!(64, Float, [R: Float 32 @ 0, G: Float 32 @ 32]),
+            R32G32Uint => info!(64, Uint, [R: Uint 32 @ 0, G: Uint 32 @ 32]),
+            R32G32Sint => info!(64, Sint, [R: Sint 32 @ 0, G: Sint 32 @ 32]),
+
+            R32G8X24Typeless => info!(64, Typeless, [
+                R: Typeless 32 @ 0, G: Typeless 8 @ 32, X: Typeless 24 @ 40
+            ]),
+            D32FloatS8X24Uint => info!(64, Float, [
+                D: Float 32 @ 0, S: Uint 8 @ 32, X: Typeless 24 @ 40
+            ], depth, stencil),
+            R32FloatX8X24Typeless => info!(64, Float, [
+                R: Float 32 @ 0, X: Typeless 8 @ 32, X: Typeless 24 @ 40
+            ]),
+            X32TypelessG8X24Uint => info!(64, Uint, [
+                X: Typeless 32 @ 0, G: Uint 8 @ 32, X: Typeless 24 @ 40
+            ]),
+
+            R10G10B10A2Typeless => info!(32, Typeless, [
+                R: Typeless 10 @ 0, G: Typeless 10 @ 10, B: Typeless 10 @ 20, A: Typeless 2 @ 30
+            ]),
+            R10G10B10A2Unorm => info!(32, Unorm, [
+                R: Unorm 10 @ 0, G: Unorm 10 @ 10, B: Unorm 10 @ 20, A: Unorm 2 @ 30
+            ]),
+            R10G10B10A2Uint => info!(32, Uint, [
+                R: Uint 10 @ 0, G: Uint 10 @ 10, B: Uint 10 @ 20, A: Uint 2 @ 30
+            ]),
+            R11G11B10Float => {
+                info!(32, Float, [R: Float 11 @ 0, G: Float 11 @ 11, B: Float 10 @ 22])
+            }
+
+            R8G8B8A8Typeless => info!(32, Typeless, [
+                R: Typeless 8 @ 0, G: Typeless 8 @ 8, B: Typeless 8 @ 16, A: Typeless 8 @ 24
+            ]),
+            R8G8B8A8Unorm => info!(32, Unorm, [
+                R: Unorm 8 @ 0, G: Unorm 8 @ 8, B: Unorm 8 @ 16, A: Unorm 8 @ 24
+            ]),
+            R8G8B8A8UnormSrgb => info!(32, Srgb, [
+                R: Srgb 8 @ 0, G: Srgb 8 @ 8, B: Srgb 8 @ 16, A: Unorm 8 @ 24
+            ]),
+            R8G8B8A8Uint => info!(32, Uint, [
+                R: Uint 8 @ 0, G: Uint 8 @ 8, B: Uint 8 @ 16, A: Uint 8 @ 24
+            ]),
+            R8G8B8A8Snorm => info!(32, Snorm, [
+                R: Snorm 8 @ 0, G: Snorm 8 @ 8, B: Snorm 8 @ 16, A: Snorm 8 @ 24
+            ]),
+            R8G8B8A8Sint => info!(32, Sint, [
+                R: Sint 8 @ 0, G: Sint 8 @ 8, B: Sint 8 @ 16, A: Sint 8 @ 24
+            ]),
+
+            R16G16Typeless => info!(32, Typeless, [R: Typeless 16 @ 0, G: Typeless 16 @ 16]),
+            R16G16Float => info!(32, Float, [R: Float 16 @ 0, G: Float 16 @ 16]),
+            R16G16Unorm => info!(32, Unorm, [R: Unorm 16 @ 0, G: Unorm 16 @ 16]),
+            R16G16Uint => info!(32, Uint, [R: Uint 16 @ 0, G: Uint 16 @ 16]),
+            R16G16Snorm => info!(32, Snorm, [R: Snorm 16 @ 0, G: Snorm 16 @ 16]),
+            R16G16Sint => info!(32, Sint, [R: Sint 16 @ 0, G: Sint 16 @ 16]),
+
+            R32Typeless => info!(32, Typeless, [R: Typeless 32 @ 0]),
+            D32Float => info!(32, Float, [D: Float 32 @ 0], depth),
+            R32Float => info!(32, Float, [R: Float 32 @ 0]),
+            R32Uint => info!(32, Uint, [R: Uint 32 @ 0]),
+            R32Sint => info!(32, Sint, [R: Sint 32 @ 0]),
+
+            R24G8Typeless => info!(32, Typeless, [R: Typeless 24 @ 0, G: Typeless 8 @ 24]),
+            D24UnormS8Uint => info!(32, Unorm, [D: Unorm 24 @ 0, S: Uint 8 @ 24], depth, stencil),
+            R24UnormX8Typeless => info!(32, Unorm, [R: Unorm 24 @ 0, X: Typeless 8 @ 24]),
+            X24TypelessG8Uint => info!(32, Uint, [X: Typeless 24 @ 0, G: Uint 8 @ 24]),
+
+            R8G8Typeless => info!(16, Typeless, [R: Typeless 8 @ 0, G: Typeless 8 @ 8]),
+            R8G8Unorm => info!(16, Unorm, [R: Unorm 8 @ 0, G: Unorm 8 @ 8]),
+            R8G8Uint => info!(16, Uint, [R: Uint 8 @ 0, G: Uint 8 @ 8]),
+            R8G8Snorm => info!(16, Snorm, [R: Snorm 8 @ 0, G: Snorm 8 @ 8]),
+            R8G8Sint => info!(16, Sint, [R: Sint 8 @ 0, G: Sint 8 @ 8]),
+
+            R16Typeless => info!(16, Typeless, [R: Typeless 16 @ 0]),
+            R16Float => info!(16, Float, [R: Float 16 @ 0]),
+            D16Unorm => info!(16, Unorm, [D: Unorm 16 @ 0], depth),
+            R16Unorm => info!(16, Unorm, [R: Unorm 16 @ 0]),
+            R16Uint => info!(16, Uint, [R: Uint 16 @ 0]),
+            R16Snorm => info!(16, Snorm, [R: Snorm 16 @ 0]),
+            R16Sint => info!(16, Sint, [R: Sint 16 @ 0]),
+
+            R8Typeless => info!(8, Typeless, [R: Typeless 8 @ 0]),
+            R8Unorm => info!(8, Unorm, [R: Unorm 8 @ 0]),
+            R8Uint => info!(8, Uint, [R: Uint 8 @ 0]),
+            R8Snorm => info!(8, Snorm, [R: Snorm 8 @ 0]),
+            R8Sint => info!(8, Sint, [R: Sint 8 @ 0]),
+            A8Unorm => info!(8, Unorm, [A: Unorm 8 @ 0]),
+            R1Unorm => info!(1, Unorm, [R: Unorm 1 @ 0]),
+
+            R9G9B9E5SharedExponent => info!(32, SharedExp, [
+                R: SharedExp 9 @ 0, G: SharedExp 9 @ 9, B: SharedExp 9 @ 18, E: SharedExp 5 @ 27
+            ]),
+            R8G8_B8G8Unorm => info!(32, block: 2, 1, Unorm, [
+                R: Unorm 8 @ 0, G: Unorm 8 @ 8, B: Unorm 8 @ 16, G: Unorm 8 @ 24
+            ]),
+            G8R8_G8B8Unorm => info!(32, block: 2, 1, Unorm, [
+                G: Unorm 8 @ 0, R: Unorm 8 @ 8, G: Unorm 8 @ 16, B: Unorm 8 @ 24
+            ]),
+
+            Bc1Typeless => info!(64, block: 4, 4, Typeless, [
+                R: Typeless 0 @ 0, G: Typeless 0 @ 0, B: Typeless 0 @ 0, A: Typeless 0 @ 0
+            ]),
+            Bc1Unorm => info!(64, block: 4, 4, Unorm, [
+                R: Unorm 0 @ 0, G: Unorm 0 @ 0, B: Unorm 0 @ 0, A: Unorm 0 @ 0
+            ]),
+            Bc1UnormSrgb => info!(64, block: 4, 4, Srgb, [
+                R: Srgb 0 @ 0, G: Srgb 0 @ 0, B: Srgb 0 @ 0, A: Unorm 0 @ 0
+            ]),
+            Bc2Typeless => info!(128, block: 4, 4, Typeless, [
+                R: Typeless 0 @ 0, G: Typeless 0 @ 0, B: Typeless 0 @ 0, A: Typeless 0 @ 0
+            ]),
+            Bc2Unorm => info!(128, block: 4, 4, Unorm, [
+                R: Unorm 0 @ 0, G: Unorm 0 @ 0, B: Unorm 0 @ 0, A: Unorm 0 @ 0
+            ]),
+            Bc2UnormSrgb => info!(128, block: 4, 4, Srgb, [
+                R: Srgb 0 @ 0, G: Srgb 0 @ 0, B: Srgb 0 @ 0, A: Unorm 0 @ 0
+            ]),
+            Bc3Typeless => info!(128, block: 4, 4, Typeless, [
+                R: Typeless 0 @ 0, G: Typeless 0 @ 0, B: Typeless 0 @ 0, A: Typeless 0 @ 0
+            ]),
+            Bc3Unorm => info!(128, block: 4, 4, Unorm, [
+                R: Unorm 0 @ 0, G: Unorm 0 @ 0, B: Unorm 0 @ 0, A: Unorm 0 @ 0
+            ]),
+            Bc3UnormSrgb => info!(128, block: 4, 4, Srgb, [
+                R: Srgb 0 @ 0, G: Srgb 0 @ 0, B: Srgb 0 @ 0, A: Unorm 0 @ 0
+            ]),
+            Bc4Typeless => info!(64, block: 4, 4, Typeless, [R: Typeless 0 @ 0]),
+            Bc4Unorm => info!(64, block: 4, 4, Unorm, [R: Unorm 0 @ 0]),
+            Bc4Snorm => info!(64, block: 4, 4, Snorm, [R: Snorm 0 @ 0]),
+            Bc5Typeless => {
+                info!(128, block: 4, 4, Typeless, [R: Typeless 0 @ 0, G: Typeless 0 @ 0])
+            }
+            Bc5Unorm => info!(128, block: 4, 4, Unorm, [R: Unorm 0 @ 0, G: Unorm 0 @ 0]),
+            Bc5Snorm => info!(128, block: 4, 4, Snorm, [R: Snorm 0 @ 0, G: Snorm 0 @ 0]),
+
+            B5G6R5Unorm => info!(16, Unorm, [B: Unorm 5 @ 0, G: Unorm 6 @ 5, R: Unorm 5 @ 11]),
+            B5G5R5A1Unorm => info!(16, Unorm, [
+                B: Unorm 5 @ 0, G: Unorm 5 @ 5, R: Unorm 5 @ 10, A: Unorm 1 @ 15
+            ]),
+            B8G8R8A8Unorm => info!(32, Unorm, [
+                B: Unorm 8 @ 0, G: Unorm 8 @ 8, R: Unorm 8 @ 16, A: Unorm 8 @ 24
+            ]),
+            B8G8R8X8Unorm => info!(32, Unorm, [
+                B: Unorm 8 @ 0, G: Unorm 8 @ 8, R: Unorm 8 @ 16, X: Typeless 8 @ 24
+            ]),
+            // The XR bias encoding is a fixed-point format of its own, it is
+            // reported as Unorm since it shares the same storage.
+            R10G10B10XrBiasA2Unorm => info!(32, Unorm, [
+                R: Unorm 10 @ 0, G: Unorm 10 @ 10, B: Unorm 10 @ 20, A: Unorm 2 @ 30
+            ]),
+            B8G8R8A8Typeless => info!(32, Typeless, [
+                B: Typeless 8 @ 0, G: Typeless 8 @ 8, R: Typeless 8 @ 16, A: Typeless 8 @ 24
+            ]),
+            B8G8R8A8UnormSrgb => info!(32, Srgb, [
+                B: Srgb 8 @ 0, G: Srgb 8 @ 8, R: Srgb 8 @ 16, A: Unorm 8 @ 24
+            ]),
+            B8G8R8X8Typeless => info!(32, Typeless, [
+                B: Typeless 8 @ 0, G: Typeless 8 @ 8, R: Typeless 8 @ 16, X: Typeless 8 @ 24
+            ]),
+            B8G8R8X8UnormSrgb => info!(32, Srgb, [
+                B: Srgb 8 @ 0, G: Srgb 8 @ 8, R: Srgb 8 @ 16, X: Typeless 8 @ 24
+            ]),
+
+            Bc6hTypeless => info!(128, block: 4, 4, Typeless, [
+                R: Typeless 0 @ 0, G: Typeless 0 @ 0, B: Typeless 0 @ 0
+            ]),
+            Bc6hUf16 => {
+                info!(128, block: 4, 4, Float, [R: Float 0 @ 0, G: Float 0 @ 0, B: Float 0 @ 0])
+            }
+            Bc6hSf16 => {
+                info!(128, block: 4, 4, Float, [R: Float 0 @ 0, G: Float 0 @ 0, B: Float 0 @ 0])
+            }
+            Bc7Typeless => info!(128, block: 4, 4, Typeless, [
+                R: Typeless 0 @ 0, G: Typeless 0 @ 0, B: Typeless 0 @ 0, A: Typeless 0 @ 0
+            ]),
+            Bc7Unorm => info!(128, block: 4, 4, Unorm, [
+                R: Unorm 0 @ 0, G: Unorm 0 @ 0, B: Unorm 0 @ 0, A: Unorm 0 @ 0
+            ]),
+            Bc7UnormSrgb => info!(128, block: 4, 4, Srgb, [
+                R: Srgb 0 @ 0, G: Srgb 0 @ 0, B: Srgb 0 @ 0, A: Unorm 0 @ 0
+            ]),
+
+            Ayuv => info!(32, Unorm, [
+                V: Unorm 8 @ 0, U: Unorm 8 @ 8, Y: Unorm 8 @ 16, A: Unorm 8 @ 24
+            ]),
+            Y410 => info!(32, Unorm, [
+                U: Unorm 10 @ 0, Y: Unorm 10 @ 10, V: Unorm 10 @ 20, A: Unorm 2 @ 30
+            ]),
+            Y416 => info!(64, Unorm, [
+                U: Unorm 16 @ 0, Y: Unorm 16 @ 16, V: Unorm 16 @ 32, A: Unorm 16 @ 48
+            ]),
+            Nv12 => info!(12, Unorm, [
+                Y: Unorm 8 @ 0, U: Unorm 8 @ 0 in 1, V: Unorm 8 @ 8 in 1
+            ], planar),
+            // 10 significant bits stored in the high bits of 16-bit words.
+            P010 => info!(24, Unorm, [
+                Y: Unorm 10 @ 6, U: Unorm 10 @ 6 in 1, V: Unorm 10 @ 22 in 1
+            ], planar),
+            P016 => info!(24, Unorm, [
+                Y: Unorm 16 @ 0, U: Unorm 16 @ 0 in 1, V: Unorm 16 @ 16 in 1
+            ], planar),
+            // The real layout is driver defined; it is sized like `Nv12`.
+            Yuv420Opaque => info!(12, Unorm, [
+                Y: Unorm 8 @ 0, U: Unorm 8 @ 0 in 1, V: Unorm 8 @ 8 in 1
+            ], planar),
+            Yuy2 => info!(32, block: 2, 1, Unorm, [
+                Y: Unorm 8 @ 0, U: Unorm 8 @ 8, Y: Unorm 8 @ 16, V: Unorm 8 @ 24
+            ]),
+            Y210 => info!(64, block: 2, 1, Unorm, [
+                Y: Unorm 10 @ 6, U: Unorm 10 @ 22, Y: Unorm 10 @ 38, V: Unorm 10 @ 54
+            ]),
+            Y216 => info!(64, block: 2, 1, Unorm, [
+                Y: Unorm 16 @ 0, U: Unorm 16 @ 16, Y: Unorm 16 @ 32, V: Unorm 16 @ 48
+            ]),
+            Nv11 => info!(12, Unorm, [
+                Y: Unorm 8 @ 0, U: Unorm 8 @ 0 in 1, V: Unorm 8 @ 8 in 1
+            ], planar),
+            Ai44 => info!(8, Uint, [P: Uint 4 @ 0, A: Unorm 4 @ 4]),
+            Ia44 => info!(8, Uint, [A: Unorm 4 @ 0, P: Uint 4 @ 4]),
+            P8 => info!(8, Uint, [P: Uint 8 @ 0]),
+            A8P8 => info!(16, Uint, [P: Uint 8 @ 0, A: Unorm 8 @ 8]),
+            B4G4R4A4Unorm => info!(16, Unorm, [
+                B: Unorm 4 @ 0, G: Unorm 4 @ 4, R: Unorm 4 @ 8, A: Unorm 4 @ 12
+            ]),
+            P208 => info!(16, Unorm, [
+                Y: Unorm 8 @ 0, U: Unorm 8 @ 0 in 1, V: Unorm 8 @ 8 in 1
+            ], planar),
+            V208 => info!(16, Unorm, [
+                Y: Unorm 8 @ 0, U: Unorm 8 @ 0 in 1, V: Unorm 8 @ 0 in 2
+            ], planar),
+            V408 => info!(24, Unorm, [
+                Y: Unorm 8 @ 0, U: Unorm 8 @ 0 in 1, V: Unorm 8 @ 0 in 2
+            ], planar),
+        }
+    }
+}
+
+#[cfg(test)]
+fn all_formats() -> impl Iterator<Item = Format> {
+    (0..=132).filter_map(Format::from_u32)
+}
+
+#[cfg(test)]
+#[test]
+fn info_matches_pixel_size() {
+    for format in all_formats() {
+        let info = format.info();
+        if format.pixel_size() != 0 {
+            assert_eq!(
+                info.bits_per_element,
+                format.pixel_size() as u32 * 8,
+                "{:?}",
+                format
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn info_channels_fit_element() {
+    for format in all_formats() {
+        let info = format.info();
+        if info.planar || info.is_block_compressed() {
+            continue;
+        }
+
+        let mut used = 0u128;
+        for channel in info.channels {
+            let end = channel.offset as u32 + channel.bits as u32;
+            assert!(end <= info.bits_per_element, "{:?}", format);
+
+            let mask = ((1u128 << channel.bits) - 1) << channel.offset;
+            assert_eq!(used & mask, 0, "{:?} has overlapping channels", format);
+            used |= mask;
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn info_flags() {
+    assert!(Format::D24UnormS8Uint.info().depth);
+    assert!(Format::D24UnormS8Uint.info().stencil);
+    assert!(Format::D32Float.info().depth);
+    assert!(!Format::D32Float.info().stencil);
+    assert!(!Format::R24G8Typeless.info().depth);
+    assert!(Format::Nv12.info().planar);
+    assert!(Format::V408.info().planar);
+    assert!(!Format::Yuy2.info().planar);
+    assert!(Format::Bc7Unorm.info().is_block_compressed());
+    assert!(Format::Yuy2.info().is_packed());
+    assert!(Format::G8R8_G8B8Unorm.info().is_packed());
+}
+
+#[cfg(test)]
+#[test]
+fn info_components() {
+    let info = Format::B5G6R5Unorm.info();
+    assert_eq!(info.component_count(), 3);
+    let r = info.channel(FormatComponent::R).unwrap();
+    assert_eq!((r.bits, r.offset), (5, 11));
+
+    assert_eq!(Format::B8G8R8X8Unorm.info().component_count(), 3);
+    assert_eq!(Format::Yuy2.info().component_count(), 3);
+    assert_eq!(Format::Bc1Unorm.info().component_count(), 4);
+    assert_eq!(Format::Bc5Snorm.info().component_count(), 2);
+    assert_eq!(Format::Unknown.info().component_count(), 0);
+
+    let info = Format::Bc3UnormSrgb.info();
+    assert_eq!((info.bits_per_element, info.block_width), (128, 4));
+    assert_eq!(info.numeric, NumericType::Srgb);
+    assert_eq!(
+        info.channel(FormatComponent::A).unwrap().numeric,
+        NumericType::Unorm
+    );
+
+    let info = Format::P010.info();
+    let v = info.channel(FormatComponent::V).unwrap();
+    assert_eq!((v.bits, v.offset, v.plane), (10, 22, 1));
+}
@@ -0,0 +1,469 @@
+use crate::enums::Format;
+
+use std::cmp::max;
+
+/// The most subresources a Direct3D resource can have
+/// (`D3D11_REQ_SUBRESOURCES`).
+const MAX_SUBRESOURCES: usize = 30720;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// The memory layout of a single (mip level of a) surface, following the
+/// same rules Direct3D uses for tightly packed subresources.
+///
+/// All planes of a planar format share the same `row_pitch`, and the
+/// planes are stored one after the other (luma first).
+pub struct SurfaceLayout {
+    /// Number of bytes in one row of elements. For block-compressed formats
+    /// a row is a row of 4x4 blocks.
+    pub row_pitch: usize,
+    /// Number of bytes in one depth slice, including every plane.
+    pub slice_pitch: usize,
+    /// Number of rows of elements in one depth slice, including the rows of
+    /// every plane.
+    pub num_rows: u32,
+    /// The width rounded up to a multiple of the format's block or
+    /// sub-sampling alignment.
+    pub padded_width: u32,
+    /// The height rounded up to a multiple of the format's block or
+    /// sub-sampling alignment.
+    pub padded_height: u32,
+    /// The number of depth slices.
+    pub depth: u32,
+    plane_count: u32,
+    planes: [PlaneLayout; 3],
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// The location of one plane inside a depth slice of a [`SurfaceLayout`][1].
+///
+/// [1]: struct.SurfaceLayout.html
+pub struct PlaneLayout {
+    /// Offset of the plane in bytes from the start of the slice.
+    pub offset: usize,
+    /// Number of bytes between consecutive rows of the plane.
+    pub row_pitch: usize,
+    /// Number of rows in the plane.
+    pub num_rows: u32,
+    /// Number of bytes in the plane.
+    pub size: usize,
+    /// Horizontal sub-sampling factor of the plane relative to the full
+    /// resolution (e.g. 2 for the chroma plane of `Nv12`).
+    pub subsample_x: u32,
+    /// Vertical sub-sampling factor of the plane relative to the full
+    /// resolution.
+    pub subsample_y: u32,
+}
+
+impl SurfaceLayout {
+    /// The planes making up each depth slice. Non-planar formats have a
+    /// single plane covering the whole slice.
+    pub fn planes(&self) -> &[PlaneLayout] {
+        &self.planes[..self.plane_count as usize]
+    }
+
+    /// Total number of bytes occupied by every depth slice.
+    pub fn total_size(&self) -> usize {
+        self.slice_pitch * self.depth as usize
+    }
+}
+
+impl Format {
+    /// Compute the tightly packed layout of a surface in this format with
+    /// the given dimensions in pixels.
+    ///
+    /// Block-compressed formats always occupy at least one block in each
+    /// dimension. Packed 4:2:2 formats (`Yuy2`, `R8G8_B8G8Unorm`...) and
+    /// planar formats are padded to their sub-sampling alignment, except
+    /// that the luma plane of a planar format has exactly `height` rows and
+    /// each chroma plane has `height` divided by its sub-sampling, rounded
+    /// up. An odd height `Nv12` surface has `h + (h + 1) / 2` rows.
+    ///
+    /// Panics if the padded dimensions don't fit in a `u32` or the total size
+    /// doesn't fit in a `usize`; see `checked_surface_layout`.
+    pub fn surface_layout(self, width: u32, height: u32, depth: u32) -> SurfaceLayout {
+        self.checked_surface_layout(width, height, depth)
+            .expect("surface size overflowed")
+    }
+
+    /// Like `surface_layout`, but returns `None` instead of panicking when
+    /// the dimensions are too large to lay out.
+    pub fn checked_surface_layout(
+        self,
+        width: u32,
+        height: u32,
+        depth: u32,
+    ) -> Option<SurfaceLayout> {
+        use self::Format::*;
+
+        let info = self.info();
+        let mut layout = SurfaceLayout {
+            row_pitch: 0,
+            slice_pitch: 0,
+            num_rows: height,
+            padded_width: width,
+            padded_height: height,
+            depth,
+            plane_count: 1,
+            planes: Default::default(),
+        };
+
+        // (bytes per sample, width alignment, height alignment,
+        //  [(sub_x, sub_y); chroma planes])
+        let planar = match self {
+            Nv12 | Yuv420Opaque => Some((1, 2, 2, [(2, 2), (0, 0)])),
+            P010 | P016 => Some((2, 2, 2, [(2, 2), (0, 0)])),
+            Nv11 => Some((1, 4, 1, [(4, 1), (0, 0)])),
+            P208 => Some((1, 2, 1, [(2, 1), (0, 0)])),
+            V208 => Some((1, 1, 2, [(1, 2), (1, 2)])),
+            V408 => Some((1, 1, 1, [(1, 1), (1, 1)])),
+            _ => None,
+        };
+
+        if let Some((bytes, align_x, align_y, chroma)) = planar {
+            layout.padded_width = align(width, align_x)?;
+            layout.padded_height = align(height, align_y)?;
+            layout.row_pitch = (layout.padded_width as usize).checked_mul(bytes)?;
+
+            let luma = (1, 1);
+            let mut offset = 0;
+            layout.plane_count = 0;
+            for &(sub_x, sub_y) in Some(&luma).into_iter().chain(&chroma) {
+                if sub_x == 0 {
+                    continue;
+                }
+
+                let num_rows = height.div_ceil(sub_y);
+                let size = layout.row_pitch.checked_mul(num_rows as usize)?;
+                layout.planes[layout.plane_count as usize] = PlaneLayout {
+                    offset,
+                    row_pitch: layout.row_pitch,
+                    num_rows,
+                    size,
+                    subsample_x: sub_x,
+                    subsample_y: sub_y,
+                };
+                layout.plane_count += 1;
+                offset = offset.checked_add(size)?;
+            }
+
+            layout.num_rows = layout
+                .planes()
+                .iter()
+                .try_fold(0u32, |rows, p| rows.checked_add(p.num_rows))?;
+            layout.slice_pitch = offset;
+            offset.checked_mul(depth as usize)?;
+            return Some(layout);
+        }
+
+        let bytes = (info.bits_per_element / 8) as usize;
+        if info.is_block_compressed() {
+            let blocks_wide = max(1, width.div_ceil(4));
+            let blocks_high = max(1, height.div_ceil(4));
+            layout.padded_width = blocks_wide.checked_mul(4)?;
+            layout.padded_height = blocks_high.checked_mul(4)?;
+            layout.num_rows = blocks_high;
+            layout.row_pitch = (blocks_wide as usize).checked_mul(bytes)?;
+        } else if info.is_packed() {
+            layout.padded_width = align(width, 2)?;
+            layout.row_pitch = ((layout.padded_width / 2) as usize).checked_mul(bytes)?;
+        } else {
+            let bits = (width as usize).checked_mul(info.bits_per_element as usize)?;
+            layout.row_pitch = bits.div_ceil(8);
+        }
+
+        layout.slice_pitch = layout.row_pitch.checked_mul(layout.num_rows as usize)?;
+        layout.slice_pitch.checked_mul(depth as usize)?;
+        layout.planes[0] = PlaneLayout {
+            offset: 0,
+            row_pitch: layout.row_pitch,
+            num_rows: layout.num_rows,
+            size: layout.slice_pitch,
+            subsample_x: 1,
+            subsample_y: 1,
+        };
+        Some(layout)
+    }
+
+    /// Compute the layout of a full set of subresources. See
+    /// [`MipChainLayout::new`][1].
+    ///
+    /// [1]: struct.MipChainLayout.html#method.new
+    pub fn mip_chain_layout(
+        self,
+        width: u32,
+        height: u32,
+        depth: u32,
+        mip_levels: u32,
+        array_size: u32,
+    ) -> Option<MipChainLayout> {
+        MipChainLayout::new(self, width, height, depth, mip_levels, array_size)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// The layout of every subresource (mip level and array slice) of a
+/// texture, packed one after the other with no padding in between. The
+/// subresources are ordered by array slice, then by mip level, which
+/// matches Direct3D subresource indices and the layout of DDS files.
+pub struct MipChainLayout {
+    /// The number of mip levels in each array slice.
+    pub mip_levels: u32,
+    /// The number of array slices.
+    pub array_size: u32,
+    /// The total number of bytes needed to store every subresource.
+    pub total_size: usize,
+    /// The subresources, indexed by `mip_level + array_slice * mip_levels`.
+    pub subresources: Vec<SubresourceLayout>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// The location and layout of one subresource in a [`MipChainLayout`][1].
+///
+/// [1]: struct.MipChainLayout.html
+pub struct SubresourceLayout {
+    pub mip_level: u32,
+    pub array_slice: u32,
+    /// Offset of the subresource in bytes from the start of the data.
+    pub offset: usize,
+    /// Width of this mip level in pixels.
+    pub width: u32,
+    /// Height of this mip level in pixels.
+    pub height: u32,
+    /// Depth of this mip level in pixels.
+    pub depth: u32,
+    pub layout: SurfaceLayout,
+}
+
+impl MipChainLayout {
+    /// Compute the layout of a texture. A `mip_levels` of 0 generates a full
+    /// mip chain down to 1x1x1, the same as it does when creating a texture.
+    /// An `array_size` of 0 is treated as 1.
+    ///
+    /// Returns `None` if there are more subresources than Direct3D allows
+    /// (30720) or the total size doesn't fit in a `usize`.
+    pub fn new(
+        format: Format,
+        width: u32,
+        height: u32,
+        depth: u32,
+        mip_levels: u32,
+        array_size: u32,
+    ) -> Option<MipChainLayout> {
+        let mip_levels = match mip_levels {
+            0 => MipChainLayout::max_levels(width, height, depth),
+            n => n,
+        };
+        let array_size = max(array_size, 1);
+        let count = (mip_levels as usize).checked_mul(array_size as usize)?;
+        if count > MAX_SUBRESOURCES {
+            return None;
+        }
+
+        // Lay out one array slice first, so that the total size is known to
+        // fit before allocating a subresource for every slice.
+        let mut levels = Vec::new();
+        let mut slice_size = 0usize;
+        for mip_level in 0..mip_levels {
+            let width = max(width.checked_shr(mip_level).unwrap_or(0), 1);
+            let height = max(height.checked_shr(mip_level).unwrap_or(0), 1);
+            let depth = max(depth.checked_shr(mip_level).unwrap_or(0), 1);
+            let layout = format.checked_surface_layout(width, height, depth)?;
+            levels.push((slice_size, width, height, depth, layout));
+            slice_size = slice_size.checked_add(layout.total_size())?;
+        }
+        let total_size = slice_size.checked_mul(array_size as usize)?;
+
+        let mut subresources = Vec::with_capacity(count);
+        for array_slice in 0..array_size {
+            let base = slice_size * array_slice as usize;
+            for (mip_level, &(offset, width, height, depth, layout)) in levels.iter().enumerate() {
+                subresources.push(SubresourceLayout {
+                    mip_level: mip_level as u32,
+                    array_slice,
+                    offset: base + offset,
+                    width,
+                    height,
+                    depth,
+                    layout,
+                });
+            }
+        }
+
+        Some(MipChainLayout {
+            mip_levels,
+            array_size,
+            total_size,
+            subresources,
+        })
+    }
+
+    /// The number of levels in a full mip chain for the given dimensions.
+    pub fn max_levels(width: u32, height: u32, depth: u32) -> u32 {
+        let largest = max(max(width, height), max(depth, 1));
+        32 - largest.leading_zeros()
+    }
+
+    /// Get the layout of a specific subresource.
+    pub fn subresource(&self, mip_level: u32, array_slice: u32) -> Option<&SubresourceLayout> {
+        if mip_level >= self.mip_levels || array_slice >= self.array_size {
+            return None;
+        }
+
+        self.subresources
+            .get((mip_level + array_slice * self.mip_levels) as usize)
+    }
+}
+
+#[inline]
+fn align(value: u32, alignment: u32) -> Option<u32> {
+    value.div_ceil(alignment).checked_mul(alignment)
+}
+
+#[cfg(test)]
+#[test]
+fn uncompressed_layout() {
+    let layout = Format::R8G8B8A8Unorm.surface_layout(13, 7, 1);
+    assert_eq!(layout.row_pitch, 52);
+    assert_eq!(layout.slice_pitch, 52 * 7);
+    assert_eq!(layout.num_rows, 7);
+    assert_eq!((layout.padded_width, layout.padded_height), (13, 7));
+
+    let layout = Format::R1Unorm.surface_layout(9, 2, 1);
+    assert_eq!(layout.row_pitch, 2);
+
+    let layout = Format::R32G32B32Float.surface_layout(3, 3, 4);
+    assert_eq!(layout.row_pitch, 36);
+    assert_eq!(layout.total_size(), 36 * 3 * 4);
+    assert_eq!(layout.planes().len(), 1);
+}
+
+#[cfg(test)]
+#[test]
+fn block_compressed_layout() {
+    let layout = Format::Bc1Unorm.surface_layout(10, 6, 1);
+    assert_eq!(layout.row_pitch, 3 * 8);
+    assert_eq!(layout.num_rows, 2);
+    assert_eq!(layout.slice_pitch, 3 * 8 * 2);
+    assert_eq!((layout.padded_width, layout.padded_height), (12, 8));
+
+    let layout = Format::Bc7Unorm.surface_layout(1, 1, 1);
+    assert_eq!(layout.row_pitch, 16);
+    assert_eq!(layout.slice_pitch, 16);
+    assert_eq!((layout.padded_width, layout.padded_height), (4, 4));
+}
+
+#[cfg(test)]
+#[test]
+fn packed_layout() {
+    let layout = Format::Yuy2.surface_layout(5, 3, 1);
+    assert_eq!(layout.padded_width, 6);
+    assert_eq!(layout.row_pitch, 12);
+    assert_eq!(layout.slice_pitch, 36);
+
+    let layout = Format::Y210.surface_layout(4, 1, 1);
+    assert_eq!(layout.row_pitch, 16);
+}
+
+#[cfg(test)]
+#[test]
+fn planar_layout() {
+    let layout = Format::Nv12.surface_layout(64, 32, 1);
+    assert_eq!(layout.row_pitch, 64);
+    assert_eq!(layout.num_rows, 48);
+    assert_eq!(layout.slice_pitch, 64 * 48);
+    let planes = layout.planes();
+    assert_eq!(planes.len(), 2);
+    assert_eq!((planes[1].offset, planes[1].num_rows), (64 * 32, 16));
+    assert_eq!((planes[1].subsample_x, planes[1].subsample_y), (2, 2));
+
+    // Odd heights don't add a row of luma.
+    let layout = Format::P010.surface_layout(3, 3, 1);
+    assert_eq!((layout.padded_width, layout.padded_height), (4, 4));
+    assert_eq!(layout.row_pitch, 8);
+    assert_eq!(layout.num_rows, 3 + 2);
+    assert_eq!(layout.slice_pitch, 8 * 5);
+    let layout = Format::Nv12.surface_layout(6, 5, 1);
+    assert_eq!(layout.num_rows, 5 + 3);
+    assert_eq!(layout.planes()[1].offset, 6 * 5);
+    assert_eq!(layout.slice_pitch, 6 * 8);
+
+    let layout = Format::V208.surface_layout(8, 3, 1);
+    assert_eq!(layout.slice_pitch, 8 * (3 + 2 + 2));
+
+    let layout = Format::Nv11.surface_layout(8, 4, 1);
+    assert_eq!(layout.row_pitch, 8);
+    assert_eq!(layout.slice_pitch, 8 * 4 * 2);
+
+    let layout = Format::P208.surface_layout(8, 4, 1);
+    assert_eq!(layout.slice_pitch, 8 * 4 * 2);
+
+    let layout = Format::V208.surface_layout(8, 4, 1);
+    assert_eq!(layout.planes().len(), 3);
+    assert_eq!(layout.slice_pitch, 8 * (4 + 2 + 2));
+
+    let layout = Format::V408.surface_layout(8, 4, 1);
+    assert_eq!(layout.planes()[2].offset, 64);
+    assert_eq!(layout.slice_pitch, 8 * 4 * 3);
+}
+
+#[cfg(test)]
+#[test]
+fn mip_chain() {
+    assert_eq!(MipChainLayout::max_levels(1, 1, 1), 1);
+    assert_eq!(MipChainLayout::max_levels(256, 64, 1), 9);
+    assert_eq!(MipChainLayout::max_levels(5, 3, 0), 3);
+
+    let chain = Format::R8G8B8A8Unorm
+        .mip_chain_layout(4, 4, 1, 0, 2)
+        .unwrap();
+    assert_eq!(chain.mip_levels, 3);
+    assert_eq!(chain.subresources.len(), 6);
+    let slice_size = 4 * 4 * 4 + 2 * 2 * 4 + 4;
+    assert_eq!(chain.total_size, slice_size * 2);
+
+    let sub = chain.subresource(2, 1).unwrap();
+    assert_eq!(sub.offset, slice_size + 80);
+    assert_eq!((sub.width, sub.height), (1, 1));
+    assert!(chain.subresource(3, 0).is_none());
+
+    let chain = Format::Bc1Unorm.mip_chain_layout(8, 8, 1, 0, 1).unwrap();
+    let sizes: Vec<_> = chain
+        .subresources
+        .iter()
+        .map(|s| s.layout.slice_pitch)
+        .collect();
+    assert_eq!(sizes, [32, 8, 8, 8]);
+}
+
+#[cfg(test)]
+#[test]
+fn overflowing_layouts() {
+    assert_eq!(
+        Format::Bc1Unorm.checked_surface_layout(u32::MAX, 4, 1),
+        None
+    );
+    assert_eq!(Format::Nv12.checked_surface_layout(u32::MAX, 2, 1), None);
+    assert_eq!(Format::Yuy2.checked_surface_layout(u32::MAX, 1, 1), None);
+    assert_eq!(
+        Format::R32G32B32A32Float.checked_surface_layout(u32::MAX, u32::MAX, u32::MAX),
+        None
+    );
+    assert!(Format::R8Unorm
+        .checked_surface_layout(u32::MAX, 1, 1)
+        .is_some());
+
+    let huge = Format::R32G32B32A32Float.mip_chain_layout(1 << 20, 1 << 20, 1 << 20, 1, 1);
+    assert_eq!(huge, None);
+
+    // Formats without a size still can't ask for billions of subresources.
+    assert_eq!(Format::Unknown.mip_chain_layout(1, 1, 1, 1, u32::MAX), None);
+    assert_eq!(Format::Unknown.mip_chain_layout(1, 1, 1, u32::MAX, 1), None);
+    let most = Format::Unknown.mip_chain_layout(1, 1, 1, 15, 2048).unwrap();
+    assert_eq!(most.subresources.len(), 30720);
+    assert_eq!(most.total_size, 0);
+
+    // More levels than the dimensions have still lay out as 1x1x1.
+    let chain = Format::R8Unorm.mip_chain_layout(4, 4, 1, 40, 1).unwrap();
+    assert_eq!(chain.subresources.len(), 40);
+    assert_eq!(chain.total_size, 16 + 4 + 38);
+}
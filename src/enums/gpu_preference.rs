@@ -1,4 +1,5 @@
 #[auto_enum::auto_enum(u32)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GpuPreference {
     Unspecified,
     MinimumPower,
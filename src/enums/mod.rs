@@ -5,7 +5,10 @@ pub use self::alpha_mode::AlphaMode;
 #[doc(inline)]
 pub use self::factory_creation_flags::FactoryCreationFlags;
 #[doc(inline)]
-pub use self::format::Format;
+pub use self::format::{
+    CastFamily, Format, FormatChannel, FormatComponent, FormatInfo, MipChainLayout, NumericType,
+    PlaneLayout, SubresourceLayout, SurfaceLayout,
+};
 #[doc(inline)]
 pub use self::gpu_preference::GpuPreference;
 #[doc(inline)]
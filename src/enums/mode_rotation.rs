@@ -1,4 +1,7 @@
+mod transform;
+
 #[auto_enum::auto_enum(u32, checked)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Indicates how the back buffers should be rotated to fit the physical
 /// rotation of a monitor.
 pub enum ModeRotation {
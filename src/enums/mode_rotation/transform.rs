@@ -0,0 +1,217 @@
+use crate::enums::ModeRotation;
+
+use math2d::{Matrix3x2f, Point2i, Recti};
+
+/// Transforms between the unrotated space an application lays its content
+/// out in and the rotated space of a `NONPREROTATED` swap chain's buffers, or
+/// one given a rotation with `set_rotation`.
+///
+/// Rotations are clockwise, in pixel coordinates with y pointing down. The
+/// `width` and `height` passed to each method are always those of the
+/// unrotated space; the buffers are [`rotate_size`](ModeRotation::rotate_size)
+/// of that. `Unspecified` is treated as `Identity`.
+impl ModeRotation {
+    /// The rotation in degrees.
+    pub fn degrees(self) -> u32 {
+        match self {
+            ModeRotation::Unspecified | ModeRotation::Identity => 0,
+            ModeRotation::Rotate90 => 90,
+            ModeRotation::Rotate180 => 180,
+            ModeRotation::Rotate270 => 270,
+        }
+    }
+
+    /// The rotation that undoes this one.
+    pub fn inverse(self) -> ModeRotation {
+        match self {
+            ModeRotation::Rotate90 => ModeRotation::Rotate270,
+            ModeRotation::Rotate270 => ModeRotation::Rotate90,
+            rotation => rotation,
+        }
+    }
+
+    /// Whether width and height trade places, i.e. for 90 and 270 degrees.
+    pub fn swaps_dimensions(self) -> bool {
+        matches!(self, ModeRotation::Rotate90 | ModeRotation::Rotate270)
+    }
+
+    /// The size of the rotated space, e.g. the buffer size to create a
+    /// swap chain with for a `width` by `height` window.
+    pub fn rotate_size(self, width: u32, height: u32) -> (u32, u32) {
+        if self.swaps_dimensions() {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// The matrix mapping unrotated coordinates to rotated ones, to append
+    /// to a projection or 2D transform.
+    pub fn transform(self, width: f32, height: f32) -> Matrix3x2f {
+        let (a, b, c, d, x, y) = match self {
+            ModeRotation::Unspecified | ModeRotation::Identity => (1.0, 0.0, 0.0, 1.0, 0.0, 0.0),
+            ModeRotation::Rotate90 => (0.0, 1.0, -1.0, 0.0, height, 0.0),
+            ModeRotation::Rotate180 => (-1.0, 0.0, 0.0, -1.0, width, height),
+            ModeRotation::Rotate270 => (0.0, -1.0, 1.0, 0.0, 0.0, width),
+        };
+        Matrix3x2f { a, b, c, d, x, y }
+    }
+
+    /// The matrix mapping rotated coordinates back to unrotated ones.
+    pub fn inverse_transform(self, width: f32, height: f32) -> Matrix3x2f {
+        let (width, height) = if self.swaps_dimensions() {
+            (height, width)
+        } else {
+            (width, height)
+        };
+        self.inverse().transform(width, height)
+    }
+
+    /// Maps pixel `point` from unrotated to rotated space. This agrees with
+    /// [`transform`](ModeRotation::transform) applied to the pixel's center,
+    /// so in-bounds pixels stay in bounds.
+    pub fn rotate_point(self, point: Point2i, width: u32, height: u32) -> Point2i {
+        let (w, h) = (width as i32, height as i32);
+        match self {
+            ModeRotation::Unspecified | ModeRotation::Identity => point,
+            ModeRotation::Rotate90 => Point2i::new(h - 1 - point.y, point.x),
+            ModeRotation::Rotate180 => Point2i::new(w - 1 - point.x, h - 1 - point.y),
+            ModeRotation::Rotate270 => Point2i::new(point.y, w - 1 - point.x),
+        }
+    }
+
+    /// Maps pixel `point` from rotated space back to unrotated space, e.g.
+    /// for pointer input against content drawn rotated.
+    pub fn unrotate_point(self, point: Point2i, width: u32, height: u32) -> Point2i {
+        let (width, height) = self.rotate_size(width, height);
+        self.inverse().rotate_point(point, width, height)
+    }
+
+    /// Maps `rect` from unrotated to rotated space, e.g. for viewports,
+    /// scissor rects and dirty rects.
+    pub fn rotate_rect(self, rect: Recti, width: u32, height: u32) -> Recti {
+        let (w, h) = (width as i32, height as i32);
+        match self {
+            ModeRotation::Unspecified | ModeRotation::Identity => rect,
+            ModeRotation::Rotate90 => {
+                Recti::new(h - rect.bottom, rect.left, h - rect.top, rect.right)
+            }
+            ModeRotation::Rotate180 => {
+                Recti::new(w - rect.right, h - rect.bottom, w - rect.left, h - rect.top)
+            }
+            ModeRotation::Rotate270 => {
+                Recti::new(rect.top, w - rect.right, rect.bottom, w - rect.left)
+            }
+        }
+    }
+
+    /// Maps `rect` from rotated space back to unrotated space.
+    pub fn unrotate_rect(self, rect: Recti, width: u32, height: u32) -> Recti {
+        let (width, height) = self.rotate_size(width, height);
+        self.inverse().rotate_rect(rect, width, height)
+    }
+}
+
+#[cfg(test)]
+const ROTATIONS: [ModeRotation; 4] = [
+    ModeRotation::Identity,
+    ModeRotation::Rotate90,
+    ModeRotation::Rotate180,
+    ModeRotation::Rotate270,
+];
+
+#[cfg(test)]
+fn apply(m: &Matrix3x2f, x: f32, y: f32) -> (f32, f32) {
+    (x * m.a + y * m.c + m.x, x * m.b + y * m.d + m.y)
+}
+
+#[cfg(test)]
+#[test]
+fn sizes_and_inverses() {
+    for &rotation in &ROTATIONS {
+        assert_eq!(rotation.inverse().inverse(), rotation);
+        assert_eq!((rotation.degrees() + rotation.inverse().degrees()) % 360, 0);
+        let (w, h) = rotation.rotate_size(640, 480);
+        assert_eq!(rotation.inverse().rotate_size(w, h), (640, 480));
+    }
+    assert_eq!(ModeRotation::Rotate90.rotate_size(640, 480), (480, 640));
+    assert_eq!(ModeRotation::Rotate180.rotate_size(640, 480), (640, 480));
+    assert_eq!(ModeRotation::Unspecified.rotate_size(640, 480), (640, 480));
+    assert_eq!(
+        ModeRotation::Unspecified.inverse(),
+        ModeRotation::Unspecified
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn point_round_trips() {
+    let (width, height) = (5, 3);
+    for &rotation in &ROTATIONS {
+        let (rw, rh) = rotation.rotate_size(width, height);
+        let matrix = rotation.transform(width as f32, height as f32);
+        let inverse = rotation.inverse_transform(width as f32, height as f32);
+
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                let point = Point2i::new(x, y);
+                let rotated = rotation.rotate_point(point, width, height);
+                assert!(rotated.x >= 0 && rotated.x < rw as i32, "{:?}", rotation);
+                assert!(rotated.y >= 0 && rotated.y < rh as i32, "{:?}", rotation);
+                assert_eq!(rotation.unrotate_point(rotated, width, height), point);
+
+                let center = apply(&matrix, x as f32 + 0.5, y as f32 + 0.5);
+                assert_eq!(center, (rotated.x as f32 + 0.5, rotated.y as f32 + 0.5));
+                let back = apply(&inverse, center.0, center.1);
+                assert_eq!(back, (x as f32 + 0.5, y as f32 + 0.5));
+            }
+        }
+    }
+
+    // The top left corner moves to the corner the rotation takes it to.
+    let corner = |rotation: ModeRotation| rotation.rotate_point(Point2i::new(0, 0), 5, 3);
+    assert_eq!(corner(ModeRotation::Identity), Point2i::new(0, 0));
+    assert_eq!(corner(ModeRotation::Rotate90), Point2i::new(2, 0));
+    assert_eq!(corner(ModeRotation::Rotate180), Point2i::new(4, 2));
+    assert_eq!(corner(ModeRotation::Rotate270), Point2i::new(0, 4));
+}
+
+#[cfg(test)]
+#[test]
+fn rect_round_trips() {
+    let (width, height) = (1920, 1080);
+    let rects = [
+        Recti::new(0, 0, 1920, 1080),
+        Recti::new(0, 0, 1, 1),
+        Recti::new(100, 50, 400, 300),
+        Recti::new(1900, 1000, 1920, 1080),
+    ];
+    for &rotation in &ROTATIONS {
+        let (rw, rh) = rotation.rotate_size(width, height);
+        let matrix = rotation.transform(width as f32, height as f32);
+
+        for &rect in &rects {
+            let rotated = rotation.rotate_rect(rect, width, height);
+            assert!(rotated.left < rotated.right && rotated.top < rotated.bottom);
+            assert!(rotated.left >= 0 && rotated.right <= rw as i32);
+            assert!(rotated.top >= 0 && rotated.bottom <= rh as i32);
+            assert_eq!(rotation.unrotate_rect(rotated, width, height), rect);
+
+            // The rect's corners land on the rotated rect's corners.
+            let (x0, y0) = apply(&matrix, rect.left as f32, rect.top as f32);
+            let (x1, y1) = apply(&matrix, rect.right as f32, rect.bottom as f32);
+            let expected = Recti::new(
+                x0.min(x1) as i32,
+                y0.min(y1) as i32,
+                x0.max(x1) as i32,
+                y0.max(y1) as i32,
+            );
+            assert_eq!(rotated, expected, "{:?}", rotation);
+        }
+    }
+
+    assert_eq!(
+        ModeRotation::Rotate90.rotate_rect(Recti::new(0, 0, 1920, 1080), width, height),
+        Recti::new(0, 0, 1080, 1920)
+    );
+}
@@ -1,4 +1,5 @@
 #[auto_enum::auto_enum(u32, checked)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Indicates how an image is stretched to fit a given monitor's resolution.
 pub enum ModeScaling {
     /// Unspecified, the meaning of this is contextual to the API
@@ -1,4 +1,5 @@
 #[auto_enum::auto_enum(u32, checked)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Indicates the method the raster uses to create an image on a surface.
 pub enum ModeScanlineOrder {
     /// Unspecified, the meaning of this is contextual to the API
@@ -1,4 +1,5 @@
 #[auto_enum::auto_enum(u32, checked)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GraphicsPreemptionGranularity {
     DmaBufferBoundary = 0,
     PrimitiveBoundary = 1,
@@ -8,6 +9,7 @@ pub enum GraphicsPreemptionGranularity {
 }
 
 #[auto_enum::auto_enum(u32, checked)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ComputePreemptionGranularity {
     DmaBufferBoundary = 0,
     DispatchBoundary = 1,
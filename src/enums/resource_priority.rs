@@ -1,6 +1,11 @@
 use std::ops::{Add, Sub};
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 /// The relative priority of a memory resource, which is often used to determine
 /// which resources should be cleared to make room for others.
 pub struct ResourcePriority(pub u32);
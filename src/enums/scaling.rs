@@ -1,4 +1,5 @@
 #[auto_enum::auto_enum(u32, checked)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Identifies resize behavior when the back-buffer size does not match the
 /// size of the target output.
 pub enum Scaling {
@@ -1,4 +1,5 @@
 #[auto_enum::auto_enum(u32, checked)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Options for handling pixels in a display surface after calling
 /// [`SwapChain::present`][1].
 ///
@@ -0,0 +1,361 @@
+//! Typed DXGI error and status codes.
+//!
+//! [`DxgiError`] names every `DXGI_ERROR_*` and `DXGI_STATUS_*` code, groups
+//! them into an [`ErrorCategory`] for recovery code, and converts to and from
+//! the raw `HRESULT` without losing anything: codes DXGI doesn't define are
+//! kept as `DxgiError::Other`.
+
+use crate::backend::HResult;
+
+use std::fmt;
+
+/// What a caller can do about an error.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// The call may succeed if it is retried later, or after waiting for or
+    /// recreating something other than the device (e.g. the swap chain
+    /// being unoccluded, a duplication interface being recreated).
+    Transient,
+    /// The device is gone. Recreate the device and every resource created
+    /// from it.
+    DeviceLost,
+    /// The call was invalid. Retrying it won't help.
+    Programming,
+    /// The system, driver or hardware doesn't support the request.
+    Unsupported,
+    /// A code that doesn't belong in any of the other categories.
+    Other,
+}
+
+macro_rules! dxgi_errors {
+    ($(
+        $(#[$attr:meta])*
+        $variant:ident = $code:expr, $name:expr, $category:ident, $message:expr;
+    )*) => {
+        /// A DXGI error or status code.
+        ///
+        /// The status codes (`Occluded`, `ModeChanged`, ...) are success
+        /// codes that `Present` and a few other methods return to report
+        /// something the caller may want to react to.
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        pub enum DxgiError {
+            $(
+                $(#[$attr])*
+                $variant,
+            )*
+            /// Any other `HRESULT`.
+            Other(i32),
+        }
+
+        const TABLE: &[Entry] = &[
+            $(
+                Entry {
+                    error: DxgiError::$variant,
+                    code: $code as u32 as i32,
+                    name: $name,
+                    category: ErrorCategory::$category,
+                    message: $message,
+                },
+            )*
+        ];
+    };
+}
+
+struct Entry {
+    error: DxgiError,
+    code: i32,
+    name: &'static str,
+    category: ErrorCategory,
+    message: &'static str,
+}
+
+dxgi_errors! {
+    InvalidCall = 0x887A_0001u32, "DXGI_ERROR_INVALID_CALL", Programming,
+        "the method call is invalid, e.g. a parameter is not a valid pointer";
+    NotFound = 0x887A_0002u32, "DXGI_ERROR_NOT_FOUND", Programming,
+        "the object was not found, e.g. an enumeration index is past the end";
+    MoreData = 0x887A_0003u32, "DXGI_ERROR_MORE_DATA", Programming,
+        "the buffer supplied is not big enough to hold the data";
+    Unsupported = 0x887A_0004u32, "DXGI_ERROR_UNSUPPORTED", Unsupported,
+        "the requested functionality is not supported by the device or driver";
+    DeviceRemoved = 0x887A_0005u32, "DXGI_ERROR_DEVICE_REMOVED", DeviceLost,
+        "the GPU device instance has been suspended or removed";
+    DeviceHung = 0x887A_0006u32, "DXGI_ERROR_DEVICE_HUNG", DeviceLost,
+        "the device failed because of a badly formed command";
+    DeviceReset = 0x887A_0007u32, "DXGI_ERROR_DEVICE_RESET", DeviceLost,
+        "the device failed because of a badly formed command and was reset";
+    WasStillDrawing = 0x887A_000Au32, "DXGI_ERROR_WAS_STILL_DRAWING", Transient,
+        "the GPU was busy at the moment the call was made";
+    FrameStatisticsDisjoint = 0x887A_000Bu32, "DXGI_ERROR_FRAME_STATISTICS_DISJOINT", Transient,
+        "an event such as a power cycle interrupted the gathering of presentation statistics";
+    GraphicsVidpnSourceInUse = 0x887A_000Cu32, "DXGI_ERROR_GRAPHICS_VIDPN_SOURCE_IN_USE", Transient,
+        "the output is already owned by another application";
+    DriverInternalError = 0x887A_0020u32, "DXGI_ERROR_DRIVER_INTERNAL_ERROR", DeviceLost,
+        "the driver encountered a problem and was put into the device removed state";
+    NonExclusive = 0x887A_0021u32, "DXGI_ERROR_NONEXCLUSIVE", Transient,
+        "a global counter resource is in use and can't be used by this device right now";
+    NotCurrentlyAvailable = 0x887A_0022u32, "DXGI_ERROR_NOT_CURRENTLY_AVAILABLE", Transient,
+        "the resource or request is not currently available, but it might become available later";
+    RemoteClientDisconnected = 0x887A_0023u32, "DXGI_ERROR_REMOTE_CLIENT_DISCONNECTED", Transient,
+        "the remote desktop client was disconnected";
+    RemoteOutOfMemory = 0x887A_0024u32, "DXGI_ERROR_REMOTE_OUTOFMEMORY", Transient,
+        "the remote device ran out of memory";
+    ModeChangeInProgress = 0x887A_0025u32, "DXGI_ERROR_MODE_CHANGE_IN_PROGRESS", Transient,
+        "a display mode change is in progress, so the call can't be completed right now";
+    AccessLost = 0x887A_0026u32, "DXGI_ERROR_ACCESS_LOST", Transient,
+        "the desktop duplication interface is invalid and must be recreated";
+    WaitTimeout = 0x887A_0027u32, "DXGI_ERROR_WAIT_TIMEOUT", Transient,
+        "the time-out interval elapsed before the next desktop frame was available";
+    SessionDisconnected = 0x887A_0028u32, "DXGI_ERROR_SESSION_DISCONNECTED", Transient,
+        "the remote desktop services session is disconnected";
+    RestrictToOutputStale = 0x887A_0029u32, "DXGI_ERROR_RESTRICT_TO_OUTPUT_STALE", Transient,
+        "the output the swap chain was restricted to has been disconnected or changed";
+    CannotProtectContent = 0x887A_002Au32, "DXGI_ERROR_CANNOT_PROTECT_CONTENT", Unsupported,
+        "content protection is not available on the output";
+    AccessDenied = 0x887A_002Bu32, "DXGI_ERROR_ACCESS_DENIED", Unsupported,
+        "access to the shared resource was denied";
+    NameAlreadyExists = 0x887A_002Cu32, "DXGI_ERROR_NAME_ALREADY_EXISTS", Programming,
+        "a shared resource with that name already exists";
+    SdkComponentMissing = 0x887A_002Du32, "DXGI_ERROR_SDK_COMPONENT_MISSING", Unsupported,
+        "an SDK component required by the operation is missing or mismatched";
+    NotCurrent = 0x887A_002Eu32, "DXGI_ERROR_NOT_CURRENT", Transient,
+        "the resource is no longer valid, e.g. the video memory was lost and must be recreated";
+    HwProtectionOutOfMemory = 0x887A_0030u32, "DXGI_ERROR_HW_PROTECTION_OUTOFMEMORY", Transient,
+        "there is not enough hardware protected memory";
+    DynamicCodePolicyViolation = 0x887A_0031u32, "DXGI_ERROR_DYNAMIC_CODE_POLICY_VIOLATION", Unsupported,
+        "the operation would require dynamic code generation, which the process policy forbids";
+    NonCompositedUi = 0x887A_0032u32, "DXGI_ERROR_NON_COMPOSITED_UI", Transient,
+        "the operation failed because non-composited UI is being shown";
+    CacheCorrupt = 0x887A_0033u32, "DXGI_ERROR_CACHE_CORRUPT", Transient,
+        "the shader cache is corrupt";
+    CacheFull = 0x887A_0034u32, "DXGI_ERROR_CACHE_FULL", Transient,
+        "the shader cache is full";
+    CacheHashCollision = 0x887A_0035u32, "DXGI_ERROR_CACHE_HASH_COLLISION", Transient,
+        "an entry with the same hash but different data is already in the shader cache";
+    AlreadyExists = 0x887A_0036u32, "DXGI_ERROR_ALREADY_EXISTS", Programming,
+        "the entry already exists";
+    MpoUnpinned = 0x887A_0064u32, "DXGI_ERROR_MPO_UNPINNED", Transient,
+        "the multiplane overlay was unpinned from the output";
+
+    Occluded = 0x087A_0001u32, "DXGI_STATUS_OCCLUDED", Transient,
+        "the window content is not visible, so the frame was not presented";
+    Clipped = 0x087A_0002u32, "DXGI_STATUS_CLIPPED", Transient,
+        "the window content is partially clipped";
+    NoRedirection = 0x087A_0004u32, "DXGI_STATUS_NO_REDIRECTION", Other,
+        "the window is not redirected";
+    NoDesktopAccess = 0x087A_0005u32, "DXGI_STATUS_NO_DESKTOP_ACCESS", Transient,
+        "the desktop is not accessible, e.g. the secure desktop is being shown";
+    GraphicsVidpnSourceInUseStatus = 0x087A_0006u32, "DXGI_STATUS_GRAPHICS_VIDPN_SOURCE_IN_USE", Transient,
+        "the output is in use by another application";
+    ModeChanged = 0x087A_0007u32, "DXGI_STATUS_MODE_CHANGED", Transient,
+        "the display mode changed, so the frame was presented in a different mode";
+    ModeChangeInProgressStatus = 0x087A_0008u32, "DXGI_STATUS_MODE_CHANGE_IN_PROGRESS", Transient,
+        "a display mode change is in progress";
+    Unoccluded = 0x087A_0009u32, "DXGI_STATUS_UNOCCLUDED", Transient,
+        "the window content is visible again";
+    DdaWasStillDrawing = 0x087A_000Au32, "DXGI_STATUS_DDA_WAS_STILL_DRAWING", Transient,
+        "the desktop duplication was still drawing";
+    PresentRequired = 0x087A_002Fu32, "DXGI_STATUS_PRESENT_REQUIRED", Transient,
+        "the swap chain must be presented before the call can succeed";
+}
+
+const E_NOTIMPL: i32 = 0x8000_4001u32 as i32;
+const E_NOINTERFACE: i32 = 0x8000_4002u32 as i32;
+const E_POINTER: i32 = 0x8000_4003u32 as i32;
+const E_OUTOFMEMORY: i32 = 0x8007_000Eu32 as i32;
+const E_INVALIDARG: i32 = 0x8007_0057u32 as i32;
+
+impl DxgiError {
+    /// Looks up a raw `HRESULT`. Codes that DXGI doesn't define give
+    /// `DxgiError::Other`.
+    pub fn from_hresult(code: i32) -> DxgiError {
+        TABLE
+            .iter()
+            .find(|entry| entry.code == code)
+            .map(|entry| entry.error)
+            .unwrap_or(DxgiError::Other(code))
+    }
+
+    /// The raw `HRESULT`.
+    pub fn hresult(self) -> i32 {
+        match self {
+            DxgiError::Other(code) => code,
+            error => error.entry().map(|entry| entry.code).unwrap_or(0),
+        }
+    }
+
+    /// The name of the constant in the Windows headers, e.g.
+    /// `DXGI_ERROR_DEVICE_REMOVED`. `None` for `Other`.
+    pub fn name(self) -> Option<&'static str> {
+        self.entry().map(|entry| entry.name)
+    }
+
+    /// What a caller can do about the error.
+    ///
+    /// The generic COM codes a DXGI method can return are categorized too:
+    /// `E_INVALIDARG` and `E_POINTER` are programming errors, `E_NOTIMPL`
+    /// and `E_NOINTERFACE` mean the request is unsupported, and
+    /// `E_OUTOFMEMORY` is transient.
+    pub fn category(self) -> ErrorCategory {
+        match self {
+            DxgiError::Other(E_INVALIDARG) | DxgiError::Other(E_POINTER) => {
+                ErrorCategory::Programming
+            }
+            DxgiError::Other(E_NOTIMPL) | DxgiError::Other(E_NOINTERFACE) => {
+                ErrorCategory::Unsupported
+            }
+            DxgiError::Other(E_OUTOFMEMORY) => ErrorCategory::Transient,
+            DxgiError::Other(_) => ErrorCategory::Other,
+            error => error
+                .entry()
+                .map(|entry| entry.category)
+                .unwrap_or(ErrorCategory::Other),
+        }
+    }
+
+    /// Whether this is a success code (a `DXGI_STATUS_*` code, or any other
+    /// non-negative `HRESULT`) rather than a failure.
+    pub fn is_status(self) -> bool {
+        self.hresult() >= 0
+    }
+
+    /// Whether the device was lost and has to be recreated.
+    pub fn is_device_lost(self) -> bool {
+        self.category() == ErrorCategory::DeviceLost
+    }
+
+    fn entry(self) -> Option<&'static Entry> {
+        TABLE.iter().find(|entry| entry.error == self)
+    }
+}
+
+impl fmt::Display for DxgiError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self.entry() {
+            Some(entry) => write!(fmt, "{} ({})", entry.message, entry.name),
+            None => write!(fmt, "HRESULT 0x{:08X}", self.hresult() as u32),
+        }
+    }
+}
+
+impl std::error::Error for DxgiError {}
+
+impl From<i32> for DxgiError {
+    fn from(code: i32) -> DxgiError {
+        DxgiError::from_hresult(code)
+    }
+}
+
+impl From<DxgiError> for i32 {
+    fn from(error: DxgiError) -> i32 {
+        error.hresult()
+    }
+}
+
+impl From<HResult> for DxgiError {
+    fn from(hr: HResult) -> DxgiError {
+        DxgiError::from_hresult(hr.0)
+    }
+}
+
+impl From<DxgiError> for HResult {
+    fn from(error: DxgiError) -> HResult {
+        HResult(error.hresult())
+    }
+}
+
+#[cfg(windows)]
+impl From<dcommon::error::Error> for DxgiError {
+    fn from(error: dcommon::error::Error) -> DxgiError {
+        DxgiError::from_hresult(error.0)
+    }
+}
+
+#[cfg(windows)]
+impl From<DxgiError> for dcommon::error::Error {
+    fn from(error: DxgiError) -> dcommon::error::Error {
+        dcommon::error::Error(error.hresult())
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn round_trip() {
+    for entry in TABLE {
+        assert_eq!(DxgiError::from_hresult(entry.code), entry.error);
+        assert_eq!(entry.error.hresult(), entry.code);
+        assert_eq!(
+            TABLE.iter().filter(|e| e.code == entry.code).count(),
+            1,
+            "{} is listed twice",
+            entry.name
+        );
+        assert_eq!(
+            entry.error.is_status(),
+            entry.name.starts_with("DXGI_STATUS_")
+        );
+    }
+
+    for &code in &[0, 1, -1, E_INVALIDARG, 0x887A_00FFu32 as i32] {
+        let error = DxgiError::from_hresult(code);
+        assert_eq!(error, DxgiError::Other(code));
+        assert_eq!(error.hresult(), code);
+        assert_eq!(HResult::from(error), HResult(code));
+    }
+
+    assert_eq!(
+        DxgiError::from(HResult::DEVICE_REMOVED),
+        DxgiError::DeviceRemoved
+    );
+    assert_eq!(i32::from(DxgiError::Occluded), 0x087A_0001);
+    assert_eq!(
+        DxgiError::from_hresult(0x887A_0025u32 as i32),
+        DxgiError::ModeChangeInProgress
+    );
+    assert_eq!(
+        DxgiError::from_hresult(0x087A_0008),
+        DxgiError::ModeChangeInProgressStatus
+    );
+    assert!(!DxgiError::ModeChangeInProgress.is_status());
+}
+
+#[cfg(test)]
+#[test]
+fn categories_and_text() {
+    assert!(DxgiError::DeviceRemoved.is_device_lost());
+    assert!(DxgiError::DeviceHung.is_device_lost());
+    assert!(DxgiError::DeviceReset.is_device_lost());
+    assert_eq!(
+        DxgiError::WasStillDrawing.category(),
+        ErrorCategory::Transient
+    );
+    assert_eq!(
+        DxgiError::ModeChangeInProgress.category(),
+        ErrorCategory::Transient
+    );
+    assert_eq!(
+        DxgiError::InvalidCall.category(),
+        ErrorCategory::Programming
+    );
+    assert_eq!(
+        DxgiError::Unsupported.category(),
+        ErrorCategory::Unsupported
+    );
+    assert_eq!(
+        DxgiError::from_hresult(E_INVALIDARG).category(),
+        ErrorCategory::Programming
+    );
+    assert_eq!(DxgiError::Other(0x1234).category(), ErrorCategory::Other);
+
+    assert_eq!(DxgiError::AccessLost.name(), Some("DXGI_ERROR_ACCESS_LOST"));
+    assert_eq!(DxgiError::Other(5).name(), None);
+    assert_eq!(
+        DxgiError::WaitTimeout.to_string(),
+        "the time-out interval elapsed before the next desktop frame was available \
+         (DXGI_ERROR_WAIT_TIMEOUT)"
+    );
+    assert_eq!(
+        DxgiError::Other(0x8000_FFFFu32 as i32).to_string(),
+        "HRESULT 0x8000FFFF"
+    );
+}
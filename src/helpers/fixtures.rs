@@ -0,0 +1,37 @@
+//! Descriptions shared by the unit tests.
+
+use crate::descriptions::{DBool, Mode, OutputDesc};
+use crate::enums::{Format, ModeRotation};
+
+use math2d::Recti;
+
+/// `s` as a nul padded UTF-16 array, like the names in DXGI descriptions.
+pub fn wide<const N: usize>(s: &str) -> [u16; N] {
+    let mut buf = [0; N];
+    for (dst, src) in buf.iter_mut().zip(s.encode_utf16()) {
+        *dst = src;
+    }
+    buf
+}
+
+pub fn mode(width: u32, height: u32, refresh: (u32, u32), format: Format) -> Mode {
+    Mode {
+        width,
+        height,
+        refresh_rate: refresh.into(),
+        format: format.into(),
+        ..Default::default()
+    }
+}
+
+/// An output attached to the desktop at `bounds`, given as left, top, right
+/// and bottom.
+pub fn output_desc(name: &str, bounds: (i32, i32, i32, i32), rotation: ModeRotation) -> OutputDesc {
+    OutputDesc {
+        device_name: wide(name),
+        desktop_coordinates: Recti::new(bounds.0, bounds.1, bounds.2, bounds.3),
+        attached_to_desktop: DBool::TRUE,
+        rotation: rotation.into(),
+        monitor: std::ptr::null_mut(),
+    }
+}
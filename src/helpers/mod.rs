@@ -1,7 +1,12 @@
+#[cfg(windows)]
 pub use self::optional_fn::OptionalFn;
 
+#[cfg(windows)]
 mod optional_fn;
 
+#[cfg(test)]
+pub mod fixtures;
+
 #[cfg(test)]
 pub struct StructSizeTracker {
     pub size: usize,
@@ -52,7 +57,7 @@ macro_rules! member_compat_test {
             use std::mem::{align_of, size_of};
             assert_eq!(size_of::<$t1>(), size_of::<$t2>(), "types are not the same size");
             assert_eq!(align_of::<$t1>(), align_of::<$t2>(), "types are not the same alignment");
-            let mut tracker = crate::helpers::StructSizeTracker::new();
+            let mut tracker = $crate::helpers::StructSizeTracker::new();
             $(
                 member_compat_test!(@member tracker, $t1, $t2, $($m1).+, $m2);
             )*
@@ -102,7 +107,6 @@ macro_rules! member_compat_test {
                 ),
             );
             $tracker.incr(size_of_val(&f1.$($m1).+), align_of_val(&f1.$($m1).+));}
-            std::mem::forget(f1);
         }
     };
 }
@@ -146,6 +150,7 @@ impl std::fmt::Debug for MemoryDbgHelper {
 }
 
 #[test]
+#[allow(clippy::identity_op)]
 fn memory_dbg_helper() {
     assert_eq!(format!("{:?}", MemoryDbgHelper(1024u64.pow(0) * 1)), "1B");
     assert_eq!(format!("{:?}", MemoryDbgHelper(1024u64.pow(0) * 10)), "10B");
@@ -221,5 +226,5 @@ fn memory_dbg_helper() {
         format!("{:?}", MemoryDbgHelper(1024u64.pow(6) * 10)),
         "10.0EB"
     );
-    assert_eq!(format!("{:?}", MemoryDbgHelper(std::u64::MAX)), "16.0EB");
+    assert_eq!(format!("{:?}", MemoryDbgHelper(u64::MAX)), "16.0EB");
 }
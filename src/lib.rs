@@ -1,25 +1,66 @@
 //! TODO: Add documentation
+//!
+//! The COM wrappers are only available on Windows. The plain-data parts of
+//! the crate (`enums`, `descriptions`, `codec` and `dds`) build on every
+//! target. Off Windows, the descriptions keep the same `#[repr(C)]` layout
+//! but lose their conversions to and from the winapi structs.
+//!
+//! Adapter and mode selection can be written against the traits in `backend`,
+//! which are implemented both by the COM wrappers and by an in-memory fake
+//! for unit tests. `error::DxgiError` gives the raw `HRESULT` codes names and
+//! categories for recovery code, and `present` interprets the status codes
+//! returned by `Present`. `pci` names the GPU behind an adapter's PCI ids,
+//! and `driver` decodes user-mode driver versions for blocklists.
+//!
+//! The `serde` feature implements `Serialize` and `Deserialize` for the
+//! enums and flags, and for the plain description structs. Descriptions are
+//! written in a human readable form: names are strings, enums are written by
+//! name and flags as a list of names.
+//!
+//! The `pci-ids` feature embeds a table of common GPUs for `pci::identify`.
 
-#![cfg(windows)]
 //#![warn(missing_docs)]
 
 extern crate checked_enum;
+#[cfg(windows)]
 extern crate com_wrapper;
 extern crate math2d;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(windows)]
 extern crate winapi;
+#[cfg(windows)]
 extern crate wio;
 
 #[macro_use]
 mod helpers;
 
+#[cfg(windows)]
 pub mod adapter;
+pub mod backend;
+pub mod codec;
+pub mod dds;
 pub mod descriptions;
+#[cfg(windows)]
 pub mod device;
+#[cfg(windows)]
 pub mod device_subobject;
+pub mod driver;
 pub mod enums;
+pub mod error;
+#[cfg(windows)]
 pub mod factory;
+#[cfg(windows)]
 pub mod features;
+#[cfg(windows)]
 pub mod output;
+pub mod pci;
+pub mod present;
+#[cfg(windows)]
 pub mod resource;
+#[cfg(feature = "serde")]
+mod serde_impls;
+#[cfg(windows)]
 pub mod surface;
+#[cfg(windows)]
 pub mod swap_chain;
@@ -0,0 +1,351 @@
+//! Identifying GPUs from the PCI ids in an adapter description.
+//!
+//! `vendor` has the ids of the common GPU vendors, which is enough for most
+//! vendor specific workarounds. Device names come from a database in the
+//! `pci.ids` format: load one at runtime with [`PciIds::parse`], or enable
+//! the `pci-ids` feature to embed a table of common GPUs, which
+//! [`identify`] then uses.
+
+use std::fmt;
+
+pub use self::ids::{Device, PciIds, PciIdsError, Subsystem, Vendor};
+
+mod ids;
+
+/// PCI vendor ids of GPU vendors, in the form `AdapterDesc::vendor_id` uses.
+pub mod vendor {
+    pub const AMD: u32 = 0x1002;
+    pub const APPLE: u32 = 0x106B;
+    pub const ARM: u32 = 0x13B5;
+    pub const BROADCOM: u32 = 0x14E4;
+    pub const IMAGINATION: u32 = 0x1010;
+    pub const INTEL: u32 = 0x8086;
+    /// Also the vendor of the Microsoft Basic Render Driver (WARP).
+    pub const MICROSOFT: u32 = 0x1414;
+    pub const NVIDIA: u32 = 0x10DE;
+    pub const QUALCOMM: u32 = 0x5143;
+    pub const RED_HAT: u32 = 0x1AF4;
+    pub const SAMSUNG: u32 = 0x144D;
+    pub const VIRTUALBOX: u32 = 0x80EE;
+    pub const VMWARE: u32 = 0x15AD;
+}
+
+/// The device id of the Microsoft Basic Render Driver, the software adapter
+/// DXGI always enumerates last.
+pub const BASIC_RENDER_DRIVER: u32 = 0x008C;
+
+/// The short name of a well known GPU vendor, e.g. `NVIDIA` for `0x10DE`.
+pub fn vendor_name(vendor_id: u32) -> Option<&'static str> {
+    Some(match vendor_id {
+        vendor::AMD => "AMD",
+        vendor::APPLE => "Apple",
+        vendor::ARM => "ARM",
+        vendor::BROADCOM => "Broadcom",
+        vendor::IMAGINATION => "Imagination",
+        vendor::INTEL => "Intel",
+        vendor::MICROSOFT => "Microsoft",
+        vendor::NVIDIA => "NVIDIA",
+        vendor::QUALCOMM => "Qualcomm",
+        vendor::RED_HAT => "Red Hat",
+        vendor::SAMSUNG => "Samsung",
+        vendor::VIRTUALBOX => "VirtualBox",
+        vendor::VMWARE => "VMware",
+        _ => return None,
+    })
+}
+
+/// The PCI ids from an adapter description.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PciId {
+    pub vendor_id: u32,
+    pub device_id: u32,
+    /// The board's subsystem device id in the high 16 bits, and its vendor
+    /// id in the low 16 bits.
+    pub subsys_id: u32,
+    pub revision: u32,
+}
+
+impl PciId {
+    /// The board vendor and device ids packed into `subsys_id`.
+    pub fn subsystem(&self) -> (u16, u16) {
+        (self.subsys_id as u16, (self.subsys_id >> 16) as u16)
+    }
+
+    /// Whether this is the Microsoft Basic Render Driver.
+    pub fn is_basic_render_driver(&self) -> bool {
+        self.vendor_id == vendor::MICROSOFT && self.device_id == BASIC_RENDER_DRIVER
+    }
+}
+
+/// A GPU described for people, e.g. `NVIDIA / GeForce RTX 3070 / Ampere`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GpuIdentity {
+    pub id: PciId,
+    pub vendor: Option<String>,
+    /// The product name, from the board when the database has it.
+    pub device: Option<String>,
+    /// The chip name, e.g. `GA104`.
+    pub chip: Option<String>,
+    /// The GPU architecture, guessed from the chip name.
+    pub architecture: Option<&'static str>,
+}
+
+impl GpuIdentity {
+    /// Whatever can be said about the GPU without a device database.
+    pub fn from_id(id: PciId) -> GpuIdentity {
+        GpuIdentity {
+            id,
+            vendor: vendor_name(id.vendor_id).map(str::to_string),
+            device: None,
+            chip: None,
+            architecture: None,
+        }
+    }
+}
+
+impl fmt::Display for GpuIdentity {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self.vendor {
+            Some(ref vendor) => fmt.write_str(vendor)?,
+            None => write!(fmt, "Vendor {:04X}", self.id.vendor_id)?,
+        }
+        match self.device {
+            Some(ref device) => write!(fmt, " / {}", device)?,
+            None => write!(fmt, " / Device {:04X}", self.id.device_id)?,
+        }
+        if let Some(architecture) = self.architecture {
+            write!(fmt, " / {}", architecture)?;
+        }
+        Ok(())
+    }
+}
+
+impl PciIds {
+    /// Looks the GPU up in this database.
+    pub fn identify(&self, id: PciId) -> GpuIdentity {
+        let mut identity = GpuIdentity::from_id(id);
+        let vendor = match self.vendor(id.vendor_id as u16) {
+            Some(vendor) if id.vendor_id <= 0xFFFF => vendor,
+            _ => return identity,
+        };
+        if identity.vendor.is_none() {
+            identity.vendor = Some(vendor.name.clone());
+        }
+
+        let device = match vendor.device(id.device_id as u16) {
+            Some(device) if id.device_id <= 0xFFFF => device,
+            _ => return identity,
+        };
+        let (sub_vendor, sub_device) = id.subsystem();
+        identity.device = Some(match device.subsystem(sub_vendor, sub_device) {
+            Some(subsystem) => subsystem.name.clone(),
+            None => device.product_name().to_string(),
+        });
+        identity.chip = device.chip_name().map(str::to_string);
+        identity.architecture = architecture(id.vendor_id, &device.name);
+        identity
+    }
+}
+
+/// The embedded device table.
+#[cfg(feature = "pci-ids")]
+pub fn builtin() -> &'static PciIds {
+    static IDS: std::sync::OnceLock<PciIds> = std::sync::OnceLock::new();
+    IDS.get_or_init(|| PciIds::parse(include_str!("pci/pci.ids")).expect("embedded pci.ids"))
+}
+
+/// Identifies the GPU using the embedded table if the `pci-ids` feature is
+/// enabled, and otherwise only names the vendor.
+pub fn identify(id: PciId) -> GpuIdentity {
+    #[cfg(feature = "pci-ids")]
+    {
+        builtin().identify(id)
+    }
+    #[cfg(not(feature = "pci-ids"))]
+    {
+        GpuIdentity::from_id(id)
+    }
+}
+
+/// Guesses the architecture from a `pci.ids` device name. Only covers the
+/// naming schemes of recent NVIDIA, AMD and Intel GPUs.
+pub fn architecture(vendor_id: u32, device_name: &str) -> Option<&'static str> {
+    match vendor_id {
+        vendor::NVIDIA => {
+            let prefix = device_name.get(..3)?;
+            if !prefix.as_bytes()[2].is_ascii_digit() {
+                return None;
+            }
+            Some(match &prefix[..2] {
+                "GB" => "Blackwell",
+                "AD" => "Ada Lovelace",
+                "GH" => "Hopper",
+                "GA" => "Ampere",
+                "TU" => "Turing",
+                "GV" => "Volta",
+                "GP" => "Pascal",
+                "GM" => "Maxwell",
+                "GK" => "Kepler",
+                "GF" => "Fermi",
+                _ => return None,
+            })
+        }
+        vendor::AMD => {
+            if let Some(rest) = device_name.strip_prefix("Navi ") {
+                return match rest.as_bytes().first()? {
+                    b'1' => Some("RDNA"),
+                    b'2' => Some("RDNA 2"),
+                    b'3' => Some("RDNA 3"),
+                    b'4' => Some("RDNA 4"),
+                    _ => None,
+                };
+            }
+            if device_name.starts_with("Vega") {
+                Some("GCN 5")
+            } else if ["Ellesmere", "Baffin", "Lexa", "Polaris"]
+                .iter()
+                .any(|chip| device_name.starts_with(chip))
+            {
+                Some("GCN 4")
+            } else {
+                None
+            }
+        }
+        vendor::INTEL => {
+            if device_name.starts_with("DG2") || device_name.contains("[Arc ") {
+                Some("Xe-HPG")
+            } else if device_name.contains("Iris Xe") || device_name.starts_with("DG1") {
+                Some("Xe-LP")
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+fn test_ids() -> PciIds {
+    PciIds::parse(
+        "\
+10de  NVIDIA Corporation
+\t2484  GA104 [GeForce RTX 3070]
+\t\t10de 146b  GeForce RTX 3070 Founders Edition
+1414  Microsoft Corporation
+\t008c  Basic Render Driver
+1234  Example Vendor
+\t0001  Example Device
+",
+    )
+    .unwrap()
+}
+
+#[cfg(test)]
+#[test]
+fn identify_devices() {
+    let ids = test_ids();
+    let id = PciId {
+        vendor_id: vendor::NVIDIA,
+        device_id: 0x2484,
+        ..Default::default()
+    };
+    let identity = ids.identify(id);
+    assert_eq!(identity.chip.as_deref(), Some("GA104"));
+    assert_eq!(identity.to_string(), "NVIDIA / GeForce RTX 3070 / Ampere");
+
+    let board = PciId {
+        subsys_id: 0x146b_10de,
+        ..id
+    };
+    assert_eq!(board.subsystem(), (0x10de, 0x146b));
+    assert_eq!(
+        ids.identify(board).to_string(),
+        "NVIDIA / GeForce RTX 3070 Founders Edition / Ampere"
+    );
+
+    let warp = PciId {
+        vendor_id: vendor::MICROSOFT,
+        device_id: BASIC_RENDER_DRIVER,
+        ..Default::default()
+    };
+    assert!(warp.is_basic_render_driver());
+    assert_eq!(
+        ids.identify(warp).to_string(),
+        "Microsoft / Basic Render Driver"
+    );
+
+    let other = PciId {
+        vendor_id: 0x1234,
+        device_id: 0x0001,
+        ..Default::default()
+    };
+    assert_eq!(
+        ids.identify(other).to_string(),
+        "Example Vendor / Example Device"
+    );
+
+    let unknown = PciId {
+        vendor_id: 0xABCD,
+        device_id: 0x0001,
+        ..Default::default()
+    };
+    assert_eq!(
+        ids.identify(unknown).to_string(),
+        "Vendor ABCD / Device 0001"
+    );
+    let unknown = PciId {
+        device_id: 0x9999,
+        ..id
+    };
+    assert_eq!(ids.identify(unknown).to_string(), "NVIDIA / Device 9999");
+}
+
+#[cfg(test)]
+#[test]
+fn architectures() {
+    assert_eq!(
+        architecture(vendor::NVIDIA, "AD102 [GeForce RTX 4090]"),
+        Some("Ada Lovelace")
+    );
+    assert_eq!(
+        architecture(vendor::NVIDIA, "TU116 [GeForce GTX 1660 Ti]"),
+        Some("Turing")
+    );
+    assert_eq!(architecture(vendor::NVIDIA, "GAxyz"), None);
+    assert_eq!(
+        architecture(vendor::AMD, "Navi 21 [Radeon RX 6800/6800 XT / 6900 XT]"),
+        Some("RDNA 2")
+    );
+    assert_eq!(
+        architecture(vendor::AMD, "Ellesmere [Radeon RX 470/480]"),
+        Some("GCN 4")
+    );
+    assert_eq!(
+        architecture(vendor::INTEL, "DG2 [Arc A770]"),
+        Some("Xe-HPG")
+    );
+    assert_eq!(
+        architecture(vendor::INTEL, "TigerLake-LP GT2 [Iris Xe Graphics]"),
+        Some("Xe-LP")
+    );
+    assert_eq!(architecture(vendor::INTEL, "HD Graphics 630"), None);
+    assert_eq!(architecture(vendor::MICROSOFT, "GA104"), None);
+}
+
+#[cfg(all(test, feature = "pci-ids"))]
+#[test]
+fn builtin_table() {
+    let identity = identify(PciId {
+        vendor_id: vendor::NVIDIA,
+        device_id: 0x2484,
+        ..Default::default()
+    });
+    assert_eq!(identity.to_string(), "NVIDIA / GeForce RTX 3070 / Ampere");
+    let identity = identify(PciId {
+        vendor_id: vendor::INTEL,
+        device_id: 0x9a49,
+        ..Default::default()
+    });
+    assert_eq!(identity.to_string(), "Intel / Iris Xe Graphics / Xe-LP");
+}
@@ -0,0 +1,274 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// A device database in the format of the [PCI ID Repository]'s `pci.ids`.
+///
+/// Only the vendor list is kept; the device class list at the end of the
+/// file is skipped.
+///
+/// [PCI ID Repository]: https://pci-ids.ucw.cz/
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PciIds {
+    vendors: Vec<Vendor>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Vendor {
+    pub id: u16,
+    pub name: String,
+    pub devices: Vec<Device>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Device {
+    pub id: u16,
+    /// The name as listed, which for GPUs is usually the chip followed by
+    /// the product names in brackets, e.g. `GA104 [GeForce RTX 3070]`.
+    pub name: String,
+    pub subsystems: Vec<Subsystem>,
+}
+
+/// A board built around a device, identified by the board vendor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Subsystem {
+    pub vendor_id: u16,
+    pub device_id: u16,
+    pub name: String,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+/// An error parsing a `pci.ids` file. Line numbers start at 1.
+pub enum PciIdsError {
+    /// The line isn't an id followed by a name.
+    InvalidLine { line: usize },
+    /// A device line came before any vendor.
+    DeviceWithoutVendor { line: usize },
+    /// A subsystem line came before any device of the current vendor.
+    SubsystemWithoutDevice { line: usize },
+}
+
+impl fmt::Display for PciIdsError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PciIdsError::InvalidLine { line } => write!(fmt, "line {}: invalid entry", line),
+            PciIdsError::DeviceWithoutVendor { line } => {
+                write!(fmt, "line {}: device listed before any vendor", line)
+            }
+            PciIdsError::SubsystemWithoutDevice { line } => {
+                write!(fmt, "line {}: subsystem listed before any device", line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PciIdsError {}
+
+impl PciIds {
+    /// Parses the contents of a `pci.ids` file.
+    pub fn parse(text: &str) -> Result<PciIds, PciIdsError> {
+        let mut vendors: Vec<Vendor> = Vec::new();
+
+        for (index, raw) in text.lines().enumerate() {
+            let line = index + 1;
+            let trimmed = raw.trim_end();
+            if trimmed.trim_start().is_empty() || trimmed.trim_start().starts_with('#') {
+                continue;
+            }
+            // The device classes come after every vendor.
+            if trimmed.starts_with("C ") {
+                break;
+            }
+
+            let depth = trimmed.len() - trimmed.trim_start_matches('\t').len();
+            let entry = &trimmed[depth..];
+            match depth {
+                0 => {
+                    let (id, name) = split_id(entry).ok_or(PciIdsError::InvalidLine { line })?;
+                    vendors.push(Vendor {
+                        id,
+                        name: name.to_string(),
+                        devices: Vec::new(),
+                    });
+                }
+                1 => {
+                    let (id, name) = split_id(entry).ok_or(PciIdsError::InvalidLine { line })?;
+                    let vendor = vendors
+                        .last_mut()
+                        .ok_or(PciIdsError::DeviceWithoutVendor { line })?;
+                    vendor.devices.push(Device {
+                        id,
+                        name: name.to_string(),
+                        subsystems: Vec::new(),
+                    });
+                }
+                2 => {
+                    let (vendor_id, rest) =
+                        split_id(entry).ok_or(PciIdsError::InvalidLine { line })?;
+                    let (device_id, name) =
+                        split_id(rest).ok_or(PciIdsError::InvalidLine { line })?;
+                    let device = vendors
+                        .last_mut()
+                        .and_then(|vendor| vendor.devices.last_mut())
+                        .ok_or(PciIdsError::SubsystemWithoutDevice { line })?;
+                    device.subsystems.push(Subsystem {
+                        vendor_id,
+                        device_id,
+                        name: name.to_string(),
+                    });
+                }
+                _ => return Err(PciIdsError::InvalidLine { line }),
+            }
+        }
+
+        vendors.sort_by_key(|vendor| vendor.id);
+        for vendor in &mut vendors {
+            vendor.devices.sort_by_key(|device| device.id);
+        }
+        Ok(PciIds { vendors })
+    }
+
+    pub fn vendors(&self) -> &[Vendor] {
+        &self.vendors
+    }
+
+    pub fn vendor(&self, vendor_id: u16) -> Option<&Vendor> {
+        let index = self
+            .vendors
+            .binary_search_by_key(&vendor_id, |vendor| vendor.id)
+            .ok()?;
+        Some(&self.vendors[index])
+    }
+
+    pub fn device(&self, vendor_id: u16, device_id: u16) -> Option<&Device> {
+        self.vendor(vendor_id)?.device(device_id)
+    }
+}
+
+impl FromStr for PciIds {
+    type Err = PciIdsError;
+
+    fn from_str(text: &str) -> Result<PciIds, PciIdsError> {
+        PciIds::parse(text)
+    }
+}
+
+impl Vendor {
+    pub fn device(&self, device_id: u16) -> Option<&Device> {
+        let index = self
+            .devices
+            .binary_search_by_key(&device_id, |device| device.id)
+            .ok()?;
+        Some(&self.devices[index])
+    }
+}
+
+impl Device {
+    /// The product names in brackets if there are any, otherwise the whole
+    /// name. `GA104 [GeForce RTX 3070]` => `GeForce RTX 3070`.
+    pub fn product_name(&self) -> &str {
+        self.brackets().map_or(&self.name, |(_, product)| product)
+    }
+
+    /// The chip name before the brackets, if the name has brackets.
+    /// `GA104 [GeForce RTX 3070]` => `GA104`.
+    pub fn chip_name(&self) -> Option<&str> {
+        self.brackets().map(|(chip, _)| chip)
+    }
+
+    /// The board with the given subsystem vendor and device ids.
+    pub fn subsystem(&self, vendor_id: u16, device_id: u16) -> Option<&Subsystem> {
+        self.subsystems
+            .iter()
+            .find(|s| s.vendor_id == vendor_id && s.device_id == device_id)
+    }
+
+    fn brackets(&self) -> Option<(&str, &str)> {
+        let open = self.name.find('[')?;
+        let close = self.name.rfind(']')?;
+        if close < open {
+            return None;
+        }
+        Some((self.name[..open].trim_end(), &self.name[open + 1..close]))
+    }
+}
+
+/// Splits a 4 digit hex id from the name following it.
+fn split_id(entry: &str) -> Option<(u16, &str)> {
+    let id = entry.get(..4)?;
+    let rest = &entry[4..];
+    if !id.bytes().all(|b| b.is_ascii_hexdigit()) || !rest.starts_with(' ') {
+        return None;
+    }
+    let id = u16::from_str_radix(id, 16).ok()?;
+    Some((id, rest.trim_start()))
+}
+
+#[cfg(test)]
+#[test]
+fn parse_entries() {
+    let text = "\
+# comment
+10de  NVIDIA Corporation
+\t2484  GA104 [GeForce RTX 3070]
+\t\t1458 404c  GeForce RTX 3070 GAMING OC
+\t\t10de 146b  GeForce RTX 3070 Founders Edition
+\t1b80  GP104 [GeForce GTX 1080]
+
+1002  Advanced Micro Devices, Inc. [AMD/ATI]
+\t67df  Ellesmere [Radeon RX 470/480/570/570X/580/580X/590]
+\t0000  Unbracketed
+C 03  Display controller
+\t00  VGA compatible controller
+";
+    let ids: PciIds = text.parse().unwrap();
+    assert_eq!(
+        ids.vendors().iter().map(|v| v.id).collect::<Vec<_>>(),
+        vec![0x1002, 0x10de]
+    );
+    assert_eq!(ids.vendor(0x10de).unwrap().name, "NVIDIA Corporation");
+    assert!(ids.vendor(0x8086).is_none());
+
+    let device = ids.device(0x10de, 0x2484).unwrap();
+    assert_eq!(device.product_name(), "GeForce RTX 3070");
+    assert_eq!(device.chip_name(), Some("GA104"));
+    assert_eq!(
+        device.subsystem(0x1458, 0x404c).unwrap().name,
+        "GeForce RTX 3070 GAMING OC"
+    );
+    assert!(device.subsystem(0x1458, 0x0000).is_none());
+
+    let device = ids.device(0x1002, 0x0000).unwrap();
+    assert_eq!(device.product_name(), "Unbracketed");
+    assert_eq!(device.chip_name(), None);
+    assert!(ids.device(0x1002, 0x1234).is_none());
+}
+
+#[cfg(test)]
+#[test]
+fn parse_errors() {
+    assert_eq!(PciIds::parse(""), Ok(PciIds::default()));
+    assert_eq!(
+        PciIds::parse("\t2484  GA104"),
+        Err(PciIdsError::DeviceWithoutVendor { line: 1 })
+    );
+    assert_eq!(
+        PciIds::parse("10de  NVIDIA\n\t\t1458 404c  Board"),
+        Err(PciIdsError::SubsystemWithoutDevice { line: 2 })
+    );
+    assert_eq!(
+        PciIds::parse("# header\n10dx  NVIDIA"),
+        Err(PciIdsError::InvalidLine { line: 2 })
+    );
+    assert_eq!(
+        PciIds::parse("10de  NVIDIA\n\t2484GA104"),
+        Err(PciIdsError::InvalidLine { line: 2 })
+    );
+    assert_eq!(
+        PciIds::parse("10de  NVIDIA\n\t2484  GA104\n\t\t\tdeep"),
+        Err(PciIdsError::InvalidLine { line: 3 })
+    );
+    assert_eq!(
+        PciIdsError::InvalidLine { line: 4 }.to_string(),
+        "line 4: invalid entry"
+    );
+}
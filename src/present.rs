@@ -0,0 +1,253 @@
+//! Interpreting the result of `Present`.
+//!
+//! `Present` and `Present1` report occlusion and display mode changes through
+//! success codes, which [`PresentOutcome`] keeps. [`OcclusionTracker`] uses
+//! them to idle a render loop while the window can't be seen, probing with
+//! `PresentFlags::TEST` until it can. [`Placement`] works out where a
+//! presented buffer lands on its window or output.
+
+use crate::enums::PresentFlags;
+use crate::error::DxgiError;
+
+pub use self::placement::{Placement, ScalingMode};
+
+mod placement;
+
+/// The successful result of presenting a frame.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PresentOutcome {
+    /// The frame was presented, or for a `TEST` present, would have been.
+    Presented,
+    /// The window content isn't visible (`DXGI_STATUS_OCCLUDED`), e.g. it is
+    /// minimized or covered. Nothing was presented.
+    Occluded,
+    /// The frame was presented, but the display mode changed since the last
+    /// present (`DXGI_STATUS_MODE_CHANGED`).
+    ModeChanged,
+    /// A display mode change is in progress
+    /// (`DXGI_STATUS_MODE_CHANGE_IN_PROGRESS`), and the frame wasn't
+    /// presented.
+    ModeChangeInProgress,
+    /// Any other success code.
+    Status(DxgiError),
+}
+
+impl PresentOutcome {
+    /// Interprets the `HRESULT` returned by `Present`, or returns the error
+    /// if it is a failure code.
+    pub fn from_hresult(hr: i32) -> Result<PresentOutcome, DxgiError> {
+        if hr < 0 {
+            return Err(DxgiError::from_hresult(hr));
+        }
+
+        Ok(match DxgiError::from_hresult(hr) {
+            DxgiError::Other(0) => PresentOutcome::Presented,
+            DxgiError::Occluded => PresentOutcome::Occluded,
+            DxgiError::ModeChanged => PresentOutcome::ModeChanged,
+            DxgiError::ModeChangeInProgressStatus => PresentOutcome::ModeChangeInProgress,
+            status => PresentOutcome::Status(status),
+        })
+    }
+
+    /// The `HRESULT` this outcome was created from.
+    pub fn hresult(self) -> i32 {
+        match self {
+            PresentOutcome::Presented => 0,
+            PresentOutcome::Occluded => DxgiError::Occluded.hresult(),
+            PresentOutcome::ModeChanged => DxgiError::ModeChanged.hresult(),
+            PresentOutcome::ModeChangeInProgress => DxgiError::ModeChangeInProgressStatus.hresult(),
+            PresentOutcome::Status(status) => status.hresult(),
+        }
+    }
+}
+
+/// Whether the render loop should be drawing frames.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PresentState {
+    /// The window is visible; render and present normally.
+    Visible,
+    /// The window is occluded; skip rendering and present with
+    /// `PresentFlags::TEST` every so often until it is visible again.
+    Occluded,
+}
+
+/// Tracks occlusion from present outcomes.
+///
+/// An ordinary present that returns `Occluded` switches to the occluded
+/// state, in which [`flags`](OcclusionTracker::flags) gives
+/// `PresentFlags::TEST` so that presents only probe the swap chain. The first
+/// probe that returns `Presented` switches back. As the DXGI documentation
+/// recommends, a `TEST` present is never used to decide to go idle.
+#[derive(Copy, Clone, Debug)]
+pub struct OcclusionTracker {
+    state: PresentState,
+}
+
+impl OcclusionTracker {
+    /// A tracker that starts out visible.
+    pub fn new() -> Self {
+        OcclusionTracker {
+            state: PresentState::Visible,
+        }
+    }
+
+    /// The current state.
+    pub fn state(&self) -> PresentState {
+        self.state
+    }
+
+    /// Whether a frame should be rendered before the next present.
+    pub fn should_render(&self) -> bool {
+        self.state == PresentState::Visible
+    }
+
+    /// The flags to present with: `flags` while visible, `TEST` while
+    /// occluded.
+    pub fn flags(&self, flags: PresentFlags) -> PresentFlags {
+        match self.state {
+            PresentState::Visible => flags,
+            PresentState::Occluded => PresentFlags::TEST,
+        }
+    }
+
+    /// Records the outcome of a present made with `flags`, returning the
+    /// new state if it changed.
+    pub fn record(&mut self, flags: PresentFlags, outcome: PresentOutcome) -> Option<PresentState> {
+        let test = flags.0 & PresentFlags::TEST.0 != 0;
+        let next = match (self.state, outcome) {
+            (PresentState::Visible, PresentOutcome::Occluded) if !test => PresentState::Occluded,
+            (PresentState::Occluded, PresentOutcome::Presented)
+            | (PresentState::Occluded, PresentOutcome::ModeChanged) => PresentState::Visible,
+            (state, _) => state,
+        };
+
+        if next == self.state {
+            None
+        } else {
+            self.state = next;
+            Some(next)
+        }
+    }
+
+    /// Presents through `present` with the flags for the current state and
+    /// records the outcome. Errors are passed through without changing the
+    /// state.
+    pub fn present<E>(
+        &mut self,
+        flags: PresentFlags,
+        present: impl FnOnce(PresentFlags) -> Result<PresentOutcome, E>,
+    ) -> Result<PresentOutcome, E> {
+        let flags = self.flags(flags);
+        let outcome = present(flags)?;
+        self.record(flags, outcome);
+        Ok(outcome)
+    }
+}
+
+impl Default for OcclusionTracker {
+    fn default() -> Self {
+        OcclusionTracker::new()
+    }
+}
+
+#[cfg(test)]
+fn run_script(
+    tracker: &mut OcclusionTracker,
+    script: &[Result<PresentOutcome, DxgiError>],
+) -> Vec<(PresentFlags, PresentState)> {
+    script
+        .iter()
+        .map(|&scripted| {
+            let mut used = PresentFlags::NONE;
+            let _ = tracker.present(PresentFlags::DO_NOT_WAIT, |flags| {
+                used = flags;
+                scripted
+            });
+            (used, tracker.state())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[test]
+fn outcomes() {
+    assert_eq!(
+        PresentOutcome::from_hresult(0),
+        Ok(PresentOutcome::Presented)
+    );
+    for &outcome in &[
+        PresentOutcome::Occluded,
+        PresentOutcome::ModeChanged,
+        PresentOutcome::ModeChangeInProgress,
+        PresentOutcome::Status(DxgiError::Unoccluded),
+    ] {
+        assert_eq!(PresentOutcome::from_hresult(outcome.hresult()), Ok(outcome));
+    }
+    assert_eq!(
+        PresentOutcome::from_hresult(0x087A_0001),
+        Ok(PresentOutcome::Occluded)
+    );
+    assert_eq!(
+        PresentOutcome::from_hresult(DxgiError::DeviceRemoved.hresult()),
+        Err(DxgiError::DeviceRemoved)
+    );
+    assert_eq!(
+        PresentOutcome::from_hresult(DxgiError::ModeChangeInProgress.hresult()),
+        Err(DxgiError::ModeChangeInProgress)
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn occlusion_script() {
+    use self::PresentOutcome::*;
+    use self::PresentState::Occluded as Idle;
+    use self::PresentState::Visible;
+
+    let normal = PresentFlags::DO_NOT_WAIT;
+    let test = PresentFlags::TEST;
+    let mut tracker = OcclusionTracker::new();
+    let steps = run_script(
+        &mut tracker,
+        &[
+            Ok(Presented),
+            Ok(Occluded),
+            Ok(Occluded),
+            Err(DxgiError::WasStillDrawing),
+            Ok(ModeChangeInProgress),
+            Ok(Presented),
+            Ok(ModeChanged),
+        ],
+    );
+
+    let flags: Vec<u32> = steps.iter().map(|step| step.0 .0).collect();
+    let states: Vec<PresentState> = steps.iter().map(|step| step.1).collect();
+    assert_eq!(
+        flags,
+        vec![normal.0, normal.0, test.0, test.0, test.0, test.0, normal.0]
+    );
+    assert_eq!(
+        states,
+        vec![Visible, Idle, Idle, Idle, Idle, Visible, Visible]
+    );
+    assert!(tracker.should_render());
+}
+
+#[cfg(test)]
+#[test]
+fn test_presents_never_idle() {
+    let mut tracker = OcclusionTracker::new();
+    assert_eq!(
+        tracker.record(PresentFlags::TEST, PresentOutcome::Occluded),
+        None
+    );
+    assert_eq!(
+        tracker.record(PresentFlags::NONE, PresentOutcome::Occluded),
+        Some(PresentState::Occluded)
+    );
+    assert!(!tracker.should_render());
+    assert_eq!(
+        tracker.record(PresentFlags::TEST, PresentOutcome::Presented),
+        Some(PresentState::Visible)
+    );
+}
@@ -0,0 +1,382 @@
+use crate::enums::{ModeRotation, ModeScaling, Scaling};
+
+use math2d::{Point2i, Recti};
+
+/// How a back buffer is fit to its presentation target.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ScalingMode {
+    /// A windowed swap chain's `SwapChainDesc1::scaling`.
+    Window(Scaling),
+    /// A fullscreen display mode's `ModeDesc::scaling`. `Unspecified` is
+    /// treated as `Stretched`, which is what most drivers do.
+    Mode(ModeScaling),
+}
+
+impl From<Scaling> for ScalingMode {
+    fn from(scaling: Scaling) -> ScalingMode {
+        ScalingMode::Window(scaling)
+    }
+}
+
+impl From<ModeScaling> for ScalingMode {
+    fn from(scaling: ModeScaling) -> ScalingMode {
+        ScalingMode::Mode(scaling)
+    }
+}
+
+/// Where a back buffer ends up on its presentation target, and how to map
+/// coordinates between the two.
+///
+/// The buffer is first rotated by the swap chain's rotation (see
+/// `ModeRotation::rotate_point`), so its content is upright, then placed on
+/// the target by the scaling mode. `Scaling::None` aligns the top left
+/// corners and ignores `WS_EX_LAYOUTRTL`; `AspectRatioStretch` uses the
+/// rounding DXGI documents for it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Placement {
+    buffer: (u32, u32),
+    target: (u32, u32),
+    rotation: ModeRotation,
+    destination: Recti,
+}
+
+impl Placement {
+    /// Places a `buffer` sized back buffer with `rotation` on a `target`
+    /// sized window or output.
+    pub fn new(
+        buffer: (u32, u32),
+        target: (u32, u32),
+        scaling: impl Into<ScalingMode>,
+        rotation: ModeRotation,
+    ) -> Placement {
+        let (width, height) = rotation.rotate_size(buffer.0, buffer.1);
+        let (target_width, target_height) = target;
+
+        let destination = match scaling.into() {
+            ScalingMode::Window(Scaling::None) => Recti::new(0, 0, width as i32, height as i32),
+            ScalingMode::Mode(ModeScaling::Centered) => {
+                let left = (target_width as i64 - width as i64) / 2;
+                let top = (target_height as i64 - height as i64) / 2;
+                Recti::new(
+                    left as i32,
+                    top as i32,
+                    (left + width as i64) as i32,
+                    (top + height as i64) as i32,
+                )
+            }
+            ScalingMode::Window(Scaling::AspectRatioStretch) if width > 0 && height > 0 => {
+                aspect_ratio_stretch(width, height, target_width, target_height)
+            }
+            _ => Recti::new(0, 0, target_width as i32, target_height as i32),
+        };
+
+        Placement {
+            buffer,
+            target,
+            rotation,
+            destination,
+        }
+    }
+
+    /// The rect the whole buffer is drawn to, in target coordinates. For
+    /// unscaled modes this can extend past the target.
+    pub fn destination(&self) -> Recti {
+        self.destination
+    }
+
+    /// The part of the target the buffer covers.
+    pub fn visible(&self) -> Recti {
+        let d = self.destination;
+        let r = Recti::new(
+            d.left.max(0),
+            d.top.max(0),
+            d.right.min(self.target.0 as i32),
+            d.bottom.min(self.target.1 as i32),
+        );
+        if r.left >= r.right || r.top >= r.bottom {
+            Recti::new(0, 0, 0, 0)
+        } else {
+            r
+        }
+    }
+
+    /// The parts of the target the buffer doesn't cover, which DXGI fills
+    /// with the background color: a full width bar above and below the
+    /// buffer, then bars to its left and right. Empty bars are left out.
+    pub fn letterbox(&self) -> Vec<Recti> {
+        let (width, height) = (self.target.0 as i32, self.target.1 as i32);
+        let v = self.visible();
+        if v.left >= v.right {
+            return if width > 0 && height > 0 {
+                vec![Recti::new(0, 0, width, height)]
+            } else {
+                Vec::new()
+            };
+        }
+
+        let bars = [
+            Recti::new(0, 0, width, v.top),
+            Recti::new(0, v.bottom, width, height),
+            Recti::new(0, v.top, v.left, v.bottom),
+            Recti::new(v.right, v.top, width, v.bottom),
+        ];
+        bars.iter()
+            .cloned()
+            .filter(|r| r.left < r.right && r.top < r.bottom)
+            .collect()
+    }
+
+    /// The scale factors from upright buffer pixels to target pixels.
+    pub fn scale(&self) -> (f64, f64) {
+        let (width, height) = self.content_size();
+        let d = self.destination;
+        (
+            ratio(d.right as i64 - d.left as i64, width),
+            ratio(d.bottom as i64 - d.top as i64, height),
+        )
+    }
+
+    /// The buffer pixel shown at target pixel `point`, e.g. under the mouse.
+    /// `None` if the point is in the letterbox or off the target.
+    pub fn target_to_buffer(&self, point: Point2i) -> Option<Point2i> {
+        let v = self.visible();
+        if point.x < v.left || point.x >= v.right || point.y < v.top || point.y >= v.bottom {
+            return None;
+        }
+
+        let (width, height) = self.content_size();
+        let (sx, sy) = self.scale();
+        let d = self.destination;
+        let x = ((point.x as f64 + 0.5 - d.left as f64) / sx).floor() as i64;
+        let y = ((point.y as f64 + 0.5 - d.top as f64) / sy).floor() as i64;
+        let content = Point2i::new(
+            x.max(0).min(width as i64 - 1) as i32,
+            y.max(0).min(height as i64 - 1) as i32,
+        );
+        Some(self.rotation.rotate_point(content, width, height))
+    }
+
+    /// The target pixel that buffer pixel `point` is shown at. The result
+    /// may be off the target for unscaled modes.
+    pub fn buffer_to_target(&self, point: Point2i) -> Point2i {
+        let (width, height) = self.content_size();
+        let content = self.rotation.unrotate_point(point, width, height);
+        let (sx, sy) = self.scale();
+        let d = self.destination;
+        Point2i::new(
+            (d.left as f64 + (content.x as f64 + 0.5) * sx).floor() as i32,
+            (d.top as f64 + (content.y as f64 + 0.5) * sy).floor() as i32,
+        )
+    }
+
+    /// The size of the buffer's content once it is upright.
+    fn content_size(&self) -> (u32, u32) {
+        self.rotation.rotate_size(self.buffer.0, self.buffer.1)
+    }
+}
+
+fn aspect_ratio_stretch(width: u32, height: u32, target_width: u32, target_height: u32) -> Recti {
+    let aspect_ratio = width as f32 / height as f32;
+
+    // Prefer filling horizontally.
+    let mut scaled_width = target_width as f32;
+    let mut scaled_height = target_width as f32 / aspect_ratio;
+    if scaled_height >= target_height as f32 {
+        scaled_width = target_height as f32 * aspect_ratio;
+        scaled_height = target_height as f32;
+    }
+
+    let offset_x = (target_width as f32 - scaled_width) * 0.5;
+    let offset_y = (target_height as f32 - scaled_height) * 0.5;
+    Recti::new(
+        offset_x.max(0.0) as i32,
+        offset_y.max(0.0) as i32,
+        ((offset_x + scaled_width) as u32).min(target_width) as i32,
+        ((offset_y + scaled_height) as u32).min(target_height) as i32,
+    )
+}
+
+fn ratio(extent: i64, size: u32) -> f64 {
+    if size == 0 {
+        0.0
+    } else {
+        extent as f64 / size as f64
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn stretch_modes() {
+    for &mode in &[
+        ScalingMode::Window(Scaling::Stretch),
+        ScalingMode::Mode(ModeScaling::Stretched),
+        ScalingMode::Mode(ModeScaling::Unspecified),
+    ] {
+        let p = Placement::new((960, 540), (1920, 1080), mode, ModeRotation::Identity);
+        assert_eq!(p.destination(), Recti::new(0, 0, 1920, 1080));
+        assert_eq!(p.letterbox(), vec![]);
+        assert_eq!(p.scale(), (2.0, 2.0));
+        assert_eq!(
+            p.target_to_buffer(Point2i::new(1919, 1)),
+            Some(Point2i::new(959, 0))
+        );
+        assert_eq!(
+            p.buffer_to_target(Point2i::new(959, 0)),
+            Point2i::new(1919, 1)
+        );
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn aspect_ratio_letterbox() {
+    // 16:9 into 4:3 fills horizontally with bars above and below.
+    let p = Placement::new(
+        (1920, 1080),
+        (1024, 768),
+        Scaling::AspectRatioStretch,
+        ModeRotation::Identity,
+    );
+    assert_eq!(p.destination(), Recti::new(0, 96, 1024, 672));
+    assert_eq!(
+        p.letterbox(),
+        vec![Recti::new(0, 0, 1024, 96), Recti::new(0, 672, 1024, 768)]
+    );
+    assert_eq!(p.target_to_buffer(Point2i::new(10, 95)), None);
+    assert_eq!(
+        p.target_to_buffer(Point2i::new(0, 96)),
+        Some(Point2i::new(0, 0))
+    );
+    assert_eq!(
+        p.target_to_buffer(Point2i::new(1023, 671)),
+        Some(Point2i::new(1919, 1079))
+    );
+
+    // 4:3 into 16:9 fills vertically with bars to the sides.
+    let p = Placement::new(
+        (640, 480),
+        (1920, 1080),
+        Scaling::AspectRatioStretch,
+        ModeRotation::Identity,
+    );
+    assert_eq!(p.destination(), Recti::new(240, 0, 1680, 1080));
+    assert_eq!(
+        p.letterbox(),
+        vec![Recti::new(0, 0, 240, 1080), Recti::new(1680, 0, 1920, 1080)]
+    );
+
+    // Every buffer pixel maps to a target pixel that maps back to it when
+    // scaling up.
+    for y in 0..480 {
+        for x in (0..640).step_by(7) {
+            let point = Point2i::new(x, y);
+            let target = p.buffer_to_target(point);
+            assert_eq!(p.target_to_buffer(target), Some(point));
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn unscaled_modes() {
+    let p = Placement::new(
+        (800, 600),
+        (1024, 768),
+        Scaling::None,
+        ModeRotation::Identity,
+    );
+    assert_eq!(p.destination(), Recti::new(0, 0, 800, 600));
+    assert_eq!(
+        p.letterbox(),
+        vec![Recti::new(0, 600, 1024, 768), Recti::new(800, 0, 1024, 600)]
+    );
+    assert_eq!(
+        p.target_to_buffer(Point2i::new(799, 599)),
+        Some(Point2i::new(799, 599))
+    );
+    assert_eq!(p.target_to_buffer(Point2i::new(800, 0)), None);
+
+    let p = Placement::new(
+        (800, 600),
+        (1024, 768),
+        ModeScaling::Centered,
+        ModeRotation::Identity,
+    );
+    assert_eq!(p.destination(), Recti::new(112, 84, 912, 684));
+    assert_eq!(p.letterbox().len(), 4);
+    assert_eq!(
+        p.target_to_buffer(Point2i::new(112, 84)),
+        Some(Point2i::new(0, 0))
+    );
+    assert_eq!(
+        p.buffer_to_target(Point2i::new(0, 0)),
+        Point2i::new(112, 84)
+    );
+
+    // A buffer larger than the target is cropped.
+    let p = Placement::new(
+        (1024, 768),
+        (800, 600),
+        ModeScaling::Centered,
+        ModeRotation::Identity,
+    );
+    assert_eq!(p.destination(), Recti::new(-112, -84, 912, 684));
+    assert_eq!(p.visible(), Recti::new(0, 0, 800, 600));
+    assert_eq!(p.letterbox(), vec![]);
+    assert_eq!(
+        p.target_to_buffer(Point2i::new(0, 0)),
+        Some(Point2i::new(112, 84))
+    );
+
+    let empty = Placement::new((0, 0), (640, 480), Scaling::None, ModeRotation::Identity);
+    assert_eq!(empty.letterbox(), vec![Recti::new(0, 0, 640, 480)]);
+    assert_eq!(empty.target_to_buffer(Point2i::new(0, 0)), None);
+}
+
+#[cfg(test)]
+#[test]
+fn rotated_buffers() {
+    // A landscape buffer for a monitor rotated to portrait.
+    let p = Placement::new(
+        (1920, 1080),
+        (1080, 1920),
+        Scaling::Stretch,
+        ModeRotation::Rotate90,
+    );
+    assert_eq!(p.scale(), (1.0, 1.0));
+    assert_eq!(p.letterbox(), vec![]);
+    for &(target, buffer) in &[
+        ((0, 0), (1919, 0)),
+        ((1079, 0), (1919, 1079)),
+        ((0, 1919), (0, 0)),
+    ] {
+        let target = Point2i::new(target.0, target.1);
+        let buffer = Point2i::new(buffer.0, buffer.1);
+        assert_eq!(p.target_to_buffer(target), Some(buffer));
+        assert_eq!(p.buffer_to_target(buffer), target);
+    }
+
+    // Rotation applies before the aspect ratio is fit.
+    let p = Placement::new(
+        (1920, 1080),
+        (1920, 1080),
+        Scaling::AspectRatioStretch,
+        ModeRotation::Rotate270,
+    );
+    assert_eq!(p.destination(), Recti::new(656, 0, 1263, 1080));
+    assert_eq!(p.letterbox().len(), 2);
+    for &rotation in &[
+        ModeRotation::Identity,
+        ModeRotation::Rotate90,
+        ModeRotation::Rotate180,
+        ModeRotation::Rotate270,
+    ] {
+        let p = Placement::new((64, 48), (640, 640), Scaling::AspectRatioStretch, rotation);
+        for y in 0..48 {
+            for x in 0..64 {
+                let point = Point2i::new(x, y);
+                assert_eq!(p.target_to_buffer(p.buffer_to_target(point)), Some(point));
+            }
+        }
+    }
+}
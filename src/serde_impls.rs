@@ -0,0 +1,395 @@
+//! `Serialize` and `Deserialize` implementations for the `serde` feature.
+//!
+//! Enums derive theirs and are written as the variant name. Flags are
+//! written as a list of flag names, with any unknown bits appended as a hex
+//! string. Description structs derive theirs with the helpers below: UTF-16
+//! names become strings, enum fields which may hold values this crate
+//! doesn't know about fall back to the raw integer, and window and monitor
+//! handles are skipped.
+
+use crate::enums::{
+    AdapterFlags, FactoryCreationFlags, MapFlags, PresentFlags, SwapChainFlags, UsageFlags,
+    WindowAssociationFlags,
+};
+
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::fmt;
+
+struct Flag {
+    name: &'static str,
+    value: u32,
+    mask: u32,
+}
+
+macro_rules! flag {
+    ($ty:ident, $name:ident) => {
+        Flag {
+            name: stringify!($name),
+            value: $ty::$name.0,
+            mask: $ty::$name.0,
+        }
+    };
+    ($ty:ident, $name:ident in $field:ident) => {
+        Flag {
+            name: stringify!($name),
+            value: $ty::$name.0,
+            mask: $ty::$field.0,
+        }
+    };
+}
+
+macro_rules! flags_serde {
+    ($($ty:ident { $($name:ident $(in $field:ident)?,)* })*) => {$(
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let table = [$(flag!($ty, $name $(in $field)?)),*];
+                serialize_flags(self.0, &table, serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<$ty, D::Error> {
+                let table = [$(flag!($ty, $name $(in $field)?)),*];
+                deserializer.deserialize_seq(FlagsVisitor(&table)).map($ty)
+            }
+        }
+    )*};
+}
+
+flags_serde! {
+    AdapterFlags {
+        REMOTE,
+        SOFTWARE,
+    }
+    FactoryCreationFlags {
+        DEBUG,
+    }
+    MapFlags {
+        READ,
+        WRITE,
+        DISCARD,
+    }
+    PresentFlags {
+        TEST,
+        DO_NOT_SEQUENCE,
+        RESTART,
+        DO_NOT_WAIT,
+        RESTRICT_TO_OUTPUT,
+        STEREO_PREFER_RIGHT,
+        STEREO_TEMPORARY_MONO,
+        USE_DURATION,
+        ALLOW_TEARING,
+    }
+    SwapChainFlags {
+        NONPREROTATED,
+        ALLOW_MODE_SWITCH,
+        GDI_COMPATIBLE,
+        RESTRICTED_CONTENT,
+        RESTRICT_SHARED_RESOURCE_DRIVER,
+        DISPLAY_ONLY,
+        FRAME_LATENCY_WAITABLE_OBJECT,
+        FOREGROUND_LAYER,
+        FULLSCREEN_VIDEO,
+        YUV_VIDEO,
+        HW_PROTECTED,
+        ALLOW_TEARING,
+        RESTRICTED_TO_ALL_HOLOGRAPHIC_DISPLAYS,
+    }
+    UsageFlags {
+        CPU_ACCESS_DYNAMIC in CPU_ACCESS_FIELD,
+        CPU_ACCESS_READ_WRITE in CPU_ACCESS_FIELD,
+        CPU_ACCESS_SCRATCH in CPU_ACCESS_FIELD,
+        SHADER_INPUT,
+        RENDER_TARGET_OUTPUT,
+        BACK_BUFFER,
+        SHARED,
+        READ_ONLY,
+        DISCARD_ON_PRESENT,
+        UNORDERED_ACCESS,
+    }
+    WindowAssociationFlags {
+        NO_WINDOW_CHANGES,
+        NO_ALT_ENTER,
+        NO_PRINT_SCREEN,
+    }
+}
+
+fn serialize_flags<S: Serializer>(
+    bits: u32,
+    table: &[Flag],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut names = Vec::new();
+    let mut unknown = bits;
+    for flag in table {
+        if bits & flag.mask == flag.value {
+            names.push(flag.name.to_string());
+            unknown &= !flag.mask;
+        }
+    }
+    if unknown != 0 {
+        names.push(format!("{:#x}", unknown));
+    }
+    serializer.collect_seq(names)
+}
+
+struct FlagsVisitor<'a>(&'a [Flag]);
+
+impl<'de, 'a> Visitor<'de> for FlagsVisitor<'a> {
+    type Value = u32;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("a list of flag names")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<u32, A::Error> {
+        let mut bits = 0;
+        while let Some(name) = seq.next_element::<String>()? {
+            if let Some(flag) = self.0.iter().find(|flag| flag.name == name) {
+                bits = bits & !flag.mask | flag.value;
+            } else if let Some(hex) = name.strip_prefix("0x") {
+                bits |= u32::from_str_radix(hex, 16).map_err(de::Error::custom)?;
+            } else {
+                return Err(de::Error::custom(format_args!("unknown flag `{}`", name)));
+            }
+        }
+        Ok(bits)
+    }
+}
+
+pub(crate) use self::descriptions::*;
+
+mod descriptions {
+    use crate::descriptions::{DBool, Luid};
+
+    use checked_enum::{CheckedEnum, UncheckedEnum};
+    use math2d::Recti;
+    use serde::de::{self, Deserialize, Deserializer, IntoDeserializer, Visitor};
+    use serde::ser::{Serialize, Serializer};
+    use std::fmt;
+    use std::marker::PhantomData;
+
+    /// `UncheckedEnum` fields are written as the variant name, or as the
+    /// raw value when it isn't one of the known variants.
+    pub(crate) mod unchecked {
+        use super::*;
+
+        pub(crate) fn serialize<T, S>(
+            value: &UncheckedEnum<T>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error>
+        where
+            T: CheckedEnum<Storage = u32> + Serialize,
+            S: Serializer,
+        {
+            match value.as_enum() {
+                Some(value) => value.serialize(serializer),
+                None => serializer.serialize_u32(value.value),
+            }
+        }
+
+        pub(crate) fn deserialize<'de, T, D>(deserializer: D) -> Result<UncheckedEnum<T>, D::Error>
+        where
+            T: CheckedEnum<Storage = u32> + Deserialize<'de>,
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_any(UncheckedVisitor(PhantomData))
+        }
+
+        struct UncheckedVisitor<T>(PhantomData<T>);
+
+        impl<'de, T> Visitor<'de> for UncheckedVisitor<T>
+        where
+            T: CheckedEnum<Storage = u32> + Deserialize<'de>,
+        {
+            type Value = UncheckedEnum<T>;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("a variant name or an integer")
+            }
+
+            fn visit_str<E: de::Error>(self, name: &str) -> Result<UncheckedEnum<T>, E> {
+                T::deserialize(name.into_deserializer()).map(UncheckedEnum::from)
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<UncheckedEnum<T>, E> {
+                if value > u64::from(u32::MAX) {
+                    return Err(E::invalid_value(de::Unexpected::Unsigned(value), &self));
+                }
+                Ok(UncheckedEnum::new(value as u32))
+            }
+        }
+    }
+
+    /// Fixed size, null terminated UTF-16 names are written as strings.
+    pub(crate) mod utf16 {
+        use super::*;
+
+        pub(crate) fn serialize<S: Serializer, const N: usize>(
+            name: &[u16; N],
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            let len = name.iter().position(|&c| c == 0).unwrap_or(N);
+            serializer.serialize_str(&String::from_utf16_lossy(&name[..len]))
+        }
+
+        pub(crate) fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+            deserializer: D,
+        ) -> Result<[u16; N], D::Error> {
+            let name = String::deserialize(deserializer)?;
+            let mut units = [0; N];
+            for (i, unit) in name.encode_utf16().enumerate() {
+                if i == N {
+                    return Err(de::Error::invalid_length(i, &"a shorter name"));
+                }
+                units[i] = unit;
+            }
+            Ok(units)
+        }
+    }
+
+    /// Arrays too large for serde's built in implementations.
+    pub(crate) mod array {
+        use super::*;
+        use std::convert::TryFrom;
+
+        pub(crate) fn serialize<S, T, const N: usize>(
+            items: &[T; N],
+            serializer: S,
+        ) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+            T: Serialize,
+        {
+            serializer.collect_seq(items.iter())
+        }
+
+        pub(crate) fn deserialize<'de, D, T, const N: usize>(
+            deserializer: D,
+        ) -> Result<[T; N], D::Error>
+        where
+            D: Deserializer<'de>,
+            T: Deserialize<'de>,
+        {
+            let items = Vec::<T>::deserialize(deserializer)?;
+            let len = items.len();
+            <[T; N]>::try_from(items).map_err(|_| de::Error::invalid_length(len, &"the full array"))
+        }
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[serde(remote = "Recti")]
+    pub(crate) struct RectiDef {
+        left: i32,
+        top: i32,
+        right: i32,
+        bottom: i32,
+    }
+
+    impl Serialize for DBool {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bool((*self).into())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for DBool {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<DBool, D::Error> {
+            bool::deserialize(deserializer).map(DBool::from)
+        }
+    }
+
+    /// Written as a hex string, the same way `Debug` prints it.
+    impl Serialize for Luid {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(&format_args!("0x{:016X}", self.as_i64() as u64))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Luid {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Luid, D::Error> {
+            let text = String::deserialize(deserializer)?;
+            let hex = text.strip_prefix("0x").unwrap_or(&text);
+            u64::from_str_radix(hex, 16)
+                .map(|luid| Luid::from(luid as i64))
+                .map_err(de::Error::custom)
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn flag_lists() {
+    let flags = PresentFlags::TEST | PresentFlags::ALLOW_TEARING;
+    let json = serde_json::to_string(&flags).unwrap();
+    assert_eq!(json, r#"["TEST","ALLOW_TEARING"]"#);
+    let back: PresentFlags = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.0, flags.0);
+
+    let none = serde_json::to_string(&SwapChainFlags::NONE).unwrap();
+    assert_eq!(none, "[]");
+
+    let usage = UsageFlags::CPU_ACCESS_READ_WRITE | UsageFlags::BACK_BUFFER;
+    let json = serde_json::to_string(&usage).unwrap();
+    assert_eq!(json, r#"["CPU_ACCESS_READ_WRITE","BACK_BUFFER"]"#);
+    let back: UsageFlags = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.0, usage.0);
+
+    let unknown = serde_json::to_string(&AdapterFlags(0x12)).unwrap();
+    assert_eq!(unknown, r#"["SOFTWARE","0x10"]"#);
+    let back: AdapterFlags = serde_json::from_str(&unknown).unwrap();
+    assert_eq!(back.0, 0x12);
+
+    assert!(serde_json::from_str::<MapFlags>(r#"["READ","BOGUS"]"#).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn enum_names() {
+    use crate::enums::{Format, ModeRotation};
+
+    let json = serde_json::to_string(&Format::B8G8R8A8UnormSrgb).unwrap();
+    assert_eq!(json, r#""B8G8R8A8UnormSrgb""#);
+    let back: ModeRotation = serde_json::from_str(r#""Rotate90""#).unwrap();
+    assert_eq!(back, ModeRotation::Rotate90);
+}
+
+#[cfg(test)]
+#[test]
+fn descriptions() {
+    use crate::descriptions::{Luid, Mode, Ratio};
+    use crate::enums::{Format, ModeScaling};
+    use checked_enum::UncheckedEnum;
+
+    let mode = Mode {
+        width: 1920,
+        height: 1080,
+        refresh_rate: Ratio::new(60000, 1001),
+        format: Format::R10G10B10A2Unorm.into(),
+        scaling: UncheckedEnum::new(17),
+        ..Default::default()
+    };
+    let json = serde_json::to_value(mode).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "width": 1920,
+            "height": 1080,
+            "refresh_rate": { "numerator": 60000, "denominator": 1001 },
+            "format": "R10G10B10A2Unorm",
+            "scanline_ordering": "Unspecified",
+            "scaling": 17,
+        })
+    );
+
+    let back: Mode = serde_json::from_value(json).unwrap();
+    assert_eq!(back.refresh_rate, mode.refresh_rate);
+    assert_eq!(back.format.as_enum(), Some(Format::R10G10B10A2Unorm));
+    assert_eq!(back.scaling.value, 17);
+    assert_eq!(back.scaling.as_enum(), None::<ModeScaling>);
+
+    let luid = Luid::from(0x1234_5678_9abc);
+    let json = serde_json::to_string(&luid).unwrap();
+    assert_eq!(json, r#""0x0000123456789ABC""#);
+    assert_eq!(serde_json::from_str::<Luid>(&json).unwrap(), luid);
+}
@@ -41,12 +41,19 @@ pub unsafe trait ISurface {
         }
 
         let map = map.assume_init();
-        let elem_size = map.Pitch as usize / desc.width as usize;
+        let (row_size, num_rows) = match desc.format.as_enum() {
+            Some(format) if format.info().bits_per_element != 0 => {
+                let layout = format.surface_layout(desc.width, desc.height, 1);
+                (layout.row_pitch, layout.num_rows)
+            }
+            _ => (map.Pitch as usize, desc.height),
+        };
 
         Ok(SurfaceMap {
             desc,
             map,
-            elem_size,
+            row_size,
+            num_rows,
             surface: &self.raw_surface(),
         })
     }
@@ -63,21 +70,29 @@ unsafe impl ISurface for Surface {
 pub struct SurfaceMap<'a> {
     pub desc: SurfaceDesc,
     map: DXGI_MAPPED_RECT,
-    elem_size: usize,
+    row_size: usize,
+    num_rows: u32,
     surface: &'a IDXGISurface,
 }
 
 impl<'a> SurfaceMap<'a> {
+    /// The number of rows in the mapped surface. This is the height of the
+    /// surface, except for block-compressed formats where a row is a row of
+    /// blocks, and planar formats where the rows of every plane are counted.
+    pub fn num_rows(&self) -> u32 {
+        self.num_rows
+    }
+
     /// NOTE: Unsafe because we can't verify T is compatible
     /// with the underlying data.
     ///
-    /// Panics if `row` is not less than `desc.height`
+    /// Panics if `row` is not less than `num_rows()`
     pub unsafe fn row<T>(&self, row: u32) -> &[T]
     where
         T: Copy,
     {
-        assert!(row < self.desc.height);
-        let len = (self.desc.width as usize * self.elem_size) / std::mem::size_of::<T>();
+        assert!(row < self.num_rows);
+        let len = self.row_size / std::mem::size_of::<T>();
         let ptr = (self.map.pBits as *mut u8).offset(self.map.Pitch as isize * row as isize);
         std::slice::from_raw_parts(ptr as *mut _, len)
     }
@@ -85,13 +100,13 @@ impl<'a> SurfaceMap<'a> {
     /// NOTE: Unsafe because we can't verify T is compatible
     /// with the underlying data.
     ///
-    /// Panics if `row` is not less than `desc.height`
+    /// Panics if `row` is not less than `num_rows()`
     pub unsafe fn row_mut<T>(&mut self, row: u32) -> &mut [T]
     where
         T: Copy,
     {
-        assert!(row < self.desc.height);
-        let len = (self.desc.width as usize * self.elem_size) / std::mem::size_of::<T>();
+        assert!(row < self.num_rows);
+        let len = self.row_size / std::mem::size_of::<T>();
         let ptr = (self.map.pBits as *mut u8).offset(self.map.Pitch as isize * row as isize);
         std::slice::from_raw_parts_mut(ptr as *mut _, len)
     }
@@ -1,5 +1,7 @@
+use crate::descriptions::{check_swap_chain, SwapChainSettings};
+use crate::descriptions::{SwapChainError, SwapChainIssue};
 use crate::enums::Format;
-use crate::enums::SwapChainFlags;
+use crate::enums::{SwapChainFlags, SwapEffect};
 use dcommon::error::Error;
 
 use checked_enum::UncheckedEnum;
@@ -13,11 +15,17 @@ pub struct ResizeBuffers<'a> {
     pub(super) height: u32,
     pub(super) format: UncheckedEnum<Format>,
     pub(super) flags: SwapChainFlags,
+    pub(super) swap_effect: UncheckedEnum<SwapEffect>,
+    pub(super) sample_count: u32,
 }
 
 impl<'a> ResizeBuffers<'a> {
+    /// Fails with `SwapChainError::Invalid` before calling into DXGI if the
+    /// new buffers have any of the issues found by `validate`.
     #[inline]
-    pub fn finish(self) -> Result<(), Error> {
+    pub fn finish(self) -> Result<(), SwapChainError> {
+        SwapChainError::check(self.validate())?;
+
         unsafe {
             let hr = self.swap_chain.ResizeBuffers(
                 self.count,
@@ -27,10 +35,25 @@ impl<'a> ResizeBuffers<'a> {
                 self.flags.0,
             );
 
-            Error::map(hr, ())
+            Ok(Error::map(hr, ())?)
         }
     }
 
+    /// Checks the new buffer settings against the swap chain's swap effect
+    /// and sample count for combinations that DXGI is known to reject, with a
+    /// suggested fix for each. A buffer count of 0 and `Format::Unknown` keep
+    /// the current values and are not checked.
+    #[inline]
+    pub fn validate(&self) -> Vec<SwapChainIssue> {
+        check_swap_chain(&SwapChainSettings {
+            swap_effect: self.swap_effect,
+            sample_count: self.sample_count,
+            buffer_count: Some(self.count).filter(|&count| count != 0),
+            format: self.format,
+            flags: self.flags,
+        })
+    }
+
     #[inline]
     pub fn dimensions(mut self, width: u32, height: u32) -> Self {
         self.width = width;
@@ -2,6 +2,7 @@ use crate::descriptions::{FrameStatistics, Mode, SwapChainDesc};
 use crate::device_subobject::IDeviceSubObject;
 use crate::enums::*;
 use crate::output::Output;
+use crate::present::PresentOutcome;
 use crate::swap_chain::resize_buffers::ResizeBuffers;
 use crate::swap_chain::BackbufferTexture;
 use crate::swap_chain::FullscreenState;
@@ -30,10 +31,17 @@ pub unsafe trait ISwapChain: IDeviceSubObject {
         }
     }
 
-    fn present(&mut self, sync_interval: u32, flags: PresentFlags) -> Result<(), Error> {
+    /// Presents the back buffer. Success codes such as
+    /// `DXGI_STATUS_OCCLUDED` are returned as a `PresentOutcome` rather than
+    /// discarded; see `present::OcclusionTracker` for idling while occluded.
+    fn present(
+        &mut self,
+        sync_interval: u32,
+        flags: PresentFlags,
+    ) -> Result<PresentOutcome, Error> {
         unsafe {
             let hr = self.raw_sc().Present(sync_interval, flags.0);
-            Error::map(hr, ())
+            PresentOutcome::from_hresult(hr).map_err(Error::from)
         }
     }
 
@@ -91,6 +99,8 @@ pub unsafe trait ISwapChain: IDeviceSubObject {
             height: desc.buffer_desc.height,
             format: desc.buffer_desc.format,
             flags: desc.flags,
+            swap_effect: desc.swap_effect,
+            sample_count: desc.sample_desc.count,
         }
     }
 
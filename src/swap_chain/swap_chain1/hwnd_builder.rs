@@ -1,4 +1,5 @@
 use crate::descriptions::{FullscreenDesc, Ratio, SwapChainDesc1};
+use crate::descriptions::{SwapChainError, SwapChainIssue};
 use crate::device::IDevice;
 use crate::enums::*;
 use crate::factory::Factory2;
@@ -35,8 +36,13 @@ impl<'a> SwapChainHwndBuilder<'a> {
 
     #[inline]
     /// Build the swap chain with the provided parameters.
-    pub fn build(self) -> Result<SwapChain1, Error> {
+    ///
+    /// Fails with `SwapChainError::Invalid` before calling into DXGI if the
+    /// description has any of the issues found by `validate`.
+    pub fn build(self) -> Result<SwapChain1, SwapChainError> {
         assert!(!self.hwnd.is_null());
+        SwapChainError::check(self.validate())?;
+
         unsafe {
             let factory = self.factory.get_raw();
             let mut ptr = std::ptr::null_mut();
@@ -51,10 +57,17 @@ impl<'a> SwapChainHwndBuilder<'a> {
                 &mut ptr,
             );
 
-            Error::map_if(hr, || SwapChain1::from_raw(ptr))
+            Ok(Error::map_if(hr, || SwapChain1::from_raw(ptr))?)
         }
     }
 
+    #[inline]
+    /// Checks the swap chain description for combinations that DXGI is known
+    /// to reject, with a suggested fix for each.
+    pub fn validate(&self) -> Vec<SwapChainIssue> {
+        self.desc.validate_hwnd()
+    }
+
     #[inline]
     /// Required
     pub fn with_hwnd(mut self, hwnd: HWND) -> Self {
@@ -79,6 +92,10 @@ impl<'a> SwapChainHwndBuilder<'a> {
 
     #[inline]
     /// Default RGBA8 UNORM
+    ///
+    /// Flip model swap chains can't be created with an sRGB format. Pass
+    /// `format.to_linear()` here and create an sRGB view of the buffers
+    /// instead.
     pub fn with_format(mut self, format: Format) -> Self {
         self.desc.format = format.into();
         self
@@ -4,6 +4,7 @@ use crate::device_subobject::IDeviceSubObject;
 use crate::enums::*;
 use crate::factory::Factory2;
 use crate::output::Output;
+use crate::present::PresentOutcome;
 use crate::swap_chain::swap_chain::ISwapChain;
 use crate::swap_chain::CoreWindowType;
 
@@ -74,17 +75,19 @@ pub unsafe trait ISwapChain1: ISwapChain {
         imp_core_window(self)
     }
 
+    /// Presents the back buffer with dirty rectangles and scrolling. Success
+    /// codes are returned as a `PresentOutcome`, as for `present`.
     fn present1(
         &mut self,
         sync_interval: u32,
         flags: PresentFlags,
         parameters: &PresentParameters,
-    ) -> Result<(), Error> {
+    ) -> Result<PresentOutcome, Error> {
         unsafe {
             let hr = self
                 .raw_sc1()
                 .Present1(sync_interval, flags.0, &parameters.into());
-            Error::map(hr, ())
+            PresentOutcome::from_hresult(hr).map_err(Error::from)
         }
     }
 